@@ -12,7 +12,12 @@ export type Entity = number;
 
 export type Vec2 = [number, number];
 
-export type Action = { Move: Vec2 } | { Attack: Entity };
+export type WardType = "Stealth" | "Control" | "Farsight";
+
+export type Action =
+  | { Move: Vec2 }
+  | { Attack: Entity }
+  | { PlaceWard: { ward_type: WardType; point: Vec2 } };
 
 export type Observe = {
   position: Vec2;
@@ -20,7 +25,7 @@ export type Observe = {
     entity: Entity;
     position: Vec2;
     health: number;
-  };
+  } | null;
 };
 
 const tools: Tool[] = [
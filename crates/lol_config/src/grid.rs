@@ -1,4 +1,5 @@
-use std::collections::{HashMap, HashSet};
+use std::cmp::Reverse;
+use std::collections::{BinaryHeap, HashMap, HashSet};
 
 use bevy::asset::Asset;
 use bevy::math::{vec2, vec3, Vec2, Vec3};
@@ -27,6 +28,9 @@ pub struct ConfigNavigationGrid {
     pub occupied_cells: HashMap<(usize, usize), f32>,
     #[serde(skip)]
     pub exclude_cells: HashSet<(usize, usize)>,
+    /// Distance from each cell center to the closest wall or map edge, see `update_clearance`
+    #[serde(skip)]
+    pub clearance: Vec<f32>,
 }
 
 impl ConfigNavigationGrid {
@@ -112,20 +116,263 @@ impl ConfigNavigationGrid {
         self.occupied_cells.get(&pos).copied().unwrap_or(0.0)
     }
 
-    /// Check if a cell is walkable (static wall + dynamic obstacle cost check)
-    pub fn is_walkable_by_xy(&self, (x, y): (usize, usize)) -> bool {
+    /// Check if a cell is walkable (static wall + dynamic obstacle cost check) for a unit that
+    /// needs `clearance`, see `get_required_clearance`
+    pub fn is_walkable_by_xy(&self, (x, y): (usize, usize), clearance: f32) -> bool {
         if x >= self.x_len || y >= self.y_len {
             return false;
         }
         if !self.get_cell_by_xy((x, y)).is_walkable() {
             return false;
         }
+        if self.get_clearance_by_xy((x, y)) < clearance {
+            return false;
+        }
         if self.exclude_cells.contains(&(x, y)) {
             return true;
         }
         let cost = self.get_cell_cost((x, y));
         cost < CELL_COST_IMPASSABLE
     }
+
+    /// Clearance of a cell, unlimited until `update_clearance` has run
+    pub fn get_clearance_by_xy(&self, (x, y): (usize, usize)) -> f32 {
+        self.clearance
+            .get(y * self.x_len + x)
+            .copied()
+            .unwrap_or(f32::MAX)
+    }
+
+    /// Clearance the cells on the path of a unit of `radius` need. Units don't stay on cell
+    /// centers so half a cell is given back, and the result is rounded up to a quarter cell so
+    /// units of similar size share the same walkable cells.
+    pub fn get_required_clearance(&self, radius: f32) -> f32 {
+        let step = self.cell_size / 4.0;
+        ((radius - self.cell_size / 2.0).max(0.0) / step).ceil() * step
+    }
+
+    /// Precompute the distance from every cell center to the closest static wall edge or map
+    /// edge. Walls spread their position outwards like a Dijkstra search, each cell keeping the
+    /// closest wall found by its neighbours. Dynamic obstacles aren't part of it.
+    pub fn update_clearance(&mut self) {
+        let len = self.x_len * self.y_len;
+        let index = |(x, y): (usize, usize)| y * self.x_len + x;
+        let mut nearest: Vec<Option<(usize, usize)>> = vec![None; len];
+        let mut distances = vec![f32::MAX; len];
+        let mut open = BinaryHeap::new();
+
+        for y in 0..self.y_len {
+            for x in 0..self.x_len {
+                if self.cells[y][x].is_wall() {
+                    nearest[index((x, y))] = Some((x, y));
+                    distances[index((x, y))] = 0.0;
+                    open.push(Reverse((0u32, x, y)));
+                }
+            }
+        }
+
+        while let Some(Reverse((distance, x, y))) = open.pop() {
+            let cell = (x, y);
+            if f32::from_bits(distance) > distances[index(cell)] {
+                continue;
+            }
+            let Some(wall) = nearest[index(cell)] else {
+                continue;
+            };
+
+            for (dx, dy) in [
+                (-1, 0),
+                (1, 0),
+                (0, -1),
+                (0, 1),
+                (-1, -1),
+                (-1, 1),
+                (1, -1),
+                (1, 1),
+            ] {
+                let (Some(nx), Some(ny)) = (x.checked_add_signed(dx), y.checked_add_signed(dy))
+                else {
+                    continue;
+                };
+                if nx >= self.x_len || ny >= self.y_len {
+                    continue;
+                }
+
+                let distance = vec2(nx as f32 - wall.0 as f32, ny as f32 - wall.1 as f32).length();
+                if distance < distances[index((nx, ny))] {
+                    distances[index((nx, ny))] = distance;
+                    nearest[index((nx, ny))] = Some(wall);
+                    open.push(Reverse((distance.to_bits(), nx, ny)));
+                }
+            }
+        }
+
+        self.clearance = (0..len)
+            .map(|i| {
+                let (x, y) = (i % self.x_len, i / self.x_len);
+                // Distance to the closest point of the wall cell rather than to its center
+                let to_wall = nearest[i].map_or(f32::MAX, |wall| {
+                    let dx = (x.abs_diff(wall.0) as f32 - 0.5).max(0.0);
+                    let dy = (y.abs_diff(wall.1) as f32 - 0.5).max(0.0);
+                    vec2(dx, dy).length()
+                });
+                let to_edge = x.min(self.x_len - 1 - x).min(y).min(self.y_len - 1 - y) as f32 + 0.5;
+                to_wall.min(to_edge) * self.cell_size
+            })
+            .collect();
+    }
+
+    /// Convert a world position to fractional grid coordinates
+    pub fn get_float_xy_by_position(&self, position: &Vec2) -> Vec2 {
+        (position - self.min_position) / self.cell_size
+    }
+
+    /// Check if a unit that needs `clearance` can walk in a straight line between two world
+    /// positions
+    pub fn has_line_of_sight(&self, from: Vec2, to: Vec2, clearance: f32) -> bool {
+        self.raycast(from, to, clearance).is_none()
+    }
+
+    /// Cast a ray against cells that aren't walkable for `clearance`, walls and impassable
+    /// dynamic obstacles
+    pub fn raycast(&self, from: Vec2, to: Vec2, clearance: f32) -> Option<GridRayHit> {
+        self.raycast_by(from, to, |cell| !self.is_walkable_by_xy(cell, clearance))
+    }
+
+    /// Cast a ray against static walls only, dynamic obstacles are ignored
+    pub fn raycast_walls(&self, from: Vec2, to: Vec2) -> Option<GridRayHit> {
+        self.raycast_by(from, to, |cell| self.get_cell_by_xy(cell).is_wall())
+    }
+
+    /// Cast a ray along every segment of a polyline, the hit distance is measured along the
+    /// whole polyline
+    pub fn raycast_path(
+        &self,
+        path: impl IntoIterator<Item = Vec2>,
+        clearance: f32,
+    ) -> Option<GridRayHit> {
+        let mut path = path.into_iter();
+        let mut from = path.next()?;
+        let mut traveled = 0.0;
+
+        for to in path {
+            if let Some(hit) = self.raycast(from, to, clearance) {
+                return Some(GridRayHit {
+                    distance: traveled + hit.distance,
+                    ..hit
+                });
+            }
+            traveled += from.distance(to);
+            from = to;
+        }
+
+        None
+    }
+
+    /// Walk the cells crossed by the segment `from` -> `to` (world positions) and return the
+    /// first one for which `is_blocked` holds. The start cell is never tested and cells outside
+    /// the grid always block. A segment passing exactly through a cell corner touches both side
+    /// cells, so it can't squeeze between two diagonal blockers.
+    pub fn raycast_by(
+        &self,
+        from: Vec2,
+        to: Vec2,
+        mut is_blocked: impl FnMut((usize, usize)) -> bool,
+    ) -> Option<GridRayHit> {
+        const CORNER_EPSILON: f32 = 1e-6;
+
+        let start = self.get_float_xy_by_position(&from);
+        let end = self.get_float_xy_by_position(&to);
+        let direction = end - start;
+
+        let end_cell = (end.x.floor() as isize, end.y.floor() as isize);
+        let mut cell = (start.x.floor() as isize, start.y.floor() as isize);
+        if cell == end_cell {
+            return None;
+        }
+
+        let step = (direction.x.signum() as isize, direction.y.signum() as isize);
+        let axis = |start: f32, cell: isize, direction: f32| {
+            if direction.abs() < CORNER_EPSILON {
+                (f32::MAX, f32::MAX)
+            } else if direction > 0.0 {
+                (((cell + 1) as f32 - start) / direction, 1.0 / direction)
+            } else {
+                ((start - cell as f32) / -direction, -1.0 / direction)
+            }
+        };
+        let (mut t_max_x, t_delta_x) = axis(start.x, cell.0, direction.x);
+        let (mut t_max_y, t_delta_y) = axis(start.y, cell.1, direction.y);
+
+        let x_normal = vec2(-step.0 as f32, 0.0);
+        let y_normal = vec2(0.0, -step.1 as f32);
+
+        let mut blocked = |cell: (isize, isize)| {
+            if cell.0 < 0 || cell.1 < 0 {
+                return true;
+            }
+            let cell = (cell.0 as usize, cell.1 as usize);
+            cell.0 >= self.x_len || cell.1 >= self.y_len || is_blocked(cell)
+        };
+        let hit = |cell: (isize, isize), t: f32, normal: Vec2| GridRayHit {
+            cell: (cell.0.max(0) as usize, cell.1.max(0) as usize),
+            position: from.lerp(to, t),
+            normal,
+            distance: from.distance(to) * t,
+        };
+
+        let steps = (end_cell.0 - cell.0).abs() + (end_cell.1 - cell.1).abs();
+        for _ in 0..steps {
+            let t = t_max_x.min(t_max_y);
+            if (t_max_x - t_max_y).abs() < CORNER_EPSILON {
+                let side_x = (cell.0 + step.0, cell.1);
+                let side_y = (cell.0, cell.1 + step.1);
+                if blocked(side_x) {
+                    return Some(hit(side_x, t, x_normal));
+                }
+                if blocked(side_y) {
+                    return Some(hit(side_y, t, y_normal));
+                }
+                cell = (cell.0 + step.0, cell.1 + step.1);
+                t_max_x += t_delta_x;
+                t_max_y += t_delta_y;
+                if blocked(cell) {
+                    return Some(hit(cell, t, (x_normal + y_normal).normalize()));
+                }
+            } else if t_max_x < t_max_y {
+                cell.0 += step.0;
+                t_max_x += t_delta_x;
+                if blocked(cell) {
+                    return Some(hit(cell, t, x_normal));
+                }
+            } else {
+                cell.1 += step.1;
+                t_max_y += t_delta_y;
+                if blocked(cell) {
+                    return Some(hit(cell, t, y_normal));
+                }
+            }
+
+            if cell == end_cell {
+                break;
+            }
+        }
+
+        None
+    }
+}
+
+/// The first blocking cell found by a grid raycast
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct GridRayHit {
+    /// Grid coordinates of the blocking cell
+    pub cell: (usize, usize),
+    /// World position where the ray enters the blocking cell
+    pub position: Vec2,
+    /// Normal of the cell face that was hit, pointing back towards the ray origin
+    pub normal: Vec2,
+    /// Distance from the ray origin to `position`
+    pub distance: f32,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -149,4 +396,17 @@ impl ConfigNavigationGridCell {
     pub fn is_walkable(&self) -> bool {
         !self.is_wall()
     }
+
+    /// Walls block vision unless they are marked as transparent
+    pub fn blocks_vision(&self) -> bool {
+        self.is_wall()
+            && !self
+                .vision_pathing_flags
+                .contains(VisionPathingFlags::TransparentWall)
+    }
+
+    pub fn is_brush(&self) -> bool {
+        self.vision_pathing_flags
+            .contains(VisionPathingFlags::Brush)
+    }
 }
@@ -8,17 +8,24 @@ pub enum Lane {
     Bot = 0,
 }
 
-impl From<u16> for Lane {
-    fn from(value: u16) -> Self {
+impl Lane {
+    /// Lane index used by the map data, `None` for values that aren't a lane
+    pub fn from_index(value: u16) -> Option<Self> {
         match value {
-            0 => Lane::Bot,
-            1 => Lane::Mid,
-            2 => Lane::Top,
-            _ => panic!("Unknown lane value: {}", value),
+            0 => Some(Lane::Bot),
+            1 => Some(Lane::Mid),
+            2 => Some(Lane::Top),
+            _ => None,
         }
     }
 }
 
+impl From<u16> for Lane {
+    fn from(value: u16) -> Self {
+        Lane::from_index(value).unwrap_or_else(|| panic!("Unknown lane value: {}", value))
+    }
+}
+
 impl From<Option<u16>> for Lane {
     fn from(value: Option<u16>) -> Self {
         match value {
@@ -1,7 +1,9 @@
 use bevy::prelude::*;
 use serde::{Deserialize, Serialize};
 
-#[derive(Component, Reflect, Debug, Default, PartialEq, Clone, Serialize, Deserialize)]
+#[derive(
+    Component, Reflect, Debug, Default, PartialEq, Eq, Hash, Clone, Serialize, Deserialize,
+)]
 #[reflect(Component)]
 pub enum Team {
     #[default]
@@ -0,0 +1,251 @@
+//! Six lane waves walking and chasing each other on a synthetic three lane map, checks the time
+//! spent pathing per fixed tick. Run with `cargo run --release --example bench_navigation`.
+
+use std::time::Duration;
+
+use bevy::gizmos::GizmoPlugin;
+use bevy::prelude::*;
+use bevy::time::TimeUpdateStrategy;
+use league_core::{
+    JungleQuadrantFlags, MainRegionFlags, NearestLaneFlags, POIFlags, RingFlags, RiverRegionFlags,
+    UnknownSRXFlags, VisionPathingFlags,
+};
+use lol_config::{ConfigNavigationGrid, ConfigNavigationGridCell};
+use moon_lol::{
+    Bounding, CommandMovement, CommandRunStart, Movement, MovementAction, MovementWay,
+    NavigationStats, PluginMovement, PluginNavigaton, PluginRun, ResourceGrid, Run, RunTarget,
+    HPA_CLUSTER_SIZE,
+};
+
+/// Pathing time a fixed tick of the lane simulation may spend
+const TICK_BUDGET: Duration = Duration::from_millis(2);
+
+const TICKS: usize = 3000;
+const CELLS: usize = 300;
+const CELL_SIZE: f32 = 50.0;
+const LANE_WIDTH: usize = 14;
+const WAVE_SIZE: usize = 6;
+const AGGRO_RANGE: f32 = 500.0;
+
+/// Lane waypoints in cells, from the blue base to the red base
+const LANES: [&[(f32, f32)]; 3] = [
+    &[
+        (30.0, 30.0),
+        (17.0, 60.0),
+        (17.0, 283.0),
+        (240.0, 283.0),
+        (270.0, 270.0),
+    ],
+    &[(30.0, 30.0), (270.0, 270.0)],
+    &[
+        (30.0, 30.0),
+        (60.0, 17.0),
+        (283.0, 17.0),
+        (283.0, 240.0),
+        (270.0, 270.0),
+    ],
+];
+
+#[derive(Component)]
+struct LaneMinion {
+    blue: bool,
+    waypoints: Vec<Vec2>,
+}
+
+#[derive(Resource, Default)]
+struct TickTimes(Vec<Duration>);
+
+/// Clusters the hierarchy rebuilt over the whole run
+#[derive(Resource, Default)]
+struct HierarchyRebuilds(u32);
+
+fn main() {
+    let mut app = App::new();
+    app.add_plugins(MinimalPlugins)
+        .add_plugins((AssetPlugin::default(), TransformPlugin))
+        // Needed by the navigation debug view
+        .add_plugins(GizmoPlugin)
+        .init_asset::<Mesh>()
+        .init_asset::<StandardMaterial>()
+        .init_asset::<ConfigNavigationGrid>()
+        .add_plugins((PluginMovement, PluginNavigaton, PluginRun))
+        .init_resource::<TickTimes>()
+        .init_resource::<HierarchyRebuilds>()
+        .add_systems(Startup, setup)
+        .add_systems(FixedUpdate, fixed_update_lane_minion)
+        .add_systems(Last, last_record_tick);
+
+    let timestep = app.world().resource::<Time<Fixed>>().timestep();
+    app.insert_resource(TimeUpdateStrategy::ManualDuration(timestep));
+
+    for _ in 0..TICKS {
+        app.update();
+    }
+
+    let rebuilds = app.world().resource::<HierarchyRebuilds>().0;
+    let mut times = app.world_mut().remove_resource::<TickTimes>().unwrap().0;
+    let total = times.iter().sum::<Duration>();
+    let mean = total / times.len() as u32;
+    times.sort();
+    println!(
+        "{} ticks, {} waves of {}: mean {:?}, p50 {:?}, p99 {:?}, max {:?}, {} clusters rebuilt",
+        times.len(),
+        LANES.len() * 2,
+        WAVE_SIZE,
+        mean,
+        times[times.len() / 2],
+        times[times.len() * 99 / 100],
+        times[times.len() - 1],
+        rebuilds,
+    );
+
+    // Every minion has the same size, so one layer whose clusters are each built once
+    let clusters = CELLS.div_ceil(HPA_CLUSTER_SIZE).pow(2) as u32;
+    assert!(
+        rebuilds <= clusters,
+        "moving units rebuilt {} clusters out of {}",
+        rebuilds,
+        clusters
+    );
+
+    assert!(
+        mean < TICK_BUDGET,
+        "pathing took {:?} per tick, over the {:?} budget",
+        mean,
+        TICK_BUDGET
+    );
+}
+
+fn at((x, y): (f32, f32)) -> Vec2 {
+    vec2(x, y) * CELL_SIZE
+}
+
+fn is_lane(x: usize, y: usize) -> bool {
+    let inner = 10..CELLS - 10;
+    if !inner.contains(&x) || !inner.contains(&y) {
+        return false;
+    }
+    let base = |v: usize| !(50..CELLS - 50).contains(&v);
+    let edge = |v: usize| !(10 + LANE_WIDTH..CELLS - 10 - LANE_WIDTH).contains(&v);
+    (base(x) && base(y) && (x < CELLS / 2) == (y < CELLS / 2))
+        || edge(x)
+        || edge(y)
+        || x.abs_diff(y) < LANE_WIDTH / 2 + 1
+}
+
+fn cell(walkable: bool) -> ConfigNavigationGridCell {
+    ConfigNavigationGridCell {
+        heuristic: 0.0,
+        vision_pathing_flags: if walkable {
+            VisionPathingFlags::Walkable
+        } else {
+            VisionPathingFlags::Wall
+        },
+        river_region_flags: RiverRegionFlags::empty(),
+        jungle_quadrant_flags: JungleQuadrantFlags::empty(),
+        main_region_flags: MainRegionFlags::Spawn,
+        nearest_lane_flags: NearestLaneFlags::BlueSideTopLane,
+        poi_flags: POIFlags::None,
+        ring_flags: RingFlags::BlueSpawnToNexus,
+        srx_flags: UnknownSRXFlags::Walkable,
+    }
+}
+
+fn setup(mut commands: Commands, mut assets_grid: ResMut<Assets<ConfigNavigationGrid>>) {
+    let grid = ConfigNavigationGrid {
+        min_position: Vec2::ZERO,
+        cell_size: CELL_SIZE,
+        x_len: CELLS,
+        y_len: CELLS,
+        cells: (0..CELLS)
+            .map(|y| (0..CELLS).map(|x| cell(is_lane(x, y))).collect())
+            .collect(),
+        height_x_len: 2,
+        height_y_len: 2,
+        height_samples: vec![vec![0.0; 2]; 2],
+        ..default()
+    };
+    commands.insert_resource(ResourceGrid(assets_grid.add(grid)));
+
+    for lane in LANES {
+        for blue in [true, false] {
+            let mut waypoints = lane.iter().map(|&v| at(v)).collect::<Vec<_>>();
+            if !blue {
+                waypoints.reverse();
+            }
+            let back = (waypoints[0] - waypoints[1]).normalize();
+            for index in 0..WAVE_SIZE {
+                let position = waypoints[0] + back * 120.0 * index as f32;
+                commands.spawn((
+                    Transform::from_xyz(position.x, 0.0, position.y),
+                    Movement { speed: 325.0 },
+                    Bounding {
+                        radius: 48.0,
+                        height: 100.0,
+                    },
+                    LaneMinion {
+                        blue,
+                        waypoints: waypoints.clone(),
+                    },
+                ));
+            }
+        }
+    }
+}
+
+/// Walks the lane like `minion::fixed_update` and chases the closest enemy in range
+fn fixed_update_lane_minion(
+    mut commands: Commands,
+    q_minion: Query<(Entity, &Transform, &LaneMinion, Has<Run>)>,
+) {
+    let minions = q_minion
+        .iter()
+        .map(|(entity, transform, minion, _)| (entity, transform.translation.xz(), minion.blue))
+        .collect::<Vec<_>>();
+
+    for (entity, transform, minion, running) in q_minion.iter() {
+        let position = transform.translation.xz();
+        let enemy = minions
+            .iter()
+            .filter(|v| v.2 != minion.blue && v.1.distance(position) < AGGRO_RANGE)
+            .min_by(|a, b| a.1.distance(position).total_cmp(&b.1.distance(position)));
+
+        if let Some(&(enemy, ..)) = enemy {
+            if !running {
+                commands.trigger(CommandRunStart {
+                    entity,
+                    target: RunTarget::Target(enemy),
+                });
+            }
+            continue;
+        }
+
+        let closest = (0..minion.waypoints.len())
+            .min_by(|&a, &b| {
+                let a = minion.waypoints[a].distance(position);
+                a.total_cmp(&minion.waypoints[b].distance(position))
+            })
+            .unwrap();
+        let target = minion.waypoints[(closest + 1).min(minion.waypoints.len() - 1)];
+        commands.trigger(CommandMovement {
+            entity,
+            priority: 0,
+            action: MovementAction::Start {
+                way: MovementWay::Pathfind(vec3(target.x, 0.0, target.y)),
+                speed: None,
+                source: "Minion".to_string(),
+            },
+        });
+    }
+}
+
+fn last_record_tick(
+    res_stats: Res<NavigationStats>,
+    mut res_times: ResMut<TickTimes>,
+    mut res_rebuilds: ResMut<HierarchyRebuilds>,
+) {
+    res_times
+        .0
+        .push(res_stats.get_nav_path_time + res_stats.check_path_time);
+    res_rebuilds.0 += res_stats.hierarchy_rebuild_count;
+}
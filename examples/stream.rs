@@ -21,9 +21,10 @@ use bevy::window::ExitCondition;
 use bevy::winit::WinitPlugin;
 use crossbeam_channel::{Receiver, Sender};
 use image::codecs::jpeg::JpegEncoder;
+use lol_core::Team;
 use moon_lol::{
-    Action, AttackState, AttackTarget, CameraInit, CommandAction, Controller, Health,
-    PluginBarrack, PluginCore, PluginGymEnv, Vital,
+    is_visible_to, Action, AttackState, AttackTarget, CameraInit, CommandAction, Controller,
+    Health, PluginBarrack, PluginCore, PluginGymEnv, TeamVisibility, Vital,
 };
 use rocket::http::{ContentType, Method, Status};
 use rocket::serde::json::Json;
@@ -262,7 +263,8 @@ struct BevyAppState {
 struct Observe {
     time: f32,
     myself: ObserveMyself,
-    minions: ObserveMinion,
+    /// None while the target is hidden by fog of war
+    minions: Option<ObserveMinion>,
 }
 
 #[derive(Clone, Serialize, Deserialize)]
@@ -482,8 +484,8 @@ fn receive_and_encode_image_async(world: &mut World, shared_image: Arc<Mutex<Opt
 }
 
 fn get_observe(world: &mut World) -> Option<Observe> {
-    let Ok((_entity, transform, attack_state, _controller)) = world
-        .query::<(Entity, &Transform, Option<&AttackState>, &Controller)>()
+    let Ok((_entity, transform, attack_state, team, _controller)) = world
+        .query::<(Entity, &Transform, Option<&AttackState>, &Team, &Controller)>()
         .single(world)
     else {
         return None;
@@ -493,20 +495,28 @@ fn get_observe(world: &mut World) -> Option<Observe> {
         position: transform.translation.xz(),
         attack_state: attack_state.cloned(),
     };
-
-    let Ok((target_entity, target_transform, health, vital, _)) = world
-        .query::<(Entity, &Transform, &Health, &Vital, &AttackTarget)>()
+    let team = team.clone();
+
+    let Ok((target_entity, target_transform, health, vital, visibility, _)) = world
+        .query::<(
+            Entity,
+            &Transform,
+            &Health,
+            &Vital,
+            Option<&TeamVisibility>,
+            &AttackTarget,
+        )>()
         .single(world)
     else {
         return None;
     };
 
-    let minions = ObserveMinion {
+    let minions = is_visible_to(visibility, &team).then(|| ObserveMinion {
         entity: target_entity,
         position: target_transform.translation.xz(),
         health: health.value,
         vital: vital.clone(),
-    };
+    });
 
     let time = world.resource::<Time>().elapsed_secs();
 
@@ -1,17 +1,21 @@
 mod damage_reduction;
+mod epic_monster;
 mod fiora_e;
 mod fiora_passive;
 mod fiora_r;
+mod grievous_wounds;
+mod resist_reduction;
 mod riven_passive;
-mod riven_q;
 mod shield_magic;
 mod shield_white;
 
 pub use damage_reduction::*;
+pub use epic_monster::*;
 pub use fiora_e::*;
 pub use fiora_passive::*;
 pub use fiora_r::*;
+pub use grievous_wounds::*;
+pub use resist_reduction::*;
 pub use riven_passive::*;
-pub use riven_q::*;
 pub use shield_magic::*;
 pub use shield_white::*;
@@ -1,17 +1,20 @@
+use bevy::ecs::world::DeferredWorld;
 use bevy::prelude::*;
 
-use crate::{Buff, DamageType};
+use crate::{Buff, DamageContext, DamagePipeline, DamageType, PipelineAppExt};
 
 #[derive(Default)]
 pub struct PluginDamageReduction;
 
 impl Plugin for PluginDamageReduction {
-    fn build(&self, _app: &mut App) {}
+    fn build(&self, app: &mut App) {
+        app.add_pipeline_modifier(DamagePipeline::Reduction, damage_reduction);
+    }
 }
 
 /// Damage reduction buff component
 #[derive(Component, Debug, Clone)]
-#[require(Buff = Buff { name: "DamageReduction" })]
+#[require(Buff = Buff::new("DamageReduction"))]
 pub struct BuffDamageReduction {
     /// Reduction percentage (0.0 - 1.0)
     pub percentage: f32,
@@ -32,3 +35,27 @@ impl BuffDamageReduction {
         self.damage_type.map_or(true, |dt| dt == damage_type)
     }
 }
+
+fn damage_reduction(world: &mut DeferredWorld, context: &mut DamageContext) {
+    if !context.is_mitigable() {
+        return;
+    }
+
+    let mut total_reduction = 0.0;
+    for reduction in context
+        .buffs
+        .iter()
+        .filter_map(|&buff| world.get::<BuffDamageReduction>(buff))
+    {
+        if reduction.applies_to(context.damage_type) {
+            // Use multiplicative stacking formula: total reduction = 1 - (1 - r1) * (1 - r2) * ...
+            total_reduction = 1.0 - (1.0 - total_reduction) * (1.0 - reduction.percentage);
+        }
+    }
+
+    if total_reduction > 0.0 {
+        let reduction_amount = context.amount * total_reduction;
+        context.result.reduced_damage += reduction_amount;
+        context.amount -= reduction_amount;
+    }
+}
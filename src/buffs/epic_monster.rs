@@ -0,0 +1,20 @@
+use bevy::prelude::*;
+
+use crate::{Buff, BuffAppExt, BuffDefinition, BuffStacking};
+
+#[derive(Default)]
+pub struct PluginEpicMonster;
+
+impl Plugin for PluginEpicMonster {
+    fn build(&self, app: &mut App) {
+        // Dragon stacks are kept side by side with the timed baron and herald buffs
+        app.register_buff::<BuffEpicMonster>(
+            BuffDefinition::new("EpicMonster").with_stacking(BuffStacking::Independent, u32::MAX),
+        );
+    }
+}
+
+/// Team buff granted by slaying an epic monster, applied together with the `BuffDamage` it grants
+#[derive(Component, Debug, Clone, Default)]
+#[require(Buff = Buff::new("EpicMonster"))]
+pub struct BuffEpicMonster;
@@ -1,18 +1,20 @@
 use bevy::prelude::*;
 
-use crate::{Buff, Buffs, EventAttackEnd};
+use crate::{Buff, BuffAppExt, BuffDefinition, Buffs, CommandBuffRemove, EventAttackEnd};
 
 #[derive(Default)]
 pub struct PluginFioraE;
 
 impl Plugin for PluginFioraE {
     fn build(&self, app: &mut App) {
+        app.register_buff::<BuffFioraE>(BuffDefinition::new("FioraE"));
         app.add_observer(on_event_attack_end);
     }
 }
 
-#[derive(Component, Clone, Debug)]
-#[require(Buff = Buff { name: "FioraE" })]
+#[derive(Component, Clone, Debug, Reflect)]
+#[require(Buff = Buff::new("FioraE"))]
+#[reflect(Component, Default)]
 pub struct BuffFioraE {
     pub left: i32,
 }
@@ -42,7 +44,11 @@ fn on_event_attack_end(
         buff_fiora_e.left -= 1;
 
         if buff_fiora_e.left <= 0 {
-            commands.entity(buff).despawn();
+            commands.trigger(CommandBuffRemove {
+                entity,
+                name: "FioraE".to_string(),
+                stacks: None,
+            });
         }
     }
 }
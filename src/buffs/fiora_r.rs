@@ -1,34 +1,42 @@
 use bevy::ecs::relationship::Relationship;
+use bevy::ecs::world::DeferredWorld;
 use bevy::prelude::*;
 use league_utils::hash_bin;
 use lol_core::Team;
 
 use crate::core::{
-    is_in_direction, Buff, BuffOf, CommandSkinParticleDespawn, CommandSkinParticleSpawn, Direction,
-    EventDamageCreate, Health,
+    is_in_direction, Buff, BuffAppExt, BuffDefinition, BuffOf, CommandBuffRemove,
+    CommandSkinParticleDespawn, CommandSkinParticleSpawn, DamageContext, DamagePipeline, Direction,
+    PipelineAppExt,
 };
 use crate::get_particle_hash;
 
 const VITAL_R_TIMEOUT: f32 = 1.5;
 const FIORA_R_ACTIVE_DURATION: f32 = 0.5;
 const FIORA_R_DURATION: f32 = 7.;
+/// Share of the target's max health a struck vital deals
+const VITAL_R_DAMAGE_PERCENT: f32 = 0.05;
 
 #[derive(Default)]
 pub struct PluginFioraR;
 
 impl Plugin for PluginFioraR {
     fn build(&self, app: &mut App) {
+        app.register_buff::<BuffFioraR>(
+            BuffDefinition::new("FioraR").with_duration(FIORA_R_DURATION),
+        );
         app.add_systems(FixedUpdate, fixed_update);
-        app.add_observer(on_damage_create);
+        app.add_pipeline_modifier(DamagePipeline::Execute, damage_fiora_r_vital);
+        app.add_observer(on_remove_buff_fiora_r);
     }
 }
 
-#[derive(Component, Debug, Clone)]
-#[require(Buff = Buff { name: "FioraR" })]
+#[derive(Component, Debug, Clone, Reflect)]
+#[require(Buff = Buff::new("FioraR"))]
+#[reflect(Component, Default)]
 pub struct BuffFioraR {
     pub vitals: Vec<Direction>,
     pub active_timer: Timer,
-    pub remove_timer: Timer,
     pub timeout_red_triggered: bool,
 }
 
@@ -48,7 +56,6 @@ impl Default for BuffFioraR {
                 Direction::Left,
             ],
             active_timer: Timer::from_seconds(FIORA_R_ACTIVE_DURATION, TimerMode::Once),
-            remove_timer: Timer::from_seconds(FIORA_R_DURATION, TimerMode::Once),
             timeout_red_triggered: false,
         }
     }
@@ -56,10 +63,10 @@ impl Default for BuffFioraR {
 
 fn fixed_update(
     mut commands: Commands,
-    mut q_buff_fiora_r: Query<(Entity, &BuffOf, &mut BuffFioraR)>,
+    mut q_buff_fiora_r: Query<(&BuffOf, &Buff, &mut BuffFioraR)>,
     time: Res<Time<Fixed>>,
 ) {
-    for (entity, buff_of, mut buff) in q_buff_fiora_r.iter_mut() {
+    for (buff_of, data, mut buff) in q_buff_fiora_r.iter_mut() {
         let target_entity = buff_of.get();
 
         if !buff.is_active() {
@@ -80,9 +87,9 @@ fn fixed_update(
             continue;
         }
 
-        if !buff.timeout_red_triggered && buff.remove_timer.remaining_secs() <= VITAL_R_TIMEOUT {
+        let timing_out = data.remaining().is_some_and(|v| v <= VITAL_R_TIMEOUT);
+        if !buff.timeout_red_triggered && timing_out {
             for direction in buff.vitals.iter() {
-                commands.entity(target_entity);
                 commands.trigger(CommandSkinParticleDespawn {
                     entity: target_entity,
                     hash: get_particle_hash(direction, "Fiora_R_Mark_", ""),
@@ -94,107 +101,111 @@ fn fixed_update(
             }
             buff.timeout_red_triggered = true;
         }
-
-        buff.remove_timer.tick(time.delta());
-
-        if buff.remove_timer.is_finished() {
-            // Clean up all remaining particles
-            for direction in buff.vitals.iter() {
-                commands.entity(target_entity);
-                commands.trigger(CommandSkinParticleDespawn {
-                    entity: target_entity,
-                    hash: get_particle_hash(direction, "Fiora_R_Mark_", ""),
-                });
-                commands.trigger(CommandSkinParticleDespawn {
-                    entity: target_entity,
-                    hash: get_particle_hash(direction, "Fiora_R_Mark_", "_FioraOnly"),
-                });
-                commands.trigger(CommandSkinParticleDespawn {
-                    entity: target_entity,
-                    hash: get_particle_hash(direction, "Fiora_R_", "_Timeout"),
-                });
-            }
-            commands.entity(entity).despawn();
-        }
     }
 }
 
-/// Listen for damage events and create damage numbers
-fn on_damage_create(
-    trigger: On<EventDamageCreate>,
+/// Clean up the particles of the vitals left when the buff expires or is removed
+fn on_remove_buff_fiora_r(
+    trigger: On<Remove, BuffFioraR>,
     mut commands: Commands,
-    q_target_with_vital: Query<(&GlobalTransform, &Team, &Health)>,
-    q_transform: Query<(&GlobalTransform, &Team)>,
-    mut q_buff_fiora_r: Query<(Entity, &BuffOf, &mut BuffFioraR)>,
+    q_buff_fiora_r: Query<(&BuffOf, &BuffFioraR)>,
 ) {
-    let target_entity = trigger.event_target();
-    let Ok((transform, team)) = q_transform.get(trigger.source) else {
+    let Ok((buff_of, buff)) = q_buff_fiora_r.get(trigger.event_target()) else {
         return;
     };
+    let target_entity = buff_of.get();
+
+    for direction in buff.vitals.iter() {
+        commands.trigger(CommandSkinParticleDespawn {
+            entity: target_entity,
+            hash: get_particle_hash(direction, "Fiora_R_Mark_", ""),
+        });
+        commands.trigger(CommandSkinParticleDespawn {
+            entity: target_entity,
+            hash: get_particle_hash(direction, "Fiora_R_Mark_", "_FioraOnly"),
+        });
+        commands.trigger(CommandSkinParticleDespawn {
+            entity: target_entity,
+            hash: get_particle_hash(direction, "Fiora_R_", "_Timeout"),
+        });
+    }
+}
 
-    let Some((buff_entity, mut buff_fiora_r)) =
-        q_buff_fiora_r
-            .iter_mut()
-            .find_map(|(entity, buff_of, buff_fiora_r)| {
-                if buff_of.get() == target_entity {
-                    Some((entity, buff_fiora_r))
-                } else {
-                    None
-                }
-            })
-    else {
+/// Striking an active vital from its side pops it and deals a share of the target's max health
+/// on top of the hit
+fn damage_fiora_r_vital(world: &mut DeferredWorld, context: &mut DamageContext) {
+    if context.hit_counted {
         return;
-    };
+    }
 
-    let Ok((target_transform, target_team, _hp)) = q_target_with_vital.get(target_entity) else {
+    let (Some(source_team), Some(target_team)) = (
+        world.get::<Team>(context.source),
+        world.get::<Team>(context.target),
+    ) else {
         return;
     };
-
-    if target_team == team {
+    if source_team == target_team {
         return;
     }
 
-    if !buff_fiora_r.is_active() {
+    let (Some(source), Some(target)) = (
+        world.get::<GlobalTransform>(context.source),
+        world.get::<GlobalTransform>(context.target),
+    ) else {
         return;
-    }
-
-    let source_position = transform.translation().xz();
-    let target_position = target_transform.translation().xz();
-
-    let mut hit_direction: Option<Direction> = None;
-    buff_fiora_r.vitals.retain(|direction| {
-        if hit_direction.is_none() && is_in_direction(source_position, target_position, direction) {
-            hit_direction = Some(direction.clone());
-            false // Remove this direction
-        } else {
-            true // Keep this direction
-        }
-    });
+    };
+    let source_position = source.translation().xz();
+    let target_position = target.translation().xz();
+
+    let Some(buff) = context
+        .buffs
+        .iter()
+        .copied()
+        .find(|&buff| world.get::<BuffFioraR>(buff).is_some_and(|v| v.is_active()))
+    else {
+        return;
+    };
+    let Some(mut buff_fiora_r) = world.get_mut::<BuffFioraR>(buff) else {
+        return;
+    };
 
-    let Some(direction) = hit_direction else {
+    let Some(index) = buff_fiora_r
+        .vitals
+        .iter()
+        .position(|direction| is_in_direction(source_position, target_position, direction))
+    else {
         return;
     };
+    let direction = buff_fiora_r.vitals.remove(index);
+    let completed = buff_fiora_r.vitals.is_empty();
+
+    context.amount += context.max_health * VITAL_R_DAMAGE_PERCENT;
 
+    let mut commands = world.commands();
     commands.trigger(CommandSkinParticleSpawn {
-        entity: target_entity,
+        entity: context.target,
         hash: hash_bin("Fiora_Passive_Hit_Tar"),
     });
     commands.trigger(CommandSkinParticleDespawn {
-        entity: target_entity,
+        entity: context.target,
         hash: get_particle_hash(&direction, "Fiora_R_Mark_", ""),
     });
     commands.trigger(CommandSkinParticleDespawn {
-        entity: target_entity,
+        entity: context.target,
         hash: get_particle_hash(&direction, "Fiora_R_Mark_", "_FioraOnly"),
     });
     commands.trigger(CommandSkinParticleDespawn {
-        entity: target_entity,
+        entity: context.target,
         hash: get_particle_hash(&direction, "Fiora_R_", "_Timeout"),
     });
 
-    if buff_fiora_r.vitals.is_empty() {
+    if completed {
         // TODO: Trigger healing aura here
         // All vitals completed, remove buff
-        commands.entity(buff_entity).despawn();
+        commands.trigger(CommandBuffRemove {
+            entity: context.target,
+            name: "FioraR".to_string(),
+            stacks: None,
+        });
     }
 }
@@ -0,0 +1,41 @@
+use bevy::prelude::*;
+
+use crate::{Buff, BuffAppExt, BuffCategory, BuffDefinition};
+
+/// Default grievous wounds duration in seconds
+const GRIEVOUS_WOUNDS_DURATION: f32 = 3.0;
+
+#[derive(Default)]
+pub struct PluginGrievousWounds;
+
+impl Plugin for PluginGrievousWounds {
+    fn build(&self, app: &mut App) {
+        app.register_buff::<BuffGrievousWounds>(
+            BuffDefinition::new("GrievousWounds")
+                .with_duration(GRIEVOUS_WOUNDS_DURATION)
+                .with_category(BuffCategory::Negative),
+        );
+    }
+}
+
+/// Grievous wounds debuff component - reduces incoming healing, expires with its `Buff` timer
+#[derive(Component, Debug, Clone)]
+#[require(Buff = Buff::new("GrievousWounds"))]
+pub struct BuffGrievousWounds {
+    /// Healing reduction percentage (0.0 - 1.0)
+    pub percentage: f32,
+}
+
+impl BuffGrievousWounds {
+    pub fn new(percentage: f32) -> Self {
+        Self {
+            percentage: percentage.clamp(0.0, 1.0),
+        }
+    }
+}
+
+impl Default for BuffGrievousWounds {
+    fn default() -> Self {
+        Self::new(0.4)
+    }
+}
@@ -0,0 +1,64 @@
+use bevy::ecs::world::DeferredWorld;
+use bevy::prelude::*;
+
+use crate::{Buff, DamageContext, DamagePipeline, DamageType, PipelineAppExt};
+
+#[derive(Default)]
+pub struct PluginResistReduction;
+
+impl Plugin for PluginResistReduction {
+    fn build(&self, app: &mut App) {
+        app.add_pipeline_modifier(DamagePipeline::Resist, damage_resist_reduction);
+    }
+}
+
+/// Resistance reduction debuff component - lowers the target's armor or magic resist,
+/// applied before the attacker's penetration and allowed to go below zero
+#[derive(Component, Debug, Clone)]
+#[require(Buff = Buff::new("ResistReduction"))]
+pub struct BuffResistReduction {
+    /// Flat resistance reduction
+    pub flat: f32,
+    /// Percent resistance reduction (0.0 - 1.0)
+    pub percent: f32,
+    /// Damage type whose resistance is reduced, Physical for armor and Magic for magic resist
+    pub damage_type: DamageType,
+}
+
+impl BuffResistReduction {
+    pub fn armor(flat: f32, percent: f32) -> Self {
+        Self {
+            flat,
+            percent: percent.clamp(0.0, 1.0),
+            damage_type: DamageType::Physical,
+        }
+    }
+
+    pub fn magic_resist(flat: f32, percent: f32) -> Self {
+        Self {
+            flat,
+            percent: percent.clamp(0.0, 1.0),
+            damage_type: DamageType::Magic,
+        }
+    }
+
+    /// Check if buff reduces the resistance used against the specified damage type
+    pub fn applies_to(&self, damage_type: DamageType) -> bool {
+        self.damage_type == damage_type
+    }
+}
+
+fn damage_resist_reduction(world: &mut DeferredWorld, context: &mut DamageContext) {
+    if !context.is_mitigable() {
+        return;
+    }
+
+    for &buff in context.buffs.clone().iter() {
+        let Some(reduction) = world.get::<BuffResistReduction>(buff) else {
+            continue;
+        };
+        if reduction.applies_to(context.damage_type) {
+            context.add_resist_reduction(reduction.flat, reduction.percent);
+        }
+    }
+}
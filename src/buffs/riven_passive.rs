@@ -1,35 +1,49 @@
 use bevy::prelude::*;
 
-use crate::{Buff, Buffs, CommandDamageCreate, Damage, DamageType, EventAttackEnd, Riven};
+use crate::{
+    BonusDamage, Buff, BuffAppExt, BuffDefinition, BuffStacking, Buffs, CommandDamageCreate,
+    Damage, DamageOrigin, DamageType, EventAttackEnd, Riven,
+};
 
 /// Riven passive bonus damage ratio
 const RIVEN_PASSIVE_BONUS_RATIO: f32 = 0.2;
 
+const RIVEN_PASSIVE_MAX_STACKS: u32 = 3;
+
+/// Seconds each charge lasts
+const RIVEN_PASSIVE_DURATION: f32 = 6.0;
+
 #[derive(Default)]
 pub struct PluginRivenPassive;
 
 impl Plugin for PluginRivenPassive {
     fn build(&self, app: &mut App) {
+        app.register_buff::<BuffRivenPassive>(
+            BuffDefinition::new("RivenPassive")
+                .with_stacking(BuffStacking::Independent, RIVEN_PASSIVE_MAX_STACKS)
+                .with_duration(RIVEN_PASSIVE_DURATION),
+        );
         app.add_observer(on_damage_create_trigger_bonus);
     }
 }
 
-#[derive(Component, Clone, Debug, Default)]
-#[require(Buff = Buff { name: "RivenPassive" })]
+#[derive(Component, Clone, Debug, Default, Reflect)]
+#[require(Buff = Buff::new("RivenPassive"))]
+#[reflect(Component)]
 pub struct BuffRivenPassive;
 
 /// When Riven deals damage, if she has passive stacks, trigger bonus damage and consume one stack
 fn on_damage_create_trigger_bonus(
     trigger: On<EventAttackEnd>,
     mut commands: Commands,
-    q_riven: Query<&Damage, With<Riven>>,
+    q_riven: Query<(&Damage, &BonusDamage), With<Riven>>,
     q_buffs: Query<&Buffs>,
     q_buff_riven_passive: Query<&BuffRivenPassive>,
 ) {
     let source = trigger.entity;
 
     // Only process damage dealt by Riven
-    let Ok(damage) = q_riven.get(source) else {
+    let Ok((damage, bonus)) = q_riven.get(source) else {
         return;
     };
 
@@ -43,18 +57,22 @@ fn on_damage_create_trigger_bonus(
             continue;
         }
 
-        let bonus_damage = damage.0 * RIVEN_PASSIVE_BONUS_RATIO;
+        let bonus_damage = damage.total(bonus) * RIVEN_PASSIVE_BONUS_RATIO;
 
         // Trigger bonus damage
         commands.trigger(CommandDamageCreate {
             entity: trigger.target,
             source,
             damage_type: DamageType::Physical,
+            origin: DamageOrigin::Spell,
             amount: bonus_damage,
         });
 
         commands.entity(buff).despawn();
-        info!("{:?} Riven passive triggered, bonus damage: {:.1}", source, bonus_damage);
+        info!(
+            "{:?} Riven passive triggered, bonus damage: {:.1}",
+            source, bonus_damage
+        );
 
         return;
     }
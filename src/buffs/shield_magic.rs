@@ -1,19 +1,27 @@
+use bevy::ecs::world::DeferredWorld;
 use bevy::prelude::*;
 
-use crate::Buff;
+use crate::{
+    Buff, BuffAppExt, BuffCategory, BuffDefinition, DamageContext, DamagePipeline, DamageType,
+    PipelineAppExt,
+};
 
 #[derive(Default)]
 pub struct PluginShieldMagic;
 
 impl Plugin for PluginShieldMagic {
     fn build(&self, app: &mut App) {
+        app.register_buff::<BuffShieldMagic>(
+            BuffDefinition::new("ShieldMagic").with_category(BuffCategory::Positive),
+        );
+        app.add_pipeline_modifier(DamagePipeline::Shield, damage_shield_magic);
         app.add_systems(Update, update_shield_magic);
     }
 }
 
 /// Magic shield component - can only block magic damage
 #[derive(Component, Debug, Default, Clone)]
-#[require(Buff = Buff { name: "ShieldMagic" })]
+#[require(Buff = Buff::new("ShieldMagic"))]
 pub struct BuffShieldMagic {
     /// Current shield value
     pub current: f32,
@@ -42,6 +50,24 @@ impl BuffShieldMagic {
     }
 }
 
+fn damage_shield_magic(world: &mut DeferredWorld, context: &mut DamageContext) {
+    if context.damage_type != DamageType::Magic || context.hit_counted || context.amount <= 0.0 {
+        return;
+    }
+
+    for buff in context.buffs.clone() {
+        let Some(mut shield) = world.get_mut::<BuffShieldMagic>(buff) else {
+            continue;
+        };
+        let before = context.amount;
+        context.amount = shield.absorb_magic_damage(before);
+        context.result.magic_shield_absorbed += before - context.amount;
+        if context.amount <= 0.0 {
+            break;
+        }
+    }
+}
+
 fn update_shield_magic(mut commands: Commands, q_shield: Query<(Entity, &BuffShieldMagic)>) {
     for (entity, shield) in q_shield.iter() {
         if shield.is_depleted() {
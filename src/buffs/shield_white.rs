@@ -1,19 +1,27 @@
+use bevy::ecs::world::DeferredWorld;
 use bevy::prelude::*;
 
-use crate::Buff;
+use crate::{
+    Buff, BuffAppExt, BuffCategory, BuffDefinition, DamageContext, DamagePipeline, PipelineAppExt,
+};
 
 #[derive(Default)]
 pub struct PluginShieldWhite;
 
 impl Plugin for PluginShieldWhite {
     fn build(&self, app: &mut App) {
+        app.register_buff::<BuffShieldWhite>(
+            BuffDefinition::new("ShieldWhite").with_category(BuffCategory::Positive),
+        );
+        app.add_pipeline_modifier(DamagePipeline::Shield, damage_shield_white);
         app.add_systems(Update, update_shield_white);
     }
 }
 
 /// White shield component - can block all types of damage
-#[derive(Component, Debug, Default, Clone)]
-#[require(Buff = Buff { name: "ShieldWhite" })]
+#[derive(Component, Debug, Default, Clone, Reflect)]
+#[require(Buff = Buff::new("ShieldWhite"))]
+#[reflect(Component)]
 pub struct BuffShieldWhite {
     /// Current shield value
     pub current: f32,
@@ -42,6 +50,25 @@ impl BuffShieldWhite {
     }
 }
 
+/// Absorbs any damage type
+fn damage_shield_white(world: &mut DeferredWorld, context: &mut DamageContext) {
+    if !context.is_mitigable() || context.amount <= 0.0 {
+        return;
+    }
+
+    for buff in context.buffs.clone() {
+        let Some(mut shield) = world.get_mut::<BuffShieldWhite>(buff) else {
+            continue;
+        };
+        let before = context.amount;
+        context.amount = shield.absorb_damage(before);
+        context.result.white_shield_absorbed += before - context.amount;
+        if context.amount <= 0.0 {
+            break;
+        }
+    }
+}
+
 fn update_shield_white(mut commands: Commands, q_shield: Query<(Entity, &BuffShieldWhite)>) {
     for (entity, shield) in q_shield.iter() {
         if shield.is_depleted() {
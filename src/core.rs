@@ -3,15 +3,19 @@ mod aggro;
 mod animation;
 mod attack;
 mod attack_auto;
+mod avoidance;
 mod base;
 mod camera;
 mod character;
+mod combat_log;
 mod config;
 mod controller;
 mod cooldown;
 mod damage;
+mod death;
 mod effect;
 mod game;
+mod heal;
 mod life;
 mod lifetime;
 mod map;
@@ -19,27 +23,37 @@ mod missile;
 mod movement;
 mod navigation;
 mod particle;
+mod regen;
 mod resource;
 mod rotate;
 mod run;
+mod script;
 mod skill;
+mod skill_cast;
+mod skill_effect;
+mod skill_recast;
 mod skin;
 mod utils;
+mod vision;
 
 pub use action::*;
 pub use aggro::*;
 pub use animation::*;
 pub use attack::*;
 pub use attack_auto::*;
+pub use avoidance::*;
 pub use base::*;
 pub use camera::*;
 pub use character::*;
+pub use combat_log::*;
 pub use config::*;
 pub use controller::*;
 pub use cooldown::*;
 pub use damage::*;
+pub use death::*;
 pub use effect::*;
 pub use game::*;
+pub use heal::*;
 pub use life::*;
 pub use lifetime::*;
 pub use map::*;
@@ -47,9 +61,15 @@ pub use missile::*;
 pub use movement::*;
 pub use navigation::*;
 pub use particle::*;
+pub use regen::*;
 pub use resource::*;
 pub use rotate::*;
 pub use run::*;
+pub use script::*;
 pub use skill::*;
+pub use skill_cast::*;
+pub use skill_effect::*;
+pub use skill_recast::*;
 pub use skin::*;
 pub use utils::*;
+pub use vision::*;
@@ -5,6 +5,7 @@ mod command;
 mod damage;
 mod dash;
 mod particle;
+mod skillshot;
 
 pub use animation::*;
 pub use attack_reset::*;
@@ -15,10 +16,13 @@ pub use damage::*;
 pub use dash::*;
 pub use particle::*;
 use serde::{Deserialize, Serialize};
+pub use skillshot::*;
 
 use crate::{
     CommandAttackAutoStart, CommandAttackAutoStop, CommandMovement, CommandRunStart,
-    CommandSkillBeforeStart, CommandSkillLevelUp, CommandSkillStart, MovementAction, RunTarget,
+    CommandSkillBeforeStart, CommandSkillInterrupt, CommandSkillLevelUp, CommandSkillStart,
+    CommandWardPlace, Dead, EventSkillCastEnd, EventSkillInterrupted, MovementAction, RunTarget,
+    SkillCast, SkillInterruptReason, WardType,
 };
 
 #[derive(Default)]
@@ -36,8 +40,13 @@ impl Plugin for PluginAction {
         app.add_observer(on_action_command);
         app.add_observer(on_behave_attack_damage);
         app.add_observer(on_action_damage);
+        app.add_observer(on_action_skillshot);
+        app.add_observer(on_skillshot_end);
+        app.add_observer(on_skillshot_hit);
 
         app.add_observer(on_command_action);
+        app.add_observer(on_event_skill_cast_end);
+        app.add_observer(on_event_skill_interrupted);
 
         app.add_systems(FixedUpdate, update_dash_damage);
     }
@@ -56,16 +65,47 @@ pub enum Action {
     Stop,
     Skill { index: usize, point: Vec2 },
     SkillLevelUp(usize),
+    PlaceWard { ward_type: WardType, point: Vec2 },
 }
 
-fn on_command_action(trigger: On<CommandAction>, mut commands: Commands) {
+/// Skill requested while another one was being cast, issued once the cast ends
+#[derive(Component, Debug, Clone)]
+pub struct BufferedSkill {
+    pub index: usize,
+    pub point: Vec2,
+}
+
+fn on_command_action(
+    trigger: On<CommandAction>,
+    mut commands: Commands,
+    q_dead: Query<(), With<Dead>>,
+    q_cast: Query<(), With<SkillCast>>,
+) {
     let entity = trigger.event_target();
 
+    if q_dead.contains(entity) {
+        return;
+    }
+
+    let casting = q_cast.contains(entity);
+
     match trigger.action {
         Action::Attack(target) => {
+            if casting {
+                commands.trigger(CommandSkillInterrupt {
+                    entity,
+                    reason: SkillInterruptReason::Attack,
+                });
+            }
             commands.trigger(CommandAttackAutoStart { entity, target });
         }
         Action::Move(target) => {
+            if casting {
+                commands.trigger(CommandSkillInterrupt {
+                    entity,
+                    reason: SkillInterruptReason::Move,
+                });
+            }
             commands.trigger(CommandAttackAutoStop { entity });
             commands.trigger(CommandRunStart {
                 entity,
@@ -78,6 +118,16 @@ fn on_command_action(trigger: On<CommandAction>, mut commands: Commands) {
             // });
         }
         Action::Skill { index, point } => {
+            if casting {
+                debug!(
+                    "{} buffering skill {} until the current cast ends",
+                    entity, index
+                );
+                commands
+                    .entity(entity)
+                    .insert(BufferedSkill { index, point });
+                return;
+            }
             commands.trigger(CommandSkillBeforeStart {
                 entity,
                 index,
@@ -92,6 +142,13 @@ fn on_command_action(trigger: On<CommandAction>, mut commands: Commands) {
         Action::SkillLevelUp(index) => {
             commands.trigger(CommandSkillLevelUp { entity, index });
         }
+        Action::PlaceWard { ward_type, point } => {
+            commands.trigger(CommandWardPlace {
+                entity,
+                ward_type,
+                position: point,
+            });
+        }
         Action::Stop => {
             commands.trigger(CommandAttackAutoStop { entity });
             commands.trigger(CommandMovement {
@@ -102,3 +159,30 @@ fn on_command_action(trigger: On<CommandAction>, mut commands: Commands) {
         }
     }
 }
+
+fn on_event_skill_cast_end(
+    trigger: On<EventSkillCastEnd>,
+    mut commands: Commands,
+    q_buffered: Query<&BufferedSkill>,
+) {
+    let entity = trigger.event_target();
+
+    let Ok(buffered) = q_buffered.get(entity) else {
+        return;
+    };
+
+    commands.entity(entity).remove::<BufferedSkill>();
+    commands.trigger(CommandAction {
+        entity,
+        action: Action::Skill {
+            index: buffered.index,
+            point: buffered.point,
+        },
+    });
+}
+
+fn on_event_skill_interrupted(trigger: On<EventSkillInterrupted>, mut commands: Commands) {
+    commands
+        .entity(trigger.event_target())
+        .try_remove::<BufferedSkill>();
+}
@@ -1,33 +1,49 @@
+use std::any::TypeId;
 use std::sync::Arc;
 
 use bevy::prelude::*;
 use bevy_behave::prelude::BehaveTrigger;
 
-use crate::BuffOf;
-
-type BundleSpawner = Arc<dyn Fn(&mut EntityCommands) + Send + Sync + 'static>;
+use crate::{BuffInsert, BuffRegistry, CommandBuffApply};
 
+/// Apply a buff to the caster, named by the registered buff among its components
 #[derive(Clone)]
 pub struct ActionBuffSpawn {
-    pub bundle: BundleSpawner,
+    pub types: Vec<TypeId>,
+    pub insert: BuffInsert,
 }
 
 impl ActionBuffSpawn {
-    pub fn new(bundle: impl Bundle + Clone) -> Self {
+    pub fn new<T: Component + Clone>(component: T) -> Self {
         Self {
-            bundle: Arc::new(move |commands: &mut EntityCommands| {
-                commands.with_related::<BuffOf>(bundle.clone());
+            types: vec![TypeId::of::<T>()],
+            insert: Arc::new(move |commands: &mut EntityCommands| {
+                commands.insert(component.clone());
             }),
         }
     }
 }
 
-pub fn on_action_buff_spawn(trigger: On<BehaveTrigger<ActionBuffSpawn>>, mut commands: Commands) {
+pub fn on_action_buff_spawn(
+    trigger: On<BehaveTrigger<ActionBuffSpawn>>,
+    mut commands: Commands,
+    res_buff_registry: Res<BuffRegistry>,
+) {
     let ctx = trigger.ctx();
     let entity = ctx.target_entity();
     let event = trigger.inner();
 
-    (event.bundle)(&mut commands.entity(entity));
+    let Some(name) = res_buff_registry.name_of(&event.types) else {
+        warn!("{} spawning a buff with no registered component", entity);
+        commands.trigger(ctx.failure());
+        return;
+    };
+
+    commands.trigger(
+        CommandBuffApply::new(entity, name)
+            .with_source(entity)
+            .with_insert(event.insert.clone()),
+    );
 
     commands.trigger(ctx.success());
 }
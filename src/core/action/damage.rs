@@ -3,13 +3,14 @@ use bevy_behave::prelude::BehaveTrigger;
 use league_core::SpellObject;
 use lol_config::{HashKey, LoadHashKeyTrait};
 use lol_core::Team;
+use serde::{Deserialize, Serialize};
 
 use crate::{
-    get_skill_value, Champion, CommandDamageCreate, CommandSkinParticleSpawn, Damage, DamageType,
-    Minion, Skill, Skills,
+    get_skill_value, BonusDamage, Champion, CommandDamageCreate, CommandSkinParticleSpawn, Damage,
+    DamageOrigin, DamageType, Minion, Skill, Skills,
 };
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub enum DamageShape {
     Circle {
         radius: f32,
@@ -33,7 +34,7 @@ impl Default for DamageShape {
     }
 }
 
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub enum TargetFilter {
     All,
     Champion,
@@ -89,7 +90,7 @@ pub fn on_action_damage(
     q_team: Query<&Team>,
     q_skills: Query<&Skills>,
     q_skill: Query<&Skill>,
-    q_damage: Query<&Damage>,
+    q_damage: Query<(&Damage, &BonusDamage)>,
 ) {
     let entity = event.event_target();
 
@@ -191,8 +192,8 @@ pub fn on_action_damage(
                 let damage_amount =
                     get_skill_value(&skill_object, damage.amount, skill.level, |stat| {
                         if stat == 2 {
-                            if let Ok(damage) = q_damage.get(entity) {
-                                return damage.0;
+                            if let Ok((damage, bonus)) = q_damage.get(entity) {
+                                return damage.total(bonus);
                             }
                         }
                         0.0
@@ -204,6 +205,7 @@ pub fn on_action_damage(
                         entity: target_entity,
                         source: entity,
                         damage_type: damage.damage_type,
+                        origin: DamageOrigin::Spell,
                         amount: damage_amount,
                     });
                 }
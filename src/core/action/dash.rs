@@ -5,11 +5,12 @@ use bevy_behave::prelude::{BehaveCtx, BehaveTrigger};
 use league_core::SpellObject;
 use lol_config::{ConfigNavigationGrid, HashKey, LoadHashKeyTrait};
 use lol_core::Team;
+use serde::{Deserialize, Serialize};
 
 use crate::{
-    get_skill_value, Champion, CommandDamageCreate, CommandMovement, Damage, EventMovementEnd,
-    Minion, MovementAction, MovementWay, ResourceGrid, Skill, SkillEffectContext, Skills,
-    TargetDamage, TargetFilter,
+    get_skill_value, BonusDamage, Champion, CommandDamageCreate, CommandMovement, Damage,
+    DamageOrigin, EventMovementEnd, Minion, MovementAction, MovementWay, ResourceGrid, Skill,
+    SkillEffectContext, Skills, TargetDamage, TargetFilter,
 };
 
 #[derive(Debug, Clone)]
@@ -20,7 +21,7 @@ pub struct ActionDash {
     pub skill: HashKey<SpellObject>,
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub enum DashMoveType {
     Fixed(f32),
     Pointer { max: f32 },
@@ -81,17 +82,14 @@ pub fn on_action_dash(
         }
     };
 
-    let destination = if let Some(grid) = assets_grid.get(&res_grid.0) {
-        let grid_pos = grid.get_cell_xy_by_position(&destination);
-        if let Some(new_grid_pos) =
-            crate::core::navigation::find_nearest_walkable_cell(grid, grid_pos)
-        {
-            grid.get_cell_center_position_by_xy(new_grid_pos).xz()
-        } else {
-            destination
-        }
-    } else {
-        destination
+    // Stop half a cell in front of the first wall on the way
+    let destination = match assets_grid.get(&res_grid.0) {
+        Some(grid) => grid
+            .raycast_walls(transform.translation.xz(), destination)
+            .map_or(destination, |hit| {
+                hit.position + hit.normal * grid.cell_size / 2.0
+            }),
+        None => destination,
     };
 
     if let Some(damage) = &event.damage {
@@ -145,7 +143,7 @@ pub fn update_dash_damage(
     )>,
     q_skills: Query<&Skills>,
     q_skill: Query<&Skill>,
-    q_damage: Query<&Damage>,
+    q_damage: Query<(&Damage, &BonusDamage)>,
     res_assets_spell_object: Res<Assets<SpellObject>>,
     // TODO: Get entity radius
 ) {
@@ -203,8 +201,8 @@ pub fn update_dash_damage(
                 skill.level,
                 |stat| {
                     if stat == 2 {
-                        if let Ok(damage) = q_damage.get(entity) {
-                            return damage.0;
+                        if let Ok((damage, bonus)) = q_damage.get(entity) {
+                            return damage.total(bonus);
                         }
                     }
                     0.0
@@ -221,6 +219,7 @@ pub fn update_dash_damage(
                     entity: target,
                     source: entity,
                     damage_type: dash_damage.damage.damage.damage_type,
+                    origin: DamageOrigin::Spell,
                     amount: damage_amount,
                 });
                 // Optional: Spawn hit effect
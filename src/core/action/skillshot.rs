@@ -0,0 +1,137 @@
+use bevy::prelude::*;
+use bevy_behave::prelude::{BehaveCtx, BehaveTrigger};
+use league_core::SpellObject;
+use lol_config::{HashKey, LoadHashKeyTrait};
+
+use crate::{
+    get_skill_value, BonusDamage, Champion, CommandDamageCreate, CommandSkillshotCreate, Damage,
+    DamageOrigin, EventMissileHit, EventSkillshotEnd, Minion, Skill, SkillEffectContext, Skills,
+    TargetDamage, TargetFilter,
+};
+
+/// Fire a skillshot towards the cast point, succeeds on the first hit and fails when the
+/// missile ends without hitting anything
+#[derive(Debug, Clone)]
+pub struct ActionSkillshot {
+    pub skill: HashKey<SpellObject>,
+    pub damage: Option<TargetDamage>,
+}
+
+#[derive(Component)]
+pub struct SkillshotBehaveCtx(pub BehaveCtx);
+
+/// Damage dealt to every unit the skillshot hits
+#[derive(Component)]
+pub struct SkillshotDamage {
+    pub damage: TargetDamage,
+    pub level: usize,
+}
+
+pub fn on_action_skillshot(
+    trigger: On<BehaveTrigger<ActionSkillshot>>,
+    mut commands: Commands,
+    q_skill_effect_ctx: Query<&SkillEffectContext>,
+    q_skills: Query<&Skills>,
+    q_skill: Query<&Skill>,
+) {
+    let ctx = trigger.ctx();
+    let entity = ctx.target_entity();
+    let event = trigger.inner();
+
+    let Ok(skill_effect_ctx) = q_skill_effect_ctx.get(ctx.behave_entity()) else {
+        commands.trigger(ctx.failure());
+        return;
+    };
+
+    let level = q_skills
+        .get(entity)
+        .into_iter()
+        .flat_map(|skills| skills.iter())
+        .filter_map(|skill| q_skill.get(skill).ok())
+        .find(|skill| skill.key_spell_object == event.skill)
+        .map_or(1, |skill| skill.level);
+
+    let mut missile = commands.spawn(SkillshotBehaveCtx(*ctx));
+    if let Some(damage) = &event.damage {
+        missile.insert(SkillshotDamage {
+            damage: damage.clone(),
+            level,
+        });
+    }
+    let missile = missile.id();
+
+    commands.trigger(CommandSkillshotCreate {
+        entity: missile,
+        source: entity,
+        spell_key: event.skill,
+        level,
+        point: skill_effect_ctx.point,
+    });
+}
+
+pub fn on_skillshot_hit(
+    trigger: On<EventMissileHit>,
+    mut commands: Commands,
+    q_skillshot: Query<(Option<&SkillshotDamage>, Option<&SkillshotBehaveCtx>)>,
+    q_target: Query<(Has<Champion>, Has<Minion>)>,
+    q_damage: Query<(&Damage, &BonusDamage)>,
+    res_assets_spell_object: Res<Assets<SpellObject>>,
+) {
+    let entity = trigger.event_target();
+    let Ok((damage, ctx)) = q_skillshot.get(entity) else {
+        return;
+    };
+
+    if let Some(SkillshotBehaveCtx(ctx)) = ctx {
+        commands.trigger(ctx.success());
+        commands.entity(entity).remove::<SkillshotBehaveCtx>();
+    }
+
+    let Some(damage) = damage else {
+        return;
+    };
+    let Ok((champion, minion)) = q_target.get(trigger.target) else {
+        return;
+    };
+
+    let apply = match damage.damage.filter {
+        TargetFilter::All => true,
+        TargetFilter::Champion => champion,
+        TargetFilter::Minion => minion,
+    };
+    if !apply {
+        return;
+    }
+
+    let Some(spell_object) = res_assets_spell_object.load_hash(trigger.spell_key) else {
+        return;
+    };
+    let Some(amount) = get_skill_value(spell_object, damage.damage.amount, damage.level, |stat| {
+        if stat == 2 {
+            if let Ok((damage, bonus)) = q_damage.get(trigger.source) {
+                return damage.total(bonus);
+            }
+        }
+        0.0
+    }) else {
+        return;
+    };
+
+    commands.trigger(CommandDamageCreate {
+        entity: trigger.target,
+        source: trigger.source,
+        damage_type: damage.damage.damage_type,
+        origin: DamageOrigin::Spell,
+        amount,
+    });
+}
+
+pub fn on_skillshot_end(
+    trigger: On<EventSkillshotEnd>,
+    mut commands: Commands,
+    q_ctx: Query<&SkillshotBehaveCtx>,
+) {
+    if let Ok(SkillshotBehaveCtx(ctx)) = q_ctx.get(trigger.event_target()) {
+        commands.trigger(ctx.failure());
+    }
+}
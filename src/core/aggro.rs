@@ -3,7 +3,12 @@ use std::collections::HashMap;
 use bevy::prelude::*;
 use lol_core::Team;
 
-use crate::{DamageType, EventDamageCreate, EventDead};
+use crate::{
+    is_visible_to, Champion, Dead, EventDamageCreate, EventDead, Minion, TeamVisibility, Turret,
+};
+
+/// Seconds a call for help keeps its priority after the last hit
+pub const CALL_FOR_HELP_DURATION: f32 = 2.0;
 
 #[derive(Default)]
 pub struct PluginAggro;
@@ -20,11 +25,114 @@ impl Plugin for PluginAggro {
 #[require(AggroState)]
 pub struct Aggro {
     pub range: f32,
+    /// Allies hit within this distance call this unit for help
+    pub call_for_help_range: f32,
+}
+
+impl Aggro {
+    pub fn new(range: f32) -> Self {
+        Self {
+            range,
+            call_for_help_range: range,
+        }
+    }
+
+    pub fn with_call_for_help_range(mut self, call_for_help_range: f32) -> Self {
+        self.call_for_help_range = call_for_help_range;
+        self
+    }
+}
+
+/// Minion target priority, earlier variants win
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum AggroPriority {
+    /// Enemy champion attacking an allied champion
+    ChampionAttackingChampion,
+    /// Enemy minion attacking an allied champion
+    MinionAttackingChampion,
+    /// Enemy minion attacking an allied minion
+    MinionAttackingMinion,
+    /// Enemy turret attacking an allied minion
+    TurretAttackingMinion,
+    /// Enemy champion attacking an allied minion
+    ChampionAttackingMinion,
+    ClosestMinion,
+    ClosestChampion,
+    /// Structures and anything else in range
+    Closest,
+}
+
+/// Kind of unit as seen by the priority rules
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AggroKind {
+    Champion,
+    Minion,
+    Turret,
+    Other,
+}
+
+impl AggroKind {
+    pub fn new(is_champion: bool, is_minion: bool, is_turret: bool) -> Self {
+        if is_champion {
+            Self::Champion
+        } else if is_minion {
+            Self::Minion
+        } else if is_turret {
+            Self::Turret
+        } else {
+            Self::Other
+        }
+    }
+}
+
+impl AggroPriority {
+    /// Priority of a unit nobody called for help against
+    pub fn closest(kind: AggroKind) -> Self {
+        match kind {
+            AggroKind::Minion => Self::ClosestMinion,
+            AggroKind::Champion => Self::ClosestChampion,
+            _ => Self::Closest,
+        }
+    }
+
+    /// Priority of `attacker` after it hit an ally of kind `victim`, `None` when no help is called
+    pub fn call_for_help(attacker: AggroKind, victim: AggroKind) -> Option<Self> {
+        match (attacker, victim) {
+            (AggroKind::Champion, AggroKind::Champion) => Some(Self::ChampionAttackingChampion),
+            (AggroKind::Minion, AggroKind::Champion) => Some(Self::MinionAttackingChampion),
+            (AggroKind::Minion, AggroKind::Minion) => Some(Self::MinionAttackingMinion),
+            (AggroKind::Turret, AggroKind::Minion) => Some(Self::TurretAttackingMinion),
+            (AggroKind::Champion, AggroKind::Minion) => Some(Self::ChampionAttackingMinion),
+            _ => None,
+        }
+    }
+}
+
+/// Call for help against an attacker
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct CallForHelp {
+    pub priority: AggroPriority,
+    /// Game time at which the call is forgotten
+    pub expires_at: f32,
 }
 
 #[derive(Component, Default)]
 pub struct AggroState {
-    pub aggros: HashMap<Entity, f32>,
+    /// Active calls for help by attacker
+    pub calls: HashMap<Entity, CallForHelp>,
+    /// Current target and the priority it was picked with, kept until a better call comes in
+    pub target: Option<(Entity, AggroPriority)>,
+}
+
+impl AggroState {
+    /// Priority of `target` at game time `now`
+    pub fn priority(&self, target: Entity, kind: AggroKind, now: f32) -> AggroPriority {
+        let closest = AggroPriority::closest(kind);
+        self.calls
+            .get(&target)
+            .filter(|call| call.expires_at > now)
+            .map_or(closest, |call| call.priority.min(closest))
+    }
 }
 
 #[derive(EntityEvent, Debug)]
@@ -35,21 +143,50 @@ pub struct EventAggroTargetFound {
 
 pub fn aggro_scan(
     mut commands: Commands,
-    q_aggro: Query<(Entity, &Team, &Transform, &Aggro, &AggroState)>,
-    q_attackable: Query<(Entity, &Team, &Transform)>,
+    mut q_aggro: Query<(Entity, &Team, &Transform, &Aggro, &mut AggroState)>,
+    q_attackable: Query<
+        (
+            Entity,
+            &Team,
+            &Transform,
+            Option<&TeamVisibility>,
+            Has<Champion>,
+            Has<Minion>,
+            Has<Turret>,
+        ),
+        Without<Dead>,
+    >,
+    time: Res<Time<Fixed>>,
 ) {
-    for (entity, team, transform, aggro, aggro_state) in q_aggro.iter() {
-        let mut best_aggro = 0.0;
-        let mut closest_distance = f32::MAX;
-        let mut target_entity = Entity::PLACEHOLDER;
+    let now = time.elapsed_secs();
+
+    for (entity, team, transform, aggro, mut aggro_state) in q_aggro.iter_mut() {
+        aggro_state.calls.retain(|_, call| call.expires_at > now);
+
+        let mut best: Option<(Entity, AggroPriority, f32)> = None;
+        let mut current = None;
 
         // Iterate through all attackable units to find targets
-        for (attackable_entity, attackable_team, attackable_transform) in q_attackable.iter() {
+        for (
+            attackable_entity,
+            attackable_team,
+            attackable_transform,
+            visibility,
+            is_champion,
+            is_minion,
+            is_turret,
+        ) in q_attackable.iter()
+        {
             // Ignore friendly units
             if attackable_team == team || *attackable_team == Team::Neutral {
                 continue;
             }
 
+            // Ignore units hidden by fog of war
+            if !is_visible_to(visibility, team) {
+                continue;
+            }
+
             // Calculate distance and check if within aggro range
             let distance = transform
                 .translation
@@ -59,67 +196,95 @@ pub fn aggro_scan(
                 continue;
             }
 
-            // Get aggro value (default is 0)
-            let aggro = aggro_state
-                .aggros
-                .get(&attackable_entity)
-                .copied()
-                .unwrap_or(0.0);
-
-            // Prioritize targets with higher aggro, choose closer target when aggro is equal
-            if aggro > best_aggro || (aggro == best_aggro && distance < closest_distance) {
-                best_aggro = aggro;
-                closest_distance = distance;
-                target_entity = attackable_entity;
+            let kind = AggroKind::new(is_champion, is_minion, is_turret);
+            let priority = aggro_state.priority(attackable_entity, kind, now);
+
+            if aggro_state
+                .target
+                .is_some_and(|(target, _)| target == attackable_entity)
+            {
+                current = Some((attackable_entity, priority));
+            }
+
+            // Prioritize by the priority list, choose closer target when priority is equal
+            if best.is_none_or(|(_, best_priority, best_distance)| {
+                (priority, distance) < (best_priority, best_distance)
+            }) {
+                best = Some((attackable_entity, priority, distance));
             }
         }
 
+        // Stick to the current target unless a higher priority one showed up
+        let target = match (current, best) {
+            (Some(current), Some((_, priority, _))) if current.1 <= priority => Some(current),
+            (_, best) => best.map(|(target, priority, _)| (target, priority)),
+        };
+
+        aggro_state.target = target;
+
         // Trigger event if valid target found
-        if target_entity != Entity::PLACEHOLDER {
-            debug!("{} found aggro target {}", entity, target_entity);
-            commands.trigger(EventAggroTargetFound {
-                entity,
-                target: target_entity,
-            });
+        if let Some((target, priority)) = target {
+            debug!(
+                "{} found aggro target {} with priority {:?}",
+                entity, target, priority
+            );
+            commands.trigger(EventAggroTargetFound { entity, target });
         }
     }
 }
 
+/// Damage to a unit calls nearby allies for help against the attacker
 pub fn on_team_get_damage(
     trigger: On<EventDamageCreate>,
     mut q_aggro: Query<(&Team, &Transform, &Aggro, &mut AggroState)>,
-    q_transform: Query<&Transform>,
-    q_team: Query<&Team>,
+    q_unit: Query<(&Team, &Transform, Has<Champion>, Has<Minion>, Has<Turret>)>,
+    time: Res<Time<Fixed>>,
 ) {
     let source = trigger.source;
     let target = trigger.event_target();
 
-    if trigger.damage_type != DamageType::Physical {
+    let Ok((source_team, _, source_champion, source_minion, source_turret)) = q_unit.get(source)
+    else {
         return;
-    }
+    };
 
-    let Ok(source_transform) = q_transform.get(source) else {
+    let Ok((target_team, target_transform, target_champion, target_minion, target_turret)) =
+        q_unit.get(target)
+    else {
         return;
     };
 
-    let Ok(target_team) = q_team.get(target) else {
+    if source_team == target_team {
+        return;
+    }
+
+    let Some(priority) = AggroPriority::call_for_help(
+        AggroKind::new(source_champion, source_minion, source_turret),
+        AggroKind::new(target_champion, target_minion, target_turret),
+    ) else {
         return;
     };
 
+    let expires_at = time.elapsed_secs() + CALL_FOR_HELP_DURATION;
+
     for (team, transform, aggro, mut aggro_state) in q_aggro.iter_mut() {
         if target_team != team {
             continue;
         }
 
-        let distance = transform.translation.distance(source_transform.translation);
+        let distance = transform.translation.distance(target_transform.translation);
 
-        if distance >= aggro.range {
+        if distance >= aggro.call_for_help_range {
             continue;
         }
 
-        let aggro = aggro_state.aggros.get(&source).copied().unwrap_or(0.0);
-
-        aggro_state.aggros.insert(source, aggro + 10.0);
+        // A lower priority hit doesn't downgrade an active call
+        let call = aggro_state.calls.entry(source).or_insert(CallForHelp {
+            priority,
+            expires_at,
+        });
+        call.priority = call.priority.min(priority);
+        call.expires_at = expires_at;
     }
 }
 
@@ -127,7 +292,13 @@ fn on_target_dead(trigger: On<EventDead>, mut q_aggro: Query<&mut AggroState>) {
     let dead_entity = trigger.event_target();
 
     for mut aggro_state in q_aggro.iter_mut() {
-        aggro_state.aggros.remove(&dead_entity);
+        aggro_state.calls.remove(&dead_entity);
+        if aggro_state
+            .target
+            .is_some_and(|(target, _)| target == dead_entity)
+        {
+            aggro_state.target = None;
+        }
     }
 }
 
@@ -139,7 +310,7 @@ mod tests {
     use bevy::time::TimeUpdateStrategy;
 
     use super::*;
-    use crate::{DamageResult, DamageType, EventDamageCreate, EventDead};
+    use crate::{DamageOrigin, DamageResult, DamageType};
 
     // Used in tests to capture the target selected by the system
     #[derive(Resource, Default)]
@@ -169,16 +340,31 @@ mod tests {
         app
     }
 
-    // Helper to build DamageResult
-    fn mock_damage_result() -> DamageResult {
-        DamageResult {
-            final_damage: 10.0,
-            white_shield_absorbed: 0.0,
-            magic_shield_absorbed: 0.0,
-            reduced_damage: 0.0,
-            armor_reduced_damage: 0.0,
-            original_damage: 10.0,
-        }
+    fn hit(world: &mut World, source: Entity, target: Entity) {
+        world.trigger(EventDamageCreate {
+            entity: target,
+            source,
+            damage_type: DamageType::Physical,
+            origin: DamageOrigin::Attack,
+            damage_result: DamageResult {
+                final_damage: 10.0,
+                original_damage: 10.0,
+                ..default()
+            },
+        });
+    }
+
+    fn last_target(app: &App) -> Option<Entity> {
+        app.world().resource::<LastTarget>().0
+    }
+
+    // Minions carry their own aggro, so read the scanning unit's state directly
+    fn target_of(app: &App, entity: Entity) -> Option<Entity> {
+        app.world()
+            .get::<AggroState>(entity)
+            .unwrap()
+            .target
+            .map(|(target, _)| target)
     }
 
     #[test]
@@ -188,7 +374,7 @@ mod tests {
 
         // 1. Create unit with aggro system (Order)
         let _me = world
-            .spawn((Team::Order, Transform::default(), Aggro { range: 100.0 }))
+            .spawn((Team::Order, Transform::default(), Aggro::new(100.0)))
             .id();
 
         // 2. Create two enemies, one close (10m), one far (20m)
@@ -203,21 +389,24 @@ mod tests {
         // Run the system
         app.update();
 
-        // Assert: when no aggro values exist, should select the closest enemy
-        let target = app.world().resource::<LastTarget>().0;
-        assert_eq!(target, Some(enemy_near), "should prioritize the closest target");
+        // Assert: when no calls for help exist, should select the closest enemy
+        assert_eq!(
+            last_target(&app),
+            Some(enemy_near),
+            "should prioritize the closest target"
+        );
     }
 
     #[test]
-    fn test_scan_high_aggro_priority() {
+    fn test_scan_call_for_help_priority() {
         let mut app = setup_app();
         let world = app.world_mut();
 
         let me = world
-            .spawn((Team::Order, Transform::default(), Aggro { range: 100.0 }))
+            .spawn((Team::Order, Transform::default(), Aggro::new(100.0)))
             .id();
 
-        let enemy_near = world
+        let _enemy_near = world
             .spawn((Team::Chaos, Transform::from_xyz(10.0, 0.0, 0.0)))
             .id();
 
@@ -225,16 +414,24 @@ mod tests {
             .spawn((Team::Chaos, Transform::from_xyz(50.0, 0.0, 0.0)))
             .id();
 
-        // Manually inject aggro values: far enemy has very high aggro
+        // Manually inject a call for help against the far enemy
         let mut aggro_state = world.get_mut::<AggroState>(me).unwrap();
-        aggro_state.aggros.insert(enemy_near, 0.0);
-        aggro_state.aggros.insert(enemy_far, 100.0);
+        aggro_state.calls.insert(
+            enemy_far,
+            CallForHelp {
+                priority: AggroPriority::ChampionAttackingChampion,
+                expires_at: f32::MAX,
+            },
+        );
 
         app.update();
 
-        // Assert: should ignore distance and select the target with highest aggro
-        let target = app.world().resource::<LastTarget>().0;
-        assert_eq!(target, Some(enemy_far), "should prioritize target with highest aggro");
+        // Assert: should ignore distance and select the target called for help against
+        assert_eq!(
+            last_target(&app),
+            Some(enemy_far),
+            "should prioritize target with a call for help"
+        );
     }
 
     #[test]
@@ -243,7 +440,7 @@ mod tests {
         let world = app.world_mut();
 
         // Own range is only 10
-        world.spawn((Team::Order, Transform::default(), Aggro { range: 10.0 }));
+        world.spawn((Team::Order, Transform::default(), Aggro::new(10.0)));
 
         // Enemy is at distance 20
         world.spawn((Team::Chaos, Transform::from_xyz(20.0, 0.0, 0.0)));
@@ -251,66 +448,181 @@ mod tests {
         app.update();
 
         // Assert: no target should be selected
-        let target = app.world().resource::<LastTarget>().0;
-        assert_eq!(target, None, "out of range targets should be ignored");
+        assert_eq!(
+            last_target(&app),
+            None,
+            "out of range targets should be ignored"
+        );
     }
 
     #[test]
-    fn test_damage_increases_aggro() {
+    fn test_damage_calls_for_help_within_radius() {
         let mut app = setup_app();
         let world = app.world_mut();
 
-        // Simulate scenario: ally is attacked, nearby guard should generate aggro against attacker
+        // Simulate scenario: allied champion is attacked, nearby guard answers the call
         let attacker = world
-            .spawn((Team::Order, Transform::from_xyz(5.0, 0.0, 0.0)))
+            .spawn((Champion, Team::Order, Transform::from_xyz(5.0, 0.0, 0.0)))
+            .id();
+        let ally = world
+            .spawn((Champion, Team::Chaos, Transform::default()))
             .id();
-        let ally = world.spawn((Team::Chaos, Transform::default())).id();
 
         let guard = world
-            .spawn((Team::Chaos, Transform::default(), Aggro { range: 50.0 }))
+            .spawn((
+                Team::Chaos,
+                Transform::default(),
+                Aggro::new(50.0).with_call_for_help_range(30.0),
+            ))
+            .id();
+        let far_guard = world
+            .spawn((
+                Team::Chaos,
+                Transform::from_xyz(40.0, 0.0, 0.0),
+                Aggro::new(50.0).with_call_for_help_range(30.0),
+            ))
             .id();
 
-        // Trigger damage event
-        world.trigger(EventDamageCreate {
-            entity: ally,
-            source: attacker,
-            damage_type: DamageType::Physical,
-            damage_result: mock_damage_result(),
-        });
-
+        hit(world, attacker, ally);
         app.update();
 
-        // Verify guard's aggro list
         let state = app.world().get::<AggroState>(guard).unwrap();
-        let aggro_val = state.aggros.get(&attacker).copied().unwrap_or(0.0);
+        assert_eq!(
+            state.calls.get(&attacker).map(|v| v.priority),
+            Some(AggroPriority::ChampionAttackingChampion),
+            "ally being hit should call the guard for help"
+        );
+        assert!(
+            app.world()
+                .get::<AggroState>(far_guard)
+                .unwrap()
+                .calls
+                .is_empty(),
+            "units outside the call for help radius should not answer"
+        );
 
-        assert_eq!(aggro_val, 10.0, "ally being hit should increase attacker's aggro value");
+        // The call is forgotten once it expires
+        for _ in 0..(CALL_FOR_HELP_DURATION * 64.0) as usize + 2 {
+            app.update();
+        }
+        assert!(app
+            .world()
+            .get::<AggroState>(guard)
+            .unwrap()
+            .calls
+            .is_empty());
     }
 
     #[test]
-    fn test_remove_aggro_on_dead() {
+    fn test_minion_priority_list() {
         let mut app = setup_app();
         let world = app.world_mut();
 
-        let enemy = world.spawn(Team::Chaos).id();
-        let me = world.spawn((Team::Order, Aggro { range: 100.0 })).id();
+        let me = world
+            .spawn((Team::Order, Transform::default(), Aggro::new(1000.0)))
+            .id();
+        let ally_champion = world
+            .spawn((Champion, Team::Order, Transform::from_xyz(-100.0, 0.0, 0.0)))
+            .id();
+        let ally_minion = world
+            .spawn((
+                Minion::Melee,
+                Team::Order,
+                Transform::from_xyz(0.0, 0.0, 100.0),
+            ))
+            .id();
 
-        // 1. Initialize aggro
-        if let Some(mut state) = world.get_mut::<AggroState>(me) {
-            state.aggros.insert(enemy, 50.0);
-        }
+        let enemy_champion = world
+            .spawn((Champion, Team::Chaos, Transform::from_xyz(100.0, 0.0, 0.0)))
+            .id();
+        let enemy_minion = world
+            .spawn((
+                Minion::Melee,
+                Team::Chaos,
+                Transform::from_xyz(400.0, 0.0, 0.0),
+            ))
+            .id();
+        let enemy_caster = world
+            .spawn((
+                Minion::Ranged,
+                Team::Chaos,
+                Transform::from_xyz(500.0, 0.0, 0.0),
+            ))
+            .id();
 
-        // 2. Trigger target death event (triggered on target entity)
-        // Note: According to on_target_dead implementation, EventDead needs to be triggered on the dead entity
-        world.trigger(EventDead { entity: enemy });
+        // Minions come before closer champions
+        app.update();
+        assert_eq!(target_of(&app, me), Some(enemy_minion));
 
+        // A minion attacking an allied champion beats the current target
+        hit(app.world_mut(), enemy_caster, ally_champion);
         app.update();
+        assert_eq!(target_of(&app, me), Some(enemy_caster));
 
-        // 3. Verify cleanup logic
-        let state = app.world().get::<AggroState>(me).unwrap();
-        assert!(
-            !state.aggros.contains_key(&enemy),
-            "target should be removed from aggro list after death"
+        // A champion attacking an allied minion ranks below that
+        hit(app.world_mut(), enemy_champion, ally_minion);
+        app.update();
+        assert_eq!(target_of(&app, me), Some(enemy_caster));
+
+        // A champion attacking an allied champion ranks first
+        hit(app.world_mut(), enemy_champion, ally_champion);
+        app.update();
+        assert_eq!(target_of(&app, me), Some(enemy_champion));
+        assert_eq!(
+            app.world().get::<AggroState>(me).unwrap().target,
+            Some((enemy_champion, AggroPriority::ChampionAttackingChampion))
         );
     }
+
+    #[test]
+    fn test_target_stickiness() {
+        let mut app = setup_app();
+        let world = app.world_mut();
+
+        let me = world
+            .spawn((Team::Order, Transform::default(), Aggro::new(1000.0)))
+            .id();
+        let ally_minion = world
+            .spawn((
+                Minion::Melee,
+                Team::Order,
+                Transform::from_xyz(0.0, 0.0, 100.0),
+            ))
+            .id();
+        let first = world
+            .spawn((
+                Minion::Melee,
+                Team::Chaos,
+                Transform::from_xyz(500.0, 0.0, 0.0),
+            ))
+            .id();
+
+        app.update();
+        assert_eq!(target_of(&app, me), Some(first));
+
+        // A closer minion of the same priority doesn't pull the target away
+        let closer = app
+            .world_mut()
+            .spawn((
+                Minion::Melee,
+                Team::Chaos,
+                Transform::from_xyz(100.0, 0.0, 0.0),
+            ))
+            .id();
+        app.update();
+        assert_eq!(target_of(&app, me), Some(first));
+
+        // Until it attacks an allied minion
+        hit(app.world_mut(), closer, ally_minion);
+        app.update();
+        assert_eq!(target_of(&app, me), Some(closer));
+
+        // A dead target releases the minion to the next best one
+        app.world_mut().trigger(EventDead { entity: closer });
+        app.world_mut().entity_mut(closer).insert(Dead {
+            respawn_timer: Timer::default(),
+        });
+        app.update();
+        assert_eq!(target_of(&app, me), Some(first));
+    }
 }
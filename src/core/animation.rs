@@ -302,6 +302,11 @@ fn on_state_change(
                     .with_repeat(false)
                     .with_duration(attack.animation_duration());
             }
+            // Cast animations are played by the skill effect
+            State::Casting => {}
+            State::Dead => {
+                animation_state.update(hash_bin("Death")).with_repeat(false);
+            }
         }
     }
 }
@@ -6,7 +6,8 @@ use lol_config::{HashKey, LoadHashKeyTrait};
 use serde::{Deserialize, Serialize};
 
 use crate::{
-    Buffs, CommandDamageCreate, CommandMissileCreate, CommandRotate, Damage, DamageType, EventDead,
+    BonusDamage, Buffs, CommandDamageCreate, CommandMissileCreate, CommandRotate, Damage,
+    DamageOrigin, DamageType, EventDead,
 };
 
 #[derive(Default)]
@@ -61,7 +62,12 @@ pub struct AttackState {
     pub target: Option<Entity>,
 }
 
-#[derive(Component, Clone)]
+/// Unit can't start new attacks, e.g. while casting a skill
+#[derive(Component, Default)]
+pub struct AttackBlock;
+
+#[derive(Component, Clone, Reflect)]
+#[reflect(Component)]
 pub struct BuffAttack {
     pub bonus_attack_speed: f32,
 }
@@ -225,11 +231,17 @@ fn on_command_attack_start(
     q_transform: Query<&Transform>,
     q_buff_attack: Query<&BuffAttack>,
     q_buffs: Query<&Buffs>,
+    q_attack_block: Query<(), With<AttackBlock>>,
     time: Res<Time<Fixed>>,
 ) {
     let entity = trigger.event_target();
     let target = trigger.target;
 
+    if q_attack_block.contains(entity) {
+        debug!("{} attack blocked", entity);
+        return;
+    }
+
     let now = time.elapsed_secs();
 
     let Ok(mut attack) = q_attack.get_mut(entity) else {
@@ -351,7 +363,10 @@ fn on_event_dead(
     for (entity, attack_state) in q_attack_state.iter() {
         if let AttackStatus::Windup { target, .. } = &attack_state.status {
             if *target == dead_entity {
-                debug!("{} removing attack state: attack target {} died", dead_entity, entity);
+                debug!(
+                    "{} removing attack state: attack target {} died",
+                    dead_entity, entity
+                );
                 commands.entity(entity).try_remove::<AttackState>();
             }
         }
@@ -359,14 +374,14 @@ fn on_event_dead(
 }
 
 fn fixed_update(
-    mut query: Query<(Entity, &mut AttackState, &Attack, &Damage)>,
+    mut query: Query<(Entity, &mut AttackState, &Attack, &Damage, &BonusDamage)>,
     mut commands: Commands,
     res_assets_spell_object: Res<Assets<SpellObject>>,
     time: Res<Time<Fixed>>,
 ) {
     let now = time.elapsed_secs();
 
-    for (entity, mut attack_state, attack, damage) in query.iter_mut() {
+    for (entity, mut attack_state, attack, damage, bonus) in query.iter_mut() {
         match &attack_state.status.clone() {
             AttackStatus::Windup { target, end_time } => {
                 // Check if windup is complete
@@ -384,13 +399,15 @@ fn fixed_update(
                                     entity,
                                     target: *target,
                                     spell_key: spell_key.clone(),
+                                    damage: damage.total(bonus),
                                 });
                             } else {
                                 commands.try_trigger(CommandDamageCreate {
                                     entity: *target,
                                     source: entity,
                                     damage_type: DamageType::Physical,
-                                    amount: damage.0,
+                                    origin: DamageOrigin::Attack,
+                                    amount: damage.total(bonus),
                                 });
                             }
                         }
@@ -399,7 +416,8 @@ fn fixed_update(
                                 entity: *target,
                                 source: entity,
                                 damage_type: DamageType::Physical,
-                                amount: damage.0,
+                                origin: DamageOrigin::Attack,
+                                amount: damage.total(bonus),
                             });
                         }
                     }
@@ -1,8 +1,9 @@
 use bevy::prelude::*;
+use lol_core::Team;
 
 use crate::{
-    Attack, AttackState, AttackStatus, Bounding, CommandAttackStart, CommandAttackStop,
-    CommandRunStart, CommandRunStop, RunTarget,
+    is_visible_to, Attack, AttackState, AttackStatus, Bounding, CommandAttackStart,
+    CommandAttackStop, CommandRunStart, CommandRunStop, Dead, RunTarget, TeamVisibility,
 };
 
 #[derive(Default)]
@@ -36,11 +37,17 @@ pub struct CommandAttackAutoStop {
 fn on_command_attack_auto_start(
     trigger: On<CommandAttackAutoStart>,
     mut commands: Commands,
-    q: Query<(&Transform, &Bounding, Option<&Attack>)>,
+    q: Query<(&Transform, &Bounding, Option<&Attack>), Without<Dead>>,
+    q_team: Query<&Team>,
+    q_visibility: Query<&TeamVisibility>,
 ) {
     let entity = trigger.event_target();
     let target = trigger.target;
 
+    if !can_see_target(entity, target, &q_team, &q_visibility) {
+        return;
+    }
+
     let Ok((transform, bounding, Some(attack))) = q.get(entity) else {
         return;
     };
@@ -79,7 +86,9 @@ fn update_attack_auto(
         &Transform,
         &Bounding,
     )>,
-    q_target: Query<(&Transform, &Bounding)>,
+    q_target: Query<(&Transform, &Bounding), Without<Dead>>,
+    q_team: Query<&Team>,
+    q_visibility: Query<&TeamVisibility>,
 ) {
     for (entity, attack_auto, attack, attack_state, transform, bounding) in q_attacker.iter() {
         if let Some(AttackState {
@@ -92,6 +101,12 @@ fn update_attack_auto(
 
         let target = attack_auto.target;
 
+        // Target went into fog of war
+        if !can_see_target(entity, target, &q_team, &q_visibility) {
+            commands.trigger(CommandAttackAutoStop { entity });
+            continue;
+        }
+
         let Ok((target_transform, target_bounding)) = q_target.get(target) else {
             continue;
         };
@@ -109,6 +124,19 @@ fn update_attack_auto(
     }
 }
 
+fn can_see_target(
+    entity: Entity,
+    target: Entity,
+    q_team: &Query<&Team>,
+    q_visibility: &Query<&TeamVisibility>,
+) -> bool {
+    let Ok(team) = q_team.get(entity) else {
+        return true;
+    };
+
+    is_visible_to(q_visibility.get(target).ok(), team)
+}
+
 #[allow(clippy::too_many_arguments)]
 fn process_attack_logic(
     commands: &mut Commands,
@@ -0,0 +1,474 @@
+use std::collections::HashMap;
+
+use bevy::prelude::*;
+use lol_config::ConfigNavigationGrid;
+
+use crate::{
+    world_pos_to_grid_xy, Bounding, Dead, Movement, MovementBlock, MovementState, ResourceGrid,
+};
+
+const ORCA_EPSILON: f32 = 1e-5;
+
+/// Share of the previous avoidance correction kept each tick, smooths out jitter between
+/// neighbours that keep swapping sides
+const AVOIDANCE_DAMPING: f32 = 0.5;
+
+/// Velocity level local avoidance (ORCA) against nearby units, each unit takes its share of
+/// avoiding a collision depending on the neighbour's priority
+#[derive(Component, Debug, Clone)]
+#[require(AvoidanceState)]
+pub struct Avoidance {
+    /// Units closer than this are avoided
+    pub neighbour_radius: f32,
+    /// Seconds ahead collisions are looked for, higher values avoid earlier but more timidly
+    pub time_horizon: f32,
+    /// Units push lower priority units and are never pushed by them
+    pub priority: u8,
+}
+
+impl Avoidance {
+    pub const CHAMPION: Self = Self {
+        neighbour_radius: 300.0,
+        time_horizon: 0.5,
+        priority: 2,
+    };
+
+    pub const MINION: Self = Self {
+        neighbour_radius: 200.0,
+        time_horizon: 0.5,
+        priority: 1,
+    };
+
+    /// Share of the avoidance this unit takes against a unit of `other` priority
+    pub fn responsibility(&self, other: u8) -> f32 {
+        match self.priority.cmp(&other) {
+            std::cmp::Ordering::Greater => 0.0,
+            std::cmp::Ordering::Equal => 0.5,
+            std::cmp::Ordering::Less => 1.0,
+        }
+    }
+}
+
+#[derive(Component, Default, Debug)]
+pub struct AvoidanceState {
+    /// Velocity picked by the last avoidance step
+    pub velocity: Vec2,
+    /// Damped difference between the picked and the preferred velocity
+    pub correction: Vec2,
+}
+
+impl AvoidanceState {
+    /// Velocity to walk the path with, `None` while no neighbour is being avoided
+    pub fn steering(&self) -> Option<Vec2> {
+        (self.correction.length_squared() > ORCA_EPSILON).then_some(self.velocity)
+    }
+}
+
+/// Half-plane of allowed velocities, those left of `direction` going through `point`
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct OrcaLine {
+    pub point: Vec2,
+    pub direction: Vec2,
+}
+
+/// Constraint `velocity` must respect to avoid a neighbour at `relative_position` within
+/// `time_horizon`, `responsibility` is the share of the correction this unit takes
+pub fn orca_line(
+    relative_position: Vec2,
+    relative_velocity: Vec2,
+    velocity: Vec2,
+    combined_radius: f32,
+    time_horizon: f32,
+    dt: f32,
+    responsibility: f32,
+) -> OrcaLine {
+    let distance_squared = relative_position.length_squared();
+    let combined_radius_squared = combined_radius * combined_radius;
+
+    let (direction, u) = if distance_squared > combined_radius_squared {
+        // Vector from the cut-off circle center to the relative velocity
+        let w = relative_velocity - relative_position / time_horizon;
+        let w_length_squared = w.length_squared();
+        let dot = w.dot(relative_position);
+
+        if dot < 0.0 && dot * dot > combined_radius_squared * w_length_squared {
+            // Project on the cut-off circle
+            let w_length = w_length_squared.sqrt();
+            let unit_w = w / w_length;
+            (
+                Vec2::new(unit_w.y, -unit_w.x),
+                (combined_radius / time_horizon - w_length) * unit_w,
+            )
+        } else {
+            // Project on the legs of the velocity obstacle
+            let leg = (distance_squared - combined_radius_squared).sqrt();
+            let direction = if relative_position.perp_dot(w) > 0.0 {
+                Vec2::new(
+                    relative_position.x * leg - relative_position.y * combined_radius,
+                    relative_position.x * combined_radius + relative_position.y * leg,
+                ) / distance_squared
+            } else {
+                -Vec2::new(
+                    relative_position.x * leg + relative_position.y * combined_radius,
+                    -relative_position.x * combined_radius + relative_position.y * leg,
+                ) / distance_squared
+            };
+            (
+                direction,
+                relative_velocity.dot(direction) * direction - relative_velocity,
+            )
+        }
+    } else {
+        // Already overlapping, separate within this step
+        let w = relative_velocity - relative_position / dt;
+        let w_length = w.length();
+        let unit_w = if w_length > ORCA_EPSILON {
+            w / w_length
+        } else {
+            Vec2::X
+        };
+        (
+            Vec2::new(unit_w.y, -unit_w.x),
+            (combined_radius / dt - w_length) * unit_w,
+        )
+    };
+
+    OrcaLine {
+        point: velocity + responsibility * u,
+        direction,
+    }
+}
+
+/// Velocity closest to `preferred` within `max_speed` that satisfies all `lines`, when they
+/// can't all be satisfied the one violating them the least
+pub fn solve_orca(lines: &[OrcaLine], max_speed: f32, preferred: Vec2) -> Vec2 {
+    let mut result = Vec2::ZERO;
+    let failed = linear_program2(lines, max_speed, preferred, false, &mut result);
+    if failed < lines.len() {
+        linear_program3(lines, failed, max_speed, &mut result);
+    }
+    result
+}
+
+fn linear_program1(
+    lines: &[OrcaLine],
+    line_no: usize,
+    radius: f32,
+    opt_velocity: Vec2,
+    direction_opt: bool,
+    result: &mut Vec2,
+) -> bool {
+    let line = lines[line_no];
+    let dot = line.point.dot(line.direction);
+    let discriminant = dot * dot + radius * radius - line.point.length_squared();
+
+    if discriminant < 0.0 {
+        // Max speed circle fully invalidates this line
+        return false;
+    }
+
+    let sqrt_discriminant = discriminant.sqrt();
+    let mut t_left = -dot - sqrt_discriminant;
+    let mut t_right = -dot + sqrt_discriminant;
+
+    for other in &lines[..line_no] {
+        let denominator = line.direction.perp_dot(other.direction);
+        let numerator = other.direction.perp_dot(line.point - other.point);
+
+        if denominator.abs() <= ORCA_EPSILON {
+            // Parallel lines
+            if numerator < 0.0 {
+                return false;
+            }
+            continue;
+        }
+
+        let t = numerator / denominator;
+        if denominator >= 0.0 {
+            t_right = t_right.min(t);
+        } else {
+            t_left = t_left.max(t);
+        }
+
+        if t_left > t_right {
+            return false;
+        }
+    }
+
+    *result = if direction_opt {
+        if opt_velocity.dot(line.direction) > 0.0 {
+            line.point + t_right * line.direction
+        } else {
+            line.point + t_left * line.direction
+        }
+    } else {
+        let t = line.direction.dot(opt_velocity - line.point);
+        line.point + t.clamp(t_left, t_right) * line.direction
+    };
+
+    true
+}
+
+fn linear_program2(
+    lines: &[OrcaLine],
+    radius: f32,
+    opt_velocity: Vec2,
+    direction_opt: bool,
+    result: &mut Vec2,
+) -> usize {
+    *result = if direction_opt {
+        opt_velocity * radius
+    } else if opt_velocity.length_squared() > radius * radius {
+        opt_velocity.normalize() * radius
+    } else {
+        opt_velocity
+    };
+
+    for (i, line) in lines.iter().enumerate() {
+        if line.direction.perp_dot(line.point - *result) > 0.0 {
+            let previous = *result;
+            if !linear_program1(lines, i, radius, opt_velocity, direction_opt, result) {
+                *result = previous;
+                return i;
+            }
+        }
+    }
+
+    lines.len()
+}
+
+fn linear_program3(lines: &[OrcaLine], begin_line: usize, radius: f32, result: &mut Vec2) {
+    let mut distance = 0.0;
+
+    for (i, line) in lines.iter().enumerate().skip(begin_line) {
+        if line.direction.perp_dot(line.point - *result) <= distance {
+            continue;
+        }
+
+        let mut projected = Vec::with_capacity(i);
+        for other in &lines[..i] {
+            let determinant = line.direction.perp_dot(other.direction);
+
+            let point = if determinant.abs() <= ORCA_EPSILON {
+                if line.direction.dot(other.direction) > 0.0 {
+                    // Same direction
+                    continue;
+                }
+                0.5 * (line.point + other.point)
+            } else {
+                line.point
+                    + (other.direction.perp_dot(line.point - other.point) / determinant)
+                        * line.direction
+            };
+
+            projected.push(OrcaLine {
+                point,
+                direction: (other.direction - line.direction).normalize_or_zero(),
+            });
+        }
+
+        let previous = *result;
+        let direction = Vec2::new(-line.direction.y, line.direction.x);
+        if linear_program2(&projected, radius, direction, true, result) < projected.len() {
+            // Can only fail through floating point error, keep the last result
+            *result = previous;
+        }
+
+        distance = line.direction.perp_dot(line.point - *result);
+    }
+}
+
+struct AvoidanceAgent {
+    entity: Entity,
+    avoidance: Avoidance,
+    position: Vec2,
+    radius: f32,
+    velocity: Vec2,
+    preferred: Vec2,
+    max_speed: f32,
+    movable: bool,
+    /// Walking a path, the path movement then moves the unit with its avoidance velocity
+    following: bool,
+}
+
+/// Neighbour lookup over square buckets at least as wide as the largest avoidance range
+struct AvoidanceBuckets {
+    size: f32,
+    buckets: HashMap<IVec2, Vec<usize>>,
+}
+
+impl AvoidanceBuckets {
+    fn new(agents: &[AvoidanceAgent]) -> Self {
+        let max_radius = agents.iter().map(|v| v.radius).fold(0.0, f32::max);
+        let size = agents
+            .iter()
+            .map(|v| v.avoidance.neighbour_radius + 2.0 * max_radius)
+            .fold(ORCA_EPSILON, f32::max);
+
+        let mut buckets = HashMap::<IVec2, Vec<usize>>::new();
+        for (index, agent) in agents.iter().enumerate() {
+            buckets
+                .entry((agent.position / size).floor().as_ivec2())
+                .or_default()
+                .push(index);
+        }
+
+        Self { size, buckets }
+    }
+
+    /// Agents in the bucket of `position` and the eight around it
+    fn around(&self, position: Vec2) -> impl Iterator<Item = usize> + '_ {
+        let bucket = (position / self.size).floor().as_ivec2();
+        (-1..=1)
+            .flat_map(move |y| (-1..=1).map(move |x| bucket + IVec2::new(x, y)))
+            .filter_map(|key| self.buckets.get(&key))
+            .flatten()
+            .copied()
+    }
+}
+
+/// Picks an ORCA velocity around the neighbours before units follow their path. Path following
+/// units walk with it in the path movement, idle units are moved here.
+pub fn update_avoidance(
+    mut q_agent: Query<
+        (
+            Entity,
+            &Avoidance,
+            &mut AvoidanceState,
+            &Bounding,
+            &mut Transform,
+            Option<&Movement>,
+            Option<&MovementState>,
+            Has<MovementBlock>,
+        ),
+        Without<Dead>,
+    >,
+    res_grid: Option<Res<ResourceGrid>>,
+    assets_grid: Option<Res<Assets<ConfigNavigationGrid>>>,
+    time: Res<Time<Fixed>>,
+) {
+    let dt = time.delta_secs();
+    if dt <= 0.0 {
+        return;
+    }
+
+    let agents = q_agent
+        .iter()
+        .map(
+            |(entity, avoidance, state, bounding, transform, movement, movement_state, blocked)| {
+                let position = transform.translation.xz();
+                let max_speed = movement_state
+                    .and_then(|v| v.speed)
+                    .or(movement.map(|v| v.speed))
+                    .unwrap_or(0.0);
+
+                let target = movement_state
+                    .filter(|v| !v.completed)
+                    .and_then(|v| v.path.get(v.current_target_index));
+                let preferred = target
+                    .map(|target| (target.xz() - position).normalize_or_zero() * max_speed)
+                    .unwrap_or(Vec2::ZERO);
+
+                AvoidanceAgent {
+                    entity,
+                    avoidance: avoidance.clone(),
+                    position,
+                    radius: bounding.radius,
+                    velocity: if blocked { Vec2::ZERO } else { state.velocity },
+                    preferred,
+                    max_speed,
+                    movable: !blocked && max_speed > 0.0,
+                    following: !blocked && target.is_some(),
+                }
+            },
+        )
+        .collect::<Vec<_>>();
+
+    let grid = match (&res_grid, &assets_grid) {
+        (Some(res_grid), Some(assets_grid)) => assets_grid.get(&res_grid.0),
+        _ => None,
+    };
+
+    let buckets = AvoidanceBuckets::new(&agents);
+
+    for agent in agents.iter().filter(|v| v.movable) {
+        let avoidance = &agent.avoidance;
+        let mut lines = Vec::new();
+        for other in buckets.around(agent.position).map(|index| &agents[index]) {
+            if other.entity == agent.entity {
+                continue;
+            }
+
+            let mut relative_position = other.position - agent.position;
+            if relative_position.length() > avoidance.neighbour_radius + agent.radius + other.radius
+            {
+                continue;
+            }
+
+            // Units on top of each other separate in a stable direction
+            if relative_position.length_squared() <= ORCA_EPSILON {
+                relative_position = if agent.entity < other.entity {
+                    Vec2::X * ORCA_EPSILON
+                } else {
+                    Vec2::NEG_X * ORCA_EPSILON
+                };
+            }
+
+            let responsibility = if other.movable {
+                avoidance.responsibility(other.avoidance.priority)
+            } else {
+                1.0
+            };
+
+            if responsibility <= 0.0 {
+                continue;
+            }
+
+            lines.push(orca_line(
+                relative_position,
+                agent.velocity - other.velocity,
+                agent.velocity,
+                agent.radius + other.radius,
+                avoidance.time_horizon,
+                dt,
+                responsibility,
+            ));
+        }
+
+        let target = if lines.is_empty() {
+            agent.preferred
+        } else {
+            solve_orca(&lines, agent.max_speed, agent.preferred)
+        };
+
+        let Ok((_, _, mut state, _, mut transform, ..)) = q_agent.get_mut(agent.entity) else {
+            continue;
+        };
+
+        let correction = state
+            .correction
+            .lerp(target - agent.preferred, 1.0 - AVOIDANCE_DAMPING);
+        let velocity = (agent.preferred + correction).clamp_length_max(agent.max_speed);
+        let position = agent.position + velocity * dt;
+
+        // Never get pushed into walls
+        if let Some(grid) = grid {
+            if !grid.is_walkable_by_xy(world_pos_to_grid_xy(grid, position), 0.0) {
+                state.correction = Vec2::ZERO;
+                state.velocity = agent.preferred;
+                continue;
+            }
+        }
+
+        state.correction = correction;
+        state.velocity = velocity;
+
+        if agent.following || velocity.length_squared() <= ORCA_EPSILON {
+            continue;
+        }
+
+        debug!("{} pushed aside at {}", agent.entity, velocity);
+        transform.translation.x = position.x;
+        transform.translation.z = position.y;
+    }
+}
@@ -2,6 +2,7 @@ mod ability_resource;
 mod bounding;
 mod buff;
 mod direction;
+mod gold;
 mod level;
 mod pipeline;
 mod position;
@@ -12,6 +13,7 @@ use bevy::app::{App, Plugin};
 pub use bounding::*;
 pub use buff::*;
 pub use direction::*;
+pub use gold::*;
 pub use level::*;
 pub use pipeline::*;
 pub use position::*;
@@ -1,5 +1,7 @@
 use bevy::prelude::*;
 
+use crate::Health;
+
 #[derive(Component)]
 pub struct AbilityResource {
     pub ar_type: AbilityResourceType,
@@ -9,13 +11,199 @@ pub struct AbilityResource {
     pub per_level: f32,
     pub base_static_regen: f32,
     pub regen_per_level: f32,
+    /// Seconds since the unit last dealt or took damage, drives fury decay
+    pub out_of_combat: f32,
+}
+
+impl AbilityResource {
+    pub fn new(
+        ar_type: AbilityResourceType,
+        base: f32,
+        per_level: f32,
+        base_static_regen: f32,
+        regen_per_level: f32,
+    ) -> Self {
+        let value = if ar_type.rules().starts_empty {
+            0.0
+        } else {
+            base
+        };
+
+        Self {
+            ar_type,
+            value,
+            max: base,
+            base,
+            per_level,
+            base_static_regen,
+            regen_per_level,
+            out_of_combat: 0.0,
+        }
+    }
+
+    /// Regeneration per second at `level`
+    pub fn regen(&self, level: u32) -> f32 {
+        self.base_static_regen + self.regen_per_level * level.saturating_sub(1) as f32
+    }
+
+    /// Whether a spell with `cost` can be cast, health costs can't take the caster's last point
+    pub fn can_pay(&self, cost: f32, health: Option<&Health>) -> bool {
+        match self.ar_type.rules().cost {
+            ResourceCost::Spend => self.value >= cost,
+            // Casting while overheated is locked out
+            ResourceCost::Generate => cost <= 0.0 || self.value < self.max,
+            ResourceCost::Health => cost <= 0.0 || health.is_some_and(|v| v.value > cost),
+            ResourceCost::Free => true,
+        }
+    }
+
+    pub fn pay(&mut self, cost: f32, health: Option<&mut Health>) {
+        match self.ar_type.rules().cost {
+            ResourceCost::Spend => self.value = (self.value - cost).max(0.0),
+            ResourceCost::Generate => self.value = (self.value + cost).min(self.max),
+            ResourceCost::Health => {
+                if let Some(health) = health {
+                    health.value = (health.value - cost).max(1.0);
+                }
+            }
+            ResourceCost::Free => {}
+        }
+    }
 }
 
+/// Primary ability resource, ids follow the game's `arType` except 7 and 8 which the unit data
+/// uses for turrets and camps
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum AbilityResourceType {
     Mana,
     Energy,
     Turret,
     Camp,
+    None,
+    Shield,
+    BattleFury,
+    DragonFury,
+    Rage,
+    /// Rumble's heat
+    Heat,
+    /// Gnar's rage
+    GnarFury,
+    Ferocity,
+    BloodWell,
+    /// Yasuo's flow
+    Wind,
+    Ammo,
+    Moonlight,
+    Other,
+}
+
+/// How a spell's cost is paid
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ResourceCost {
+    /// Taken from the resource
+    Spend,
+    /// Added to the resource, casting is locked while it's full
+    Generate,
+    /// Taken from health, the resource bar isn't used for casting
+    Health,
+    Free,
+}
+
+/// Regen, decay and cost rules of a resource type
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ResourceRules {
+    pub starts_empty: bool,
+    pub regenerates: bool,
+    /// Lost per second once out of combat for `decay_delay` seconds
+    pub decay: f32,
+    pub decay_delay: f32,
+    /// Gained on each damage instance dealt or taken
+    pub gain_on_damage: f32,
+    pub cost: ResourceCost,
+}
+
+impl ResourceRules {
+    const REGEN: Self = Self {
+        starts_empty: false,
+        regenerates: true,
+        decay: 0.0,
+        decay_delay: 0.0,
+        gain_on_damage: 0.0,
+        cost: ResourceCost::Spend,
+    };
+
+    const FURY: Self = Self {
+        starts_empty: true,
+        regenerates: false,
+        decay: 5.0,
+        decay_delay: 8.0,
+        gain_on_damage: 5.0,
+        cost: ResourceCost::Spend,
+    };
+}
+
+impl AbilityResourceType {
+    pub fn rules(&self) -> ResourceRules {
+        match self {
+            Self::Mana | Self::Energy | Self::Ammo | Self::Moonlight | Self::Other => {
+                ResourceRules::REGEN
+            }
+            Self::None => ResourceRules {
+                regenerates: false,
+                cost: ResourceCost::Health,
+                ..ResourceRules::REGEN
+            },
+            Self::Turret | Self::Camp => ResourceRules {
+                regenerates: false,
+                cost: ResourceCost::Free,
+                ..ResourceRules::REGEN
+            },
+            Self::BloodWell => ResourceRules {
+                cost: ResourceCost::Health,
+                ..ResourceRules::FURY
+            },
+            Self::BattleFury | Self::DragonFury | Self::Rage | Self::GnarFury | Self::Ferocity => {
+                ResourceRules::FURY
+            }
+            Self::Shield => ResourceRules {
+                gain_on_damage: 0.0,
+                decay_delay: 0.0,
+                ..ResourceRules::FURY
+            },
+            Self::Heat => ResourceRules {
+                decay: 10.0,
+                decay_delay: 0.0,
+                gain_on_damage: 0.0,
+                cost: ResourceCost::Generate,
+                ..ResourceRules::FURY
+            },
+            Self::Wind => ResourceRules {
+                starts_empty: true,
+                ..ResourceRules::REGEN
+            },
+        }
+    }
+
+    /// Colour of the resource bar in the HUD
+    pub fn bar_color(&self) -> Color {
+        match self {
+            Self::Mana | Self::Other => Color::srgb(0.2, 0.45, 0.95),
+            Self::Energy => Color::srgb(0.95, 0.85, 0.2),
+            Self::BattleFury | Self::DragonFury | Self::Rage | Self::Ferocity => {
+                Color::srgb(0.85, 0.15, 0.1)
+            }
+            Self::GnarFury => Color::srgb(0.95, 0.5, 0.1),
+            Self::Heat => Color::srgb(0.95, 0.35, 0.1),
+            Self::BloodWell => Color::srgb(0.55, 0.05, 0.1),
+            Self::Shield
+            | Self::Wind
+            | Self::Moonlight
+            | Self::None
+            | Self::Turret
+            | Self::Camp => Color::srgb(0.85, 0.85, 0.9),
+            Self::Ammo => Color::srgb(0.6, 0.6, 0.65),
+        }
+    }
 }
 
 impl From<u8> for AbilityResourceType {
@@ -23,9 +211,24 @@ impl From<u8> for AbilityResourceType {
         match value {
             0 => AbilityResourceType::Mana,
             1 => AbilityResourceType::Energy,
+            2 => AbilityResourceType::None,
+            3 => AbilityResourceType::Shield,
+            4 => AbilityResourceType::BattleFury,
+            5 => AbilityResourceType::DragonFury,
+            6 => AbilityResourceType::Rage,
             7 => AbilityResourceType::Turret,
             8 => AbilityResourceType::Camp,
-            _ => panic!("Invalid AbilityResourceType {}", value),
+            9 => AbilityResourceType::Ferocity,
+            10 => AbilityResourceType::BloodWell,
+            11 => AbilityResourceType::Wind,
+            12 => AbilityResourceType::Ammo,
+            13 => AbilityResourceType::Moonlight,
+            14 => AbilityResourceType::Heat,
+            15 => AbilityResourceType::GnarFury,
+            _ => {
+                warn!("Unknown AbilityResourceType {}", value);
+                AbilityResourceType::Other
+            }
         }
     }
 }
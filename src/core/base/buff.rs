@@ -1,8 +1,61 @@
+use std::any::TypeId;
+use std::collections::HashMap;
+use std::sync::Arc;
+
 use bevy::prelude::*;
+use league_core::SpellObject;
+use serde::{Deserialize, Serialize};
 
-#[derive(Component, Debug)]
+use crate::rank_value;
+
+#[derive(Default)]
+pub struct PluginBuff;
+
+impl Plugin for PluginBuff {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<BuffRegistry>();
+
+        app.add_observer(on_add_buff);
+        app.add_observer(on_remove_buff);
+        app.add_observer(on_command_buff_apply);
+        app.add_observer(on_command_buff_remove);
+        app.add_observer(on_command_buff_dispel);
+
+        app.add_systems(FixedUpdate, fixed_update_buff_duration);
+    }
+}
+
+#[derive(Component, Debug, Clone)]
 pub struct Buff {
     pub name: &'static str,
+    pub stacks: u32,
+    /// Unit that applied the buff
+    pub source: Option<Entity>,
+    /// Counts down the buff's lifetime, `None` lasts until removed
+    pub timer: Option<Timer>,
+}
+
+impl Buff {
+    pub fn new(name: &'static str) -> Self {
+        Self {
+            name,
+            stacks: 1,
+            source: None,
+            timer: None,
+        }
+    }
+
+    pub fn remaining(&self) -> Option<f32> {
+        self.timer.as_ref().map(|v| v.remaining_secs())
+    }
+
+    pub fn snapshot(&self) -> BuffSnapshot {
+        BuffSnapshot {
+            name: self.name.to_string(),
+            stacks: self.stacks,
+            remaining: self.remaining(),
+        }
+    }
 }
 
 #[derive(Component, Debug)]
@@ -20,3 +73,480 @@ impl std::ops::Deref for Buffs {
         &self.0
     }
 }
+
+/// What happens when a buff is applied to a unit that already has it
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum BuffStacking {
+    /// Add a stack and restart the duration
+    #[default]
+    Refresh,
+    /// Add a stack and add the new duration to what's left
+    Extend,
+    /// Every application is its own instance with its own duration, the oldest one makes room
+    /// once `max_stacks` is reached
+    Independent,
+}
+
+/// Which effects can remove the buff
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum BuffCategory {
+    /// Kit mechanics, never dispelled
+    #[default]
+    Internal,
+    Positive,
+    Negative,
+    CrowdControl,
+}
+
+impl BuffCategory {
+    /// Categories removed by cleanse and quicksilver effects
+    pub const CLEANSE: &'static [BuffCategory] =
+        &[BuffCategory::Negative, BuffCategory::CrowdControl];
+}
+
+pub type BuffInsert = Arc<dyn Fn(&mut EntityCommands) + Send + Sync>;
+
+/// Rules shared by every instance of a buff
+#[derive(Clone)]
+pub struct BuffDefinition {
+    pub name: &'static str,
+    pub stacking: BuffStacking,
+    pub max_stacks: u32,
+    /// Default duration, `None` lasts until removed
+    pub duration: Option<f32>,
+    pub category: BuffCategory,
+    /// Inserts the buff's default component when applied by name
+    pub insert: Option<BuffInsert>,
+}
+
+impl BuffDefinition {
+    pub fn new(name: &'static str) -> Self {
+        Self {
+            name,
+            stacking: BuffStacking::default(),
+            max_stacks: 1,
+            duration: None,
+            category: BuffCategory::default(),
+            insert: None,
+        }
+    }
+
+    pub fn with_stacking(mut self, stacking: BuffStacking, max_stacks: u32) -> Self {
+        self.stacking = stacking;
+        self.max_stacks = max_stacks.max(1);
+        self
+    }
+
+    pub fn with_duration(mut self, duration: f32) -> Self {
+        self.duration = Some(duration);
+        self
+    }
+
+    pub fn with_category(mut self, category: BuffCategory) -> Self {
+        self.category = category;
+        self
+    }
+}
+
+/// Buff definitions by name
+#[derive(Resource, Default, Clone)]
+pub struct BuffRegistry {
+    definitions: HashMap<&'static str, BuffDefinition>,
+    /// Buff names keyed by the component registered for them
+    names: HashMap<TypeId, &'static str>,
+}
+
+impl BuffRegistry {
+    pub fn get(&self, name: &str) -> Option<&BuffDefinition> {
+        self.definitions.get(name)
+    }
+
+    /// Name of the buff registered for the first of `types` that has one
+    pub fn name_of(&self, types: &[TypeId]) -> Option<&'static str> {
+        types.iter().find_map(|v| self.names.get(v).copied())
+    }
+
+    /// Definition of the buff a spell applies at `level`, registered under the spell's name.
+    /// Duration and stack cap come from the spell's `BuffDuration` and `MaxStacks` values
+    pub fn get_by_spell(&self, spell: &SpellObject, level: usize) -> Option<BuffDefinition> {
+        spell.m_buff.as_ref()?;
+        let mut definition = self.get(&spell.object_name)?.clone();
+
+        let value = |name: &str| {
+            let values = spell
+                .m_spell
+                .as_ref()?
+                .data_values
+                .as_ref()?
+                .iter()
+                .find(|v| v.m_name == name)?
+                .m_values
+                .as_ref();
+            rank_value(values, level)
+        };
+        if let Some(duration) = value("BuffDuration").or_else(|| value("Duration")) {
+            definition.duration = Some(duration);
+        }
+        if let Some(max_stacks) = value("MaxStacks") {
+            definition.max_stacks = (max_stacks as u32).max(1);
+        }
+        Some(definition)
+    }
+
+    pub fn category(&self, name: &str) -> BuffCategory {
+        self.get(name)
+            .map_or(BuffCategory::Internal, |v| v.category)
+    }
+}
+
+pub trait BuffAppExt {
+    /// Register a buff so it can be applied by name, its component is inserted with its default
+    fn register_buff<T: Component + Default>(&mut self, definition: BuffDefinition) -> &mut Self;
+}
+
+impl BuffAppExt for App {
+    fn register_buff<T: Component + Default>(
+        &mut self,
+        mut definition: BuffDefinition,
+    ) -> &mut Self {
+        definition.insert = Some(Arc::new(|commands: &mut EntityCommands| {
+            commands.insert(T::default());
+        }));
+        let mut registry = self.world_mut().get_resource_or_init::<BuffRegistry>();
+        registry.names.insert(TypeId::of::<T>(), definition.name);
+        registry.definitions.insert(definition.name, definition);
+        self
+    }
+}
+
+/// Serializable state of one buff instance
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct BuffSnapshot {
+    pub name: String,
+    pub stacks: u32,
+    pub remaining: Option<f32>,
+}
+
+/// Apply a registered buff following its stacking rules
+#[derive(EntityEvent, Clone)]
+pub struct CommandBuffApply {
+    pub entity: Entity,
+    pub name: String,
+    pub source: Option<Entity>,
+    /// Overrides the registered duration
+    pub duration: Option<f32>,
+    pub stacks: u32,
+    /// Inserted instead of the buff's default component on a new instance, replaces the
+    /// components of a refreshed one
+    pub insert: Option<BuffInsert>,
+}
+
+impl CommandBuffApply {
+    pub fn new(entity: Entity, name: impl Into<String>) -> Self {
+        Self {
+            entity,
+            name: name.into(),
+            source: None,
+            duration: None,
+            stacks: 1,
+            insert: None,
+        }
+    }
+
+    pub fn with_source(mut self, source: Entity) -> Self {
+        self.source = Some(source);
+        self
+    }
+
+    pub fn with_duration(mut self, duration: f32) -> Self {
+        self.duration = Some(duration);
+        self
+    }
+
+    pub fn with_stacks(mut self, stacks: u32) -> Self {
+        self.stacks = stacks;
+        self
+    }
+
+    pub fn with_insert(mut self, insert: BuffInsert) -> Self {
+        self.insert = Some(insert);
+        self
+    }
+
+    /// Apply the buff with `bundle` instead of its default component
+    pub fn with_bundle(self, bundle: impl Bundle + Clone) -> Self {
+        self.with_insert(Arc::new(move |commands: &mut EntityCommands| {
+            commands.insert(bundle.clone());
+        }))
+    }
+
+    /// Restore a buff taken with [`Buff::snapshot`]
+    pub fn from_snapshot(entity: Entity, snapshot: &BuffSnapshot) -> Self {
+        Self {
+            duration: snapshot.remaining,
+            stacks: snapshot.stacks,
+            ..Self::new(entity, snapshot.name.clone())
+        }
+    }
+}
+
+/// Remove `stacks` stacks of a buff, or every instance when `None`
+#[derive(EntityEvent, Debug)]
+pub struct CommandBuffRemove {
+    pub entity: Entity,
+    pub name: String,
+    pub stacks: Option<u32>,
+}
+
+/// Remove every buff in one of `categories`
+#[derive(EntityEvent, Debug)]
+pub struct CommandBuffDispel {
+    pub entity: Entity,
+    pub categories: Vec<BuffCategory>,
+}
+
+#[derive(EntityEvent, Debug)]
+pub struct EventBuffAdded {
+    pub entity: Entity,
+    pub buff: Entity,
+    pub name: &'static str,
+}
+
+#[derive(EntityEvent, Debug)]
+pub struct EventBuffRemoved {
+    pub entity: Entity,
+    pub buff: Entity,
+    pub name: &'static str,
+}
+
+/// Stack count changed, for independent buffs `stacks` is the number of instances
+#[derive(EntityEvent, Debug)]
+pub struct EventBuffStackChanged {
+    pub entity: Entity,
+    pub buff: Entity,
+    pub name: &'static str,
+    pub stacks: u32,
+}
+
+/// Buffs spawned without a duration take the registered one
+fn on_add_buff(
+    trigger: On<Add, Buff>,
+    mut commands: Commands,
+    res_buff_registry: Res<BuffRegistry>,
+    mut q_buff: Query<(&mut Buff, &BuffOf)>,
+) {
+    let buff = trigger.event_target();
+    let Ok((mut data, buff_of)) = q_buff.get_mut(buff) else {
+        return;
+    };
+
+    if data.timer.is_none() {
+        let duration = res_buff_registry.get(data.name).and_then(|v| v.duration);
+        data.timer = duration.map(|v| Timer::from_seconds(v, TimerMode::Once));
+    }
+
+    commands.trigger(EventBuffAdded {
+        entity: buff_of.0,
+        buff,
+        name: data.name,
+    });
+}
+
+fn on_remove_buff(
+    trigger: On<Remove, Buff>,
+    mut commands: Commands,
+    q_buff: Query<(&Buff, &BuffOf)>,
+) {
+    let buff = trigger.event_target();
+    let Ok((data, buff_of)) = q_buff.get(buff) else {
+        return;
+    };
+
+    commands.trigger(EventBuffRemoved {
+        entity: buff_of.0,
+        buff,
+        name: data.name,
+    });
+}
+
+fn on_command_buff_apply(
+    trigger: On<CommandBuffApply>,
+    mut commands: Commands,
+    res_buff_registry: Res<BuffRegistry>,
+    q_buffs: Query<&Buffs>,
+    mut q_buff: Query<&mut Buff>,
+) {
+    let entity = trigger.event_target();
+    let Some(definition) = res_buff_registry.get(&trigger.name) else {
+        warn!("{} applying unregistered buff {}", entity, trigger.name);
+        return;
+    };
+
+    let duration = trigger.duration.or(definition.duration);
+    let timer = duration.map(|v| Timer::from_seconds(v, TimerMode::Once));
+    let instances = q_buffs
+        .get(entity)
+        .into_iter()
+        .flat_map(|buffs| buffs.iter())
+        .filter(|&buff| q_buff.get(buff).is_ok_and(|v| v.name == definition.name))
+        .collect::<Vec<_>>();
+
+    if definition.stacking != BuffStacking::Independent {
+        if let Some(&buff) = instances.first() {
+            let mut data = q_buff.get_mut(buff).unwrap();
+            let stacks = (data.stacks + trigger.stacks).min(definition.max_stacks);
+
+            data.source = trigger.source.or(data.source);
+            data.timer = match (definition.stacking, data.timer.take(), duration) {
+                (BuffStacking::Extend, Some(current), Some(duration)) => Some(Timer::from_seconds(
+                    current.remaining_secs() + duration,
+                    TimerMode::Once,
+                )),
+                _ => timer,
+            };
+
+            if let Some(insert) = trigger.insert.as_ref() {
+                insert(&mut commands.entity(buff));
+            }
+
+            if stacks != data.stacks {
+                data.stacks = stacks;
+                commands.trigger(EventBuffStackChanged {
+                    entity,
+                    buff,
+                    name: definition.name,
+                    stacks,
+                });
+            }
+            return;
+        }
+    }
+
+    // Make room for the new instance by dropping the one closest to expiring
+    let mut count = instances.len() as u32;
+    if definition.stacking == BuffStacking::Independent && count >= definition.max_stacks {
+        let oldest = instances.iter().copied().min_by(|&a, &b| {
+            let remaining = |buff| {
+                q_buff
+                    .get(buff)
+                    .ok()
+                    .and_then(|v| v.remaining())
+                    .unwrap_or(f32::MAX)
+            };
+            remaining(a).total_cmp(&remaining(b))
+        });
+        if let Some(oldest) = oldest {
+            commands.entity(oldest).despawn();
+            count -= 1;
+        }
+    }
+
+    let mut buff = commands.spawn((
+        BuffOf(entity),
+        Buff {
+            name: definition.name,
+            stacks: trigger.stacks.clamp(1, definition.max_stacks),
+            source: trigger.source,
+            timer,
+        },
+    ));
+    if let Some(insert) = trigger.insert.as_ref().or(definition.insert.as_ref()) {
+        insert(&mut buff);
+    }
+    let buff = buff.id();
+
+    if definition.stacking == BuffStacking::Independent && count > 0 {
+        commands.trigger(EventBuffStackChanged {
+            entity,
+            buff,
+            name: definition.name,
+            stacks: count + 1,
+        });
+    }
+}
+
+fn on_command_buff_remove(
+    trigger: On<CommandBuffRemove>,
+    mut commands: Commands,
+    q_buffs: Query<&Buffs>,
+    mut q_buff: Query<&mut Buff>,
+) {
+    let entity = trigger.event_target();
+    let Ok(buffs) = q_buffs.get(entity) else {
+        return;
+    };
+
+    let mut left = trigger.stacks;
+    for buff in buffs.iter() {
+        let Ok(mut data) = q_buff.get_mut(buff) else {
+            continue;
+        };
+        if data.name != trigger.name {
+            continue;
+        }
+
+        match left {
+            Some(0) => return,
+            Some(stacks) if stacks < data.stacks => {
+                data.stacks -= stacks;
+                commands.trigger(EventBuffStackChanged {
+                    entity,
+                    buff,
+                    name: data.name,
+                    stacks: data.stacks,
+                });
+                return;
+            }
+            Some(stacks) => {
+                left = Some(stacks - data.stacks);
+                commands.entity(buff).despawn();
+            }
+            None => {
+                commands.entity(buff).despawn();
+            }
+        }
+    }
+}
+
+fn on_command_buff_dispel(
+    trigger: On<CommandBuffDispel>,
+    mut commands: Commands,
+    res_buff_registry: Res<BuffRegistry>,
+    q_buffs: Query<&Buffs>,
+    q_buff: Query<&Buff>,
+) {
+    let entity = trigger.event_target();
+    let Ok(buffs) = q_buffs.get(entity) else {
+        return;
+    };
+
+    for buff in buffs.iter() {
+        let Ok(data) = q_buff.get(buff) else {
+            continue;
+        };
+
+        let category = res_buff_registry.category(data.name);
+        if category != BuffCategory::Internal && trigger.categories.contains(&category) {
+            debug!("{} dispelled {}", entity, data.name);
+            commands.entity(buff).despawn();
+        }
+    }
+}
+
+fn fixed_update_buff_duration(
+    mut commands: Commands,
+    mut q_buff: Query<(Entity, &mut Buff)>,
+    time: Res<Time<Fixed>>,
+) {
+    for (entity, mut buff) in q_buff.iter_mut() {
+        let Some(timer) = buff.timer.as_mut() else {
+            continue;
+        };
+
+        timer.tick(time.delta());
+        if timer.is_finished() {
+            debug!("{} buff {} expired", entity, buff.name);
+            commands.entity(entity).despawn();
+        }
+    }
+}
@@ -0,0 +1,7 @@
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+
+/// Gold owned by a unit
+#[derive(Component, Reflect, Clone, Default, Debug, Serialize, Deserialize)]
+#[reflect(Component)]
+pub struct Gold(pub f32);
@@ -1,5 +1,6 @@
 use std::marker::PhantomData;
 
+use bevy::ecs::world::DeferredWorld;
 use bevy::prelude::*;
 
 #[derive(Component, Default)]
@@ -118,3 +119,77 @@ fn cleanup_result<T, R, P>(
             .remove::<FinalDecision<R>>();
     }
 }
+
+/// Stage function of a [`PipelineModifiers`] pipeline
+pub type PipelineModifier<C> = fn(&mut DeferredWorld, &mut C);
+
+/// Modifiers of a pipeline resolved inside the observer that receives the request, for results
+/// that have to be visible as soon as the request is triggered. Stages run in order, modifiers
+/// of the same stage in the order they were added.
+#[derive(Resource)]
+pub struct PipelineModifiers<C, P>
+where
+    C: Send + Sync + 'static,
+    P: Ord + Copy + Send + Sync + 'static,
+{
+    modifiers: Vec<(P, PipelineModifier<C>)>,
+}
+
+impl<C, P> Default for PipelineModifiers<C, P>
+where
+    C: Send + Sync + 'static,
+    P: Ord + Copy + Send + Sync + 'static,
+{
+    fn default() -> Self {
+        Self {
+            modifiers: Vec::new(),
+        }
+    }
+}
+
+impl<C, P> PipelineModifiers<C, P>
+where
+    C: Send + Sync + 'static,
+    P: Ord + Copy + Send + Sync + 'static,
+{
+    /// Run every stage on the context
+    pub fn run(world: &mut DeferredWorld, context: &mut C) {
+        let Some(pipeline) = world.get_resource::<Self>() else {
+            return;
+        };
+
+        let modifiers = pipeline
+            .modifiers
+            .iter()
+            .map(|(_, modifier)| *modifier)
+            .collect::<Vec<_>>();
+        for modifier in modifiers {
+            modifier(world, context);
+        }
+    }
+}
+
+pub trait PipelineAppExt {
+    /// Add a modifier to a stage of a [`PipelineModifiers`] pipeline
+    fn add_pipeline_modifier<C, P>(&mut self, stage: P, modifier: PipelineModifier<C>) -> &mut Self
+    where
+        C: Send + Sync + 'static,
+        P: Ord + Copy + Send + Sync + 'static;
+}
+
+impl PipelineAppExt for App {
+    fn add_pipeline_modifier<C, P>(&mut self, stage: P, modifier: PipelineModifier<C>) -> &mut Self
+    where
+        C: Send + Sync + 'static,
+        P: Ord + Copy + Send + Sync + 'static,
+    {
+        let mut pipeline = self
+            .world_mut()
+            .get_resource_or_init::<PipelineModifiers<C, P>>();
+        let index = pipeline
+            .modifiers
+            .partition_point(|(other, _)| *other <= stage);
+        pipeline.modifiers.insert(index, (stage, modifier));
+        self
+    }
+}
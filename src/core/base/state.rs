@@ -1,6 +1,9 @@
 use bevy::prelude::*;
 
-use crate::{EventAttackStart, EventRunEnd, EventRunStart};
+use crate::{
+    EventAttackStart, EventRunEnd, EventRunStart, EventSkillCastEnd, EventSkillCastStart,
+    EventSkillInterrupted,
+};
 
 #[derive(Default)]
 pub struct PluginState;
@@ -10,6 +13,9 @@ impl Plugin for PluginState {
         app.add_observer(on_run_start);
         app.add_observer(on_run_end);
         app.add_observer(on_command_attack_start);
+        app.add_observer(on_event_skill_cast_start);
+        app.add_observer(on_event_skill_cast_end);
+        app.add_observer(on_event_skill_interrupted);
     }
 }
 
@@ -19,6 +25,8 @@ pub enum State {
     Idle,
     Running,
     Attacking,
+    Casting,
+    Dead,
 }
 
 fn on_run_start(trigger: On<EventRunStart>, mut query: Query<&mut State>) {
@@ -28,6 +36,10 @@ fn on_run_start(trigger: On<EventRunStart>, mut query: Query<&mut State>) {
         return;
     };
 
+    if *state == State::Dead {
+        return;
+    }
+
     *state = State::Running;
 }
 
@@ -38,6 +50,10 @@ fn on_run_end(trigger: On<EventRunEnd>, mut query: Query<&mut State>) {
         return;
     };
 
+    if *state == State::Dead {
+        return;
+    }
+
     *state = State::Idle;
 }
 
@@ -48,5 +64,33 @@ fn on_command_attack_start(trigger: On<EventAttackStart>, mut query: Query<&mut
         return;
     };
 
+    if *state == State::Dead {
+        return;
+    }
+
     *state = State::Attacking;
 }
+
+fn set_state(entity: Entity, query: &mut Query<&mut State>, new_state: State) {
+    let Ok(mut state) = query.get_mut(entity) else {
+        return;
+    };
+
+    if *state == State::Dead {
+        return;
+    }
+
+    *state = new_state;
+}
+
+fn on_event_skill_cast_start(trigger: On<EventSkillCastStart>, mut query: Query<&mut State>) {
+    set_state(trigger.event_target(), &mut query, State::Casting);
+}
+
+fn on_event_skill_cast_end(trigger: On<EventSkillCastEnd>, mut query: Query<&mut State>) {
+    set_state(trigger.event_target(), &mut query, State::Idle);
+}
+
+fn on_event_skill_interrupted(trigger: On<EventSkillInterrupted>, mut query: Query<&mut State>) {
+    set_state(trigger.event_target(), &mut query, State::Idle);
+}
@@ -1,11 +1,13 @@
 use bevy::prelude::*;
 use league_core::CharacterRecord;
+use league_utils::hash_bin;
 use lol_config::{HashKey, LoadHashKeyTrait};
 use lol_core::Team;
 
 use crate::{
-    AbilityResource, AbilityResourceType, Armor, Attack, Bounding, CommandLoadPropBin,
-    CommandSkinSpawn, Damage, EventDead, EventLevelUp, Health, Level, Loading, Movement, PropPath,
+    AbilityResource, AbilityResourceType, Armor, Attack, Bounding, Champion, CommandLoadPropBin,
+    CommandSkinSpawn, CoolDown, Damage, EventDead, EventLevelUp, Health, HealthRegen, Level,
+    Loading, MagicResist, Movement, PassiveSkillOf, PropPath, SightRange, Skill, SkillOf, Skills,
 };
 
 #[derive(Default)]
@@ -71,9 +73,14 @@ fn on_command_character_load(trigger: On<CommandCharacterLoad>, mut commands: Co
 fn update_character_spawn(
     mut commands: Commands,
     res_assets_character_record: Res<Assets<CharacterRecord>>,
-    q_loading: Query<(Entity, &Loading<HashKey<CharacterRecord>>)>,
+    q_loading: Query<(
+        Entity,
+        &Loading<HashKey<CharacterRecord>>,
+        Has<Champion>,
+        Has<Skills>,
+    )>,
 ) {
-    for (entity, loading) in q_loading.iter() {
+    for (entity, loading, is_champion, has_skills) in q_loading.iter() {
         let Some(character_record) = res_assets_character_record.load_hash(loading.value) else {
             return;
         };
@@ -89,22 +96,25 @@ fn update_character_spawn(
             // );
             let ar_type = AbilityResourceType::from(primary_ability_resource.ar_type);
 
-            let ar = AbilityResource {
+            let ar = AbilityResource::new(
                 ar_type,
-                value: primary_ability_resource.ar_base.unwrap_or(0.0),
-                max: primary_ability_resource.ar_base.unwrap_or(0.0),
-                base: primary_ability_resource.ar_base.unwrap_or(0.0),
-                per_level: primary_ability_resource.ar_per_level.unwrap_or(0.0),
-                base_static_regen: primary_ability_resource.ar_base_static_regen.unwrap_or(0.0),
-                regen_per_level: primary_ability_resource.ar_regen_per_level.unwrap_or(0.0),
-            };
+                primary_ability_resource.ar_base.unwrap_or(0.0),
+                primary_ability_resource.ar_per_level.unwrap_or(0.0),
+                primary_ability_resource.ar_base_static_regen.unwrap_or(0.0),
+                primary_ability_resource.ar_regen_per_level.unwrap_or(0.0),
+            );
 
             commands.entity(entity).insert(ar);
         }
         // Create components based on character_record
         let health = Health::new(character_record.base_hp.unwrap_or(0.0));
+        let health_regen = HealthRegen {
+            base: character_record.base_static_hp_regen.unwrap_or(0.0),
+            per_level: character_record.hp_regen_per_level.unwrap_or(0.0),
+        };
         let damage = Damage(character_record.base_damage.unwrap_or(0.0));
         let armor = Armor(character_record.base_armor.unwrap_or(0.0));
+        let magic_resist = MagicResist(character_record.base_spell_block.unwrap_or(0.0));
         let movement = Movement {
             speed: character_record.base_move_speed.unwrap_or(0.0),
         };
@@ -116,12 +126,24 @@ fn update_character_spawn(
         commands.entity(entity).insert((
             Character { key: loading.value },
             health,
+            health_regen,
             movement,
             damage,
             armor,
+            magic_resist,
             bounding,
         ));
 
+        if is_champion && !has_skills {
+            spawn_skills(&mut commands, entity, character_record);
+        }
+
+        if let Some(perception_bubble_radius) = character_record.perception_bubble_radius {
+            commands
+                .entity(entity)
+                .insert(SightRange(perception_bubble_radius));
+        }
+
         if let Some(attack_range) = &character_record.attack_range {
             if let Some(basic_attack) = &character_record.basic_attack {
                 if let Some(cast_time) = basic_attack.m_attack_cast_time {
@@ -163,6 +185,39 @@ fn update_character_spawn(
     }
 }
 
+/// Create the passive and spell skills of a champion, each skill plays the `SkillEffect` stored
+/// under its spell key if one exists
+fn spawn_skills(commands: &mut Commands, entity: Entity, character_record: &CharacterRecord) {
+    let name = &character_record.m_character_name;
+    let passive = character_record
+        .m_character_passive_spell
+        .unwrap_or_else(|| {
+            hash_bin(&format!(
+                "Characters/{name}/Spells/{name}PassiveAbility/{name}Passive"
+            ))
+        });
+
+    commands.entity(entity).with_related::<PassiveSkillOf>((
+        Skill {
+            key_spell_object: passive.into(),
+            key_skill_effect: passive.into(),
+            ..default()
+        },
+        CoolDown::default(),
+    ));
+
+    for &spell in character_record.spells.iter().flatten() {
+        commands.entity(entity).with_related::<SkillOf>((
+            Skill {
+                key_spell_object: spell.into(),
+                key_skill_effect: spell.into(),
+                ..default()
+            },
+            CoolDown::default(),
+        ));
+    }
+}
+
 fn on_event_dead(
     event: On<EventDead>,
     query: Query<(&GlobalTransform, &Character, &Team)>,
@@ -0,0 +1,411 @@
+use std::collections::{HashMap, VecDeque};
+use std::fs::File;
+use std::io::{self, BufWriter, Write};
+use std::path::Path;
+
+use bevy::diagnostic::FrameCount;
+use bevy::ecs::system::SystemParam;
+use bevy::prelude::*;
+use lol_core::Team;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    Buff, BuffSnapshot, DamageOrigin, DamageResult, DamageType, EventBuffAdded, EventBuffRemoved,
+    EventDamageCreate, EventDead, EventHeal, EventLevelUp, EventSkillCastStart, Gold, HealKind,
+    HealResult, Health,
+};
+
+#[derive(Default)]
+pub struct PluginCombatLog;
+
+impl Plugin for PluginCombatLog {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<CombatLog>();
+
+        app.add_observer(on_event_damage_create);
+        app.add_observer(on_event_heal);
+        app.add_observer(on_event_dead);
+        app.add_observer(on_event_buff_added);
+        app.add_observer(on_event_buff_removed);
+        app.add_observer(on_event_skill_cast_start);
+        app.add_observer(on_event_level_up);
+        app.add_observer(on_despawn_health);
+
+        app.add_systems(Last, (update_gold_log, flush_combat_log_writer).chain());
+    }
+}
+
+/// Unit taking part in a combat event
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CombatUnit {
+    pub entity: Entity,
+    /// Champion or character name, `None` for unnamed units
+    pub name: Option<String>,
+    pub team: Option<Team>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type")]
+pub enum CombatEvent {
+    Damage {
+        source: CombatUnit,
+        target: CombatUnit,
+        damage_type: DamageType,
+        origin: DamageOrigin,
+        result: DamageResult,
+    },
+    Heal {
+        source: CombatUnit,
+        target: CombatUnit,
+        kind: HealKind,
+        result: HealResult,
+    },
+    /// `killer` is the last unit that damaged the target
+    Kill {
+        target: CombatUnit,
+        killer: Option<CombatUnit>,
+    },
+    /// `buff` holds the stacks and duration the buff was applied with
+    BuffAdded {
+        target: CombatUnit,
+        buff: BuffSnapshot,
+        source: Option<CombatUnit>,
+    },
+    BuffRemoved {
+        target: CombatUnit,
+        buff: String,
+    },
+    SkillCast {
+        caster: CombatUnit,
+        index: usize,
+    },
+    LevelUp {
+        unit: CombatUnit,
+        level: u32,
+    },
+    Gold {
+        unit: CombatUnit,
+        delta: f32,
+        total: f32,
+    },
+}
+
+impl CombatEvent {
+    /// Units taking part in the event
+    pub fn units(&self) -> Vec<&CombatUnit> {
+        match self {
+            Self::Damage { source, target, .. } | Self::Heal { source, target, .. } => {
+                vec![source, target]
+            }
+            Self::Kill { target, killer } => std::iter::once(target).chain(killer).collect(),
+            Self::BuffAdded { target, source, .. } => {
+                std::iter::once(target).chain(source).collect()
+            }
+            Self::BuffRemoved { target, .. } => vec![target],
+            Self::SkillCast { caster, .. } => vec![caster],
+            Self::LevelUp { unit, .. } | Self::Gold { unit, .. } => vec![unit],
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CombatLogEntry {
+    pub frame: u32,
+    /// Seconds since startup
+    pub time: f32,
+    #[serde(flatten)]
+    pub event: CombatEvent,
+}
+
+/// Entries kept by the default [`CombatLog`]
+pub const COMBAT_LOG_CAPACITY: usize = 10000;
+
+/// Typed record of everything that happened in combat, oldest entries first
+#[derive(Resource)]
+pub struct CombatLog {
+    entries: VecDeque<CombatLogEntry>,
+    /// Oldest entries are dropped past this count, `None` keeps everything
+    pub capacity: Option<usize>,
+    last_damage_source: HashMap<Entity, Entity>,
+}
+
+impl Default for CombatLog {
+    fn default() -> Self {
+        Self::with_capacity(COMBAT_LOG_CAPACITY)
+    }
+}
+
+impl CombatLog {
+    pub fn with_capacity(capacity: usize) -> Self {
+        Self {
+            entries: VecDeque::new(),
+            capacity: Some(capacity),
+            last_damage_source: HashMap::new(),
+        }
+    }
+
+    pub fn push(&mut self, entry: CombatLogEntry) {
+        if let Some(capacity) = self.capacity {
+            while self.entries.len() >= capacity {
+                self.entries.pop_front();
+            }
+        }
+        self.entries.push_back(entry);
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    pub fn clear(&mut self) {
+        self.entries.clear();
+        self.last_damage_source.clear();
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &CombatLogEntry> {
+        self.entries.iter()
+    }
+
+    /// Entries recorded at or after `frame`
+    pub fn since(&self, frame: u32) -> impl Iterator<Item = &CombatLogEntry> {
+        self.entries.iter().filter(move |v| v.frame >= frame)
+    }
+
+    /// Entries `entity` takes part in
+    pub fn involving(&self, entity: Entity) -> impl Iterator<Item = &CombatLogEntry> {
+        self.entries
+            .iter()
+            .filter(move |v| v.event.units().iter().any(|unit| unit.entity == entity))
+    }
+
+    /// Total damage dealt by `entity` after mitigation
+    pub fn damage_dealt(&self, entity: Entity) -> f32 {
+        self.entries
+            .iter()
+            .filter_map(|v| match &v.event {
+                CombatEvent::Damage { source, result, .. } if source.entity == entity => {
+                    Some(result.final_damage)
+                }
+                _ => None,
+            })
+            .sum()
+    }
+
+    /// Total damage taken by `entity` after mitigation
+    pub fn damage_taken(&self, entity: Entity) -> f32 {
+        self.entries
+            .iter()
+            .filter_map(|v| match &v.event {
+                CombatEvent::Damage { target, result, .. } if target.entity == entity => {
+                    Some(result.final_damage)
+                }
+                _ => None,
+            })
+            .sum()
+    }
+
+    /// Units killed by `entity`
+    pub fn kills(&self, entity: Entity) -> Vec<&CombatUnit> {
+        self.entries
+            .iter()
+            .filter_map(|v| match &v.event {
+                CombatEvent::Kill {
+                    target,
+                    killer: Some(killer),
+                } if killer.entity == entity => Some(target),
+                _ => None,
+            })
+            .collect()
+    }
+
+    /// Write every entry as one JSON object per line
+    pub fn write_jsonl(&self, mut writer: impl Write) -> io::Result<()> {
+        for entry in self.entries.iter() {
+            serde_json::to_writer(&mut writer, entry)?;
+            writer.write_all(b"\n")?;
+        }
+        writer.flush()
+    }
+}
+
+/// Streams every new combat log entry as a JSON line, insert it to start writing
+#[derive(Resource)]
+pub struct CombatLogWriter {
+    writer: Box<dyn Write + Send + Sync>,
+}
+
+impl CombatLogWriter {
+    pub fn new(writer: impl Write + Send + Sync + 'static) -> Self {
+        Self {
+            writer: Box::new(writer),
+        }
+    }
+
+    pub fn create(path: impl AsRef<Path>) -> io::Result<Self> {
+        Ok(Self::new(BufWriter::new(File::create(path)?)))
+    }
+
+    fn write(&mut self, entry: &CombatLogEntry) -> io::Result<()> {
+        serde_json::to_writer(&mut self.writer, entry)?;
+        self.writer.write_all(b"\n")
+    }
+}
+
+#[derive(SystemParam)]
+struct CombatRecorder<'w, 's> {
+    log: ResMut<'w, CombatLog>,
+    writer: Option<ResMut<'w, CombatLogWriter>>,
+    frame: Res<'w, FrameCount>,
+    time: Res<'w, Time>,
+    q_unit: Query<'w, 's, (Option<&'static Name>, Option<&'static Team>)>,
+}
+
+impl CombatRecorder<'_, '_> {
+    fn unit(&self, entity: Entity) -> CombatUnit {
+        let (name, team) = self.q_unit.get(entity).unwrap_or_default();
+        CombatUnit {
+            entity,
+            name: name.map(|v| v.to_string()),
+            team: team.cloned(),
+        }
+    }
+
+    fn record(&mut self, event: CombatEvent) {
+        let entry = CombatLogEntry {
+            frame: self.frame.0,
+            time: self.time.elapsed_secs(),
+            event,
+        };
+
+        if let Some(writer) = self.writer.as_mut() {
+            if let Err(err) = writer.write(&entry) {
+                warn!("Failed to write combat log entry: {}", err);
+            }
+        }
+
+        self.log.push(entry);
+    }
+}
+
+fn on_event_damage_create(trigger: On<EventDamageCreate>, mut recorder: CombatRecorder) {
+    let target = trigger.event_target();
+    recorder
+        .log
+        .last_damage_source
+        .insert(target, trigger.source);
+
+    let event = CombatEvent::Damage {
+        source: recorder.unit(trigger.source),
+        target: recorder.unit(target),
+        damage_type: trigger.damage_type,
+        origin: trigger.origin,
+        result: trigger.damage_result.clone(),
+    };
+    recorder.record(event);
+}
+
+fn on_event_heal(trigger: On<EventHeal>, mut recorder: CombatRecorder) {
+    let event = CombatEvent::Heal {
+        source: recorder.unit(trigger.source),
+        target: recorder.unit(trigger.event_target()),
+        kind: trigger.kind,
+        result: trigger.heal_result.clone(),
+    };
+    recorder.record(event);
+}
+
+fn on_event_dead(trigger: On<EventDead>, mut recorder: CombatRecorder) {
+    let target = trigger.event_target();
+    let killer = recorder.log.last_damage_source.remove(&target);
+
+    let event = CombatEvent::Kill {
+        target: recorder.unit(target),
+        killer: killer.map(|v| recorder.unit(v)),
+    };
+    recorder.record(event);
+}
+
+/// Units despawned without dying no longer need their last attacker
+fn on_despawn_health(trigger: On<Despawn, Health>, mut log: ResMut<CombatLog>) {
+    log.last_damage_source.remove(&trigger.event_target());
+}
+
+fn on_event_buff_added(
+    trigger: On<EventBuffAdded>,
+    mut recorder: CombatRecorder,
+    q_buff: Query<&Buff>,
+) {
+    let Ok(buff) = q_buff.get(trigger.buff) else {
+        return;
+    };
+
+    let event = CombatEvent::BuffAdded {
+        target: recorder.unit(trigger.event_target()),
+        buff: buff.snapshot(),
+        source: buff.source.map(|v| recorder.unit(v)),
+    };
+    recorder.record(event);
+}
+
+fn on_event_buff_removed(trigger: On<EventBuffRemoved>, mut recorder: CombatRecorder) {
+    let event = CombatEvent::BuffRemoved {
+        target: recorder.unit(trigger.event_target()),
+        buff: trigger.name.to_string(),
+    };
+    recorder.record(event);
+}
+
+fn on_event_skill_cast_start(trigger: On<EventSkillCastStart>, mut recorder: CombatRecorder) {
+    let event = CombatEvent::SkillCast {
+        caster: recorder.unit(trigger.event_target()),
+        index: trigger.index,
+    };
+    recorder.record(event);
+}
+
+fn on_event_level_up(trigger: On<EventLevelUp>, mut recorder: CombatRecorder) {
+    let event = CombatEvent::LevelUp {
+        unit: recorder.unit(trigger.event_target()),
+        level: trigger.level,
+    };
+    recorder.record(event);
+}
+
+/// Gold is changed in place by many systems, so changes are diffed once per frame
+fn update_gold_log(
+    mut recorder: CombatRecorder,
+    q_gold: Query<(Entity, &Gold), Changed<Gold>>,
+    mut last_gold: Local<HashMap<Entity, f32>>,
+) {
+    for (entity, gold) in q_gold.iter() {
+        let Some(last) = last_gold.insert(entity, gold.0) else {
+            continue;
+        };
+
+        let delta = gold.0 - last;
+        if delta == 0.0 {
+            continue;
+        }
+
+        let event = CombatEvent::Gold {
+            unit: recorder.unit(entity),
+            delta,
+            total: gold.0,
+        };
+        recorder.record(event);
+    }
+}
+
+fn flush_combat_log_writer(writer: Option<ResMut<CombatLogWriter>>) {
+    let Some(mut writer) = writer else {
+        return;
+    };
+
+    if let Err(err) = writer.writer.flush() {
+        warn!("Failed to flush combat log: {}", err);
+    }
+}
@@ -4,7 +4,7 @@ use std::collections::HashMap;
 use bevy::prelude::*;
 use lol_core::Team;
 
-use crate::{Action, CommandAction, Map};
+use crate::{is_visible_to, Action, CommandAction, Dead, Map, TeamVisibility, Trinket, WardType};
 
 #[derive(Default)]
 pub struct PluginController;
@@ -20,6 +20,8 @@ impl Plugin for PluginController {
 pub struct Controller {
     attack_key: KeyCode,
     stop_key: KeyCode,
+    ward_key: KeyCode,
+    control_ward_key: KeyCode,
     skill_key_map: HashMap<usize, KeyCode>,
 }
 
@@ -28,6 +30,8 @@ impl Default for Controller {
         Self {
             attack_key: KeyCode::KeyA,
             stop_key: KeyCode::KeyS,
+            ward_key: KeyCode::Digit4,
+            control_ward_key: KeyCode::Digit5,
             skill_key_map: HashMap::from([
                 (0, KeyCode::KeyQ),
                 (1, KeyCode::KeyW),
@@ -45,6 +49,12 @@ impl Controller {
     pub fn stop_key(&self) -> KeyCode {
         self.stop_key
     }
+    pub fn ward_key(&self) -> KeyCode {
+        self.ward_key
+    }
+    pub fn control_ward_key(&self) -> KeyCode {
+        self.control_ward_key
+    }
     pub fn iter_skill_keys(&self) -> Iter<'_, usize, KeyCode> {
         self.skill_key_map.iter()
     }
@@ -55,9 +65,9 @@ pub fn on_key_pressed(
     camera: Single<(&Camera, &GlobalTransform)>,
     mut ray_cast: MeshRayCast,
     q_children: Query<&ChildOf>,
-    q_controller: Query<(Entity, &Team, &Controller)>,
+    q_controller: Query<(Entity, &Team, &Controller, Option<&Trinket>)>,
     q_map: Query<Entity, With<Map>>,
-    q_target: Query<(Entity, &Transform, &Team)>,
+    q_target: Query<(Entity, &Transform, &Team, Option<&TeamVisibility>), Without<Dead>>,
     res_input: Res<ButtonInput<KeyCode>>,
     window: Single<&Window>,
 ) {
@@ -90,11 +100,14 @@ pub fn on_key_pressed(
 
     let position = hit.1.point;
 
-    for (entity, team, controller) in q_controller.iter() {
+    for (entity, team, controller, trinket) in q_controller.iter() {
         let action = if res_input.just_pressed(controller.attack_key()) {
             let mut min_distance = f32::MAX;
             let mut target = None;
-            for (entity, transform, target_team) in q_target.iter() {
+            for (entity, transform, target_team, visibility) in q_target.iter() {
+                if !is_visible_to(visibility, team) {
+                    continue;
+                }
                 let distance = position.distance(transform.translation);
                 if distance < min_distance && target_team != team {
                     min_distance = distance;
@@ -108,6 +121,16 @@ pub fn on_key_pressed(
             Some(Action::Attack(target))
         } else if res_input.just_pressed(controller.stop_key()) {
             Some(Action::Stop)
+        } else if res_input.just_pressed(controller.ward_key()) {
+            trinket.map(|v| Action::PlaceWard {
+                ward_type: v.ward_type,
+                point: position.xz(),
+            })
+        } else if res_input.just_pressed(controller.control_ward_key()) {
+            Some(Action::PlaceWard {
+                ward_type: WardType::Control,
+                point: position.xz(),
+            })
         } else {
             let mut action = None;
             for (skill_id, key) in controller.iter_skill_keys() {
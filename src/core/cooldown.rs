@@ -1,14 +1,91 @@
 use bevy::prelude::*;
 
-use crate::CoolDown;
+use crate::{Buffs, CoolDown, Skills};
 
 #[derive(Default)]
 pub struct PluginCooldown;
 
 impl Plugin for PluginCooldown {
     fn build(&self, app: &mut App) {
-        app.add_systems(FixedUpdate, fixed_update_cooldown);
+        app.add_observer(on_command_skill_cooldown_reset);
+        app.add_observer(on_command_skill_cooldown_refund);
+
+        app.add_systems(
+            FixedUpdate,
+            (
+                fixed_update_cooldown,
+                fixed_update_skill_ammo,
+                fixed_update_ability_haste,
+            ),
+        );
+    }
+}
+
+/// Ability haste, each point shortens skill cooldowns by the same share of casts per second.
+/// Summed from the unit's [`BuffAbilityHaste`] every tick
+#[derive(Component, Debug, Clone, Copy, Default, PartialEq)]
+pub struct AbilityHaste(pub f32);
+
+/// Ability haste a buff grants its owner while it lives
+#[derive(Component, Clone, Debug)]
+pub struct BuffAbilityHaste {
+    pub ability_haste: f32,
+}
+
+/// Charges of a skill paid with ammo, one comes back every `recharge` seconds up to `max`
+#[derive(Component, Debug, Clone)]
+pub struct SkillAmmo {
+    pub charges: u32,
+    pub max: u32,
+    pub timer: Timer,
+}
+
+impl SkillAmmo {
+    pub fn new(max: u32, recharge: f32) -> Self {
+        Self {
+            charges: max,
+            max,
+            timer: Timer::from_seconds(recharge, TimerMode::Repeating),
+        }
     }
+
+    /// Take `used` charges, false when there aren't enough
+    pub fn spend(&mut self, used: u32) -> bool {
+        let Some(charges) = self.charges.checked_sub(used) else {
+            return false;
+        };
+        if self.charges == self.max {
+            self.timer.reset();
+        }
+        self.charges = charges;
+        true
+    }
+}
+
+pub fn cooldown_with_haste(cooldown: f32, haste: f32) -> f32 {
+    cooldown * 100.0 / (100.0 + haste.max(0.0))
+}
+
+/// Make the skill at `index` ready immediately
+#[derive(EntityEvent, Debug)]
+pub struct CommandSkillCooldownReset {
+    pub entity: Entity,
+    pub index: usize,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum CooldownRefund {
+    Seconds(f32),
+    /// Share of the remaining cooldown, 0.0 to 1.0
+    Remaining(f32),
+}
+
+/// Shorten the running cooldown of the skill at `index`
+#[derive(EntityEvent, Debug)]
+pub struct CommandSkillCooldownRefund {
+    pub entity: Entity,
+    pub index: usize,
+    pub refund: CooldownRefund,
 }
 
 fn fixed_update_cooldown(time: Res<Time>, mut q_cooldown: Query<&mut CoolDown>) {
@@ -16,3 +93,74 @@ fn fixed_update_cooldown(time: Res<Time>, mut q_cooldown: Query<&mut CoolDown>)
         cooldown.timer.tick(time.delta());
     }
 }
+
+fn fixed_update_skill_ammo(time: Res<Time>, mut q_ammo: Query<&mut SkillAmmo>) {
+    for mut ammo in q_ammo.iter_mut() {
+        if ammo.charges >= ammo.max {
+            continue;
+        }
+
+        ammo.timer.tick(time.delta());
+        let recharged = ammo.timer.times_finished_this_tick();
+        ammo.charges = (ammo.charges + recharged).min(ammo.max);
+    }
+}
+
+fn fixed_update_ability_haste(
+    mut q_haste: Query<(&mut AbilityHaste, Option<&Buffs>)>,
+    q_buff_haste: Query<&BuffAbilityHaste>,
+) {
+    for (mut haste, buffs) in q_haste.iter_mut() {
+        let total = buffs
+            .map(|buffs| {
+                q_buff_haste
+                    .iter_many(buffs.iter())
+                    .map(|v| v.ability_haste)
+                    .sum()
+            })
+            .unwrap_or(0.0);
+
+        haste.set_if_neq(AbilityHaste(total));
+    }
+}
+
+fn on_command_skill_cooldown_reset(
+    trigger: On<CommandSkillCooldownReset>,
+    q_skills: Query<&Skills>,
+    mut q_cooldown: Query<&mut CoolDown>,
+) {
+    let entity = trigger.event_target();
+    let Some(&skill) = q_skills.get(entity).ok().and_then(|v| v.get(trigger.index)) else {
+        return;
+    };
+    let Ok(mut cooldown) = q_cooldown.get_mut(skill) else {
+        return;
+    };
+
+    debug!("{} skill {} cooldown reset", entity, trigger.index);
+    cooldown.reset();
+}
+
+fn on_command_skill_cooldown_refund(
+    trigger: On<CommandSkillCooldownRefund>,
+    q_skills: Query<&Skills>,
+    mut q_cooldown: Query<&mut CoolDown>,
+) {
+    let entity = trigger.event_target();
+    let Some(&skill) = q_skills.get(entity).ok().and_then(|v| v.get(trigger.index)) else {
+        return;
+    };
+    let Ok(mut cooldown) = q_cooldown.get_mut(skill) else {
+        return;
+    };
+
+    let seconds = match trigger.refund {
+        CooldownRefund::Seconds(seconds) => seconds,
+        CooldownRefund::Remaining(share) => cooldown.timer.remaining_secs() * share.clamp(0.0, 1.0),
+    };
+    debug!(
+        "{} skill {} cooldown refunded {:.2}s",
+        entity, trigger.index, seconds
+    );
+    cooldown.refund(seconds);
+}
@@ -1,7 +1,8 @@
+use bevy::ecs::world::DeferredWorld;
 use bevy::prelude::*;
 use serde::{Deserialize, Serialize};
 
-use crate::{BuffDamageReduction, BuffShieldMagic, BuffShieldWhite, Buffs, Health};
+use crate::{Buffs, Health, PipelineAppExt, PipelineModifiers};
 
 /// Damage system plugin
 #[derive(Default)]
@@ -9,19 +10,88 @@ pub struct PluginDamage;
 
 impl Plugin for PluginDamage {
     fn build(&self, app: &mut App) {
+        app.add_pipeline_modifier(DamagePipeline::Resist, damage_penetration);
+        app.add_pipeline_modifier(DamagePipeline::Mitigate, damage_mitigate);
+
         app.add_observer(on_command_damage_create);
+
+        app.add_systems(FixedUpdate, fixed_update_bonus_damage);
     }
 }
 
 #[derive(Component, Reflect, Clone, Serialize, Deserialize)]
 #[reflect(Component)]
+#[require(BonusDamage)]
 pub struct Damage(pub f32);
 
+impl Damage {
+    /// Attack damage including the bonus granted by buffs
+    pub fn total(&self, bonus: &BonusDamage) -> f32 {
+        self.0 + bonus.0
+    }
+}
+
+/// Attack damage granted by the unit's buffs, summed from their [`BuffDamage`] every tick
+#[derive(Component, Clone, Debug, Default, PartialEq)]
+pub struct BonusDamage(pub f32);
+
+/// Flat attack damage a buff grants its owner while it lives
+#[derive(Component, Clone, Debug)]
+pub struct BuffDamage {
+    pub bonus_damage: f32,
+}
+
 #[derive(Component, Clone, Serialize, Deserialize)]
 pub struct Armor(pub f32);
 
+#[derive(Component, Clone, Serialize, Deserialize)]
+pub struct MagicResist(pub f32);
+
+/// Unit takes no damage, e.g. structures protected by the ones in front of them
+#[derive(Component, Clone, Default, Serialize, Deserialize)]
+pub struct Invulnerable;
+
+/// Unit loses exactly one health per hit regardless of the damage, e.g. wards
+#[derive(Component, Clone, Default, Serialize, Deserialize)]
+pub struct HitCounted;
+
+/// Offensive stats that ignore part of the target's armor and magic resist
+#[derive(Component, Clone, Debug, Default, Serialize, Deserialize)]
+pub struct Penetration {
+    /// Flat armor penetration
+    pub armor_flat: f32,
+    /// Percent armor penetration (0.0 - 1.0)
+    pub armor_percent: f32,
+    /// Lethality, converted 1:1 into flat armor penetration
+    pub lethality: f32,
+    /// Flat magic penetration
+    pub magic_flat: f32,
+    /// Percent magic penetration (0.0 - 1.0)
+    pub magic_percent: f32,
+}
+
+impl Penetration {
+    /// Flat penetration against the resistance used by the damage type
+    pub fn flat(&self, damage_type: DamageType) -> f32 {
+        match damage_type {
+            DamageType::Physical => self.armor_flat + self.lethality,
+            DamageType::Magic => self.magic_flat,
+            DamageType::True => 0.0,
+        }
+    }
+
+    /// Percent penetration against the resistance used by the damage type
+    pub fn percent(&self, damage_type: DamageType) -> f32 {
+        match damage_type {
+            DamageType::Physical => self.armor_percent,
+            DamageType::Magic => self.magic_percent,
+            DamageType::True => 0.0,
+        }
+    }
+}
+
 /// Damage type enum
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 pub enum DamageType {
     /// Physical damage
     Physical,
@@ -31,6 +101,16 @@ pub enum DamageType {
     True,
 }
 
+/// What produced the damage
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum DamageOrigin {
+    /// Basic attack, applies lifesteal
+    Attack,
+    /// Ability, item or passive damage
+    #[default]
+    Spell,
+}
+
 /// Damage event containing damage source, target, damage type and value
 #[derive(EntityEvent, Debug)]
 pub struct CommandDamageCreate {
@@ -39,6 +119,8 @@ pub struct CommandDamageCreate {
     pub source: Entity,
     /// Damage type
     pub damage_type: DamageType,
+    /// What produced the damage
+    pub origin: DamageOrigin,
     /// Damage value
     pub amount: f32,
 }
@@ -48,11 +130,12 @@ pub struct EventDamageCreate {
     pub entity: Entity,
     pub source: Entity,
     pub damage_type: DamageType,
+    pub origin: DamageOrigin,
     pub damage_result: DamageResult,
 }
 
 /// Damage calculation result
-#[derive(Debug)]
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
 pub struct DamageResult {
     /// Final damage dealt
     pub final_damage: f32,
@@ -62,146 +145,293 @@ pub struct DamageResult {
     pub magic_shield_absorbed: f32,
     /// Damage reduced
     pub reduced_damage: f32,
-    /// Damage reduced by armor
+    /// Damage reduced by armor, negative when negative armor amplified the damage
     pub armor_reduced_damage: f32,
+    /// Damage reduced by magic resist, negative when negative magic resist amplified the damage
+    pub magic_resist_reduced_damage: f32,
+    /// Resistance calculation steps, None for true damage
+    pub resist: Option<ResistResult>,
     /// Original damage
     pub original_damage: f32,
 }
 
-/// Damage system - handles damage events
-pub fn on_command_damage_create(
-    trigger: On<CommandDamageCreate>,
-    mut commands: Commands,
-    mut query: Query<(&mut Health, Option<&Armor>, Option<&Buffs>)>,
-    mut q_shield_white: Query<&mut BuffShieldWhite>,
-    mut q_shield_magic: Query<&mut BuffShieldMagic>,
-    q_damage_reduction: Query<&BuffDamageReduction>,
-) {
+/// Steps of the target's armor or magic resist calculation
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct ResistResult {
+    /// Resistance of the target
+    pub base: f32,
+    /// Resistance after flat and percent reduction
+    pub after_reduction: f32,
+    /// Resistance after percent penetration
+    pub after_percent_penetration: f32,
+    /// Resistance after flat penetration, used for mitigation
+    pub effective: f32,
+    /// Multiplier applied to the incoming damage
+    pub multiplier: f32,
+}
+
+/// Resolve a resistance value in order: flat reduction, percent reduction,
+/// percent penetration, flat penetration.
+///
+/// Reduction can take the resistance below zero, penetration only down to zero.
+/// Percent modifiers are ignored while the resistance is not positive.
+pub fn calculate_resist(
+    base: f32,
+    reduction_flat: f32,
+    reduction_percent: f32,
+    penetration_percent: f32,
+    penetration_flat: f32,
+) -> ResistResult {
+    let mut resist = base - reduction_flat;
+    if resist > 0.0 {
+        resist *= 1.0 - reduction_percent.clamp(0.0, 1.0);
+    }
+    let after_reduction = resist;
+
+    if resist > 0.0 {
+        resist *= 1.0 - penetration_percent.clamp(0.0, 1.0);
+    }
+    let after_percent_penetration = resist;
+
+    if resist > 0.0 {
+        resist = (resist - penetration_flat.max(0.0)).max(0.0);
+    }
+
+    ResistResult {
+        base,
+        after_reduction,
+        after_percent_penetration,
+        effective: resist,
+        multiplier: resist_damage_multiplier(resist),
+    }
+}
+
+/// Damage multiplier for a resistance value, negative resistance amplifies damage
+pub fn resist_damage_multiplier(resist: f32) -> f32 {
+    if resist >= 0.0 {
+        100.0 / (100.0 + resist)
+    } else {
+        2.0 - 100.0 / (100.0 - resist)
+    }
+}
+
+/// Stages of the damage pipeline, run in order inside [`CommandDamageCreate`]'s observer before
+/// health is subtracted and [`EventDamageCreate`] is emitted
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum DamagePipeline {
+    /// Raise or lower the raw damage, or change its type
+    Amplify,
+    /// Adjust the resistance reduction and penetration used against the target
+    Resist,
+    /// Armor and magic resist mitigation
+    Mitigate,
+    /// Percent damage reduction
+    Reduction,
+    /// Shields absorb the remaining damage
+    Shield,
+    /// Bonus damage based on the target's health, e.g. executes and Fiora's vitals
+    Execute,
+    /// Last chance to change damage that would kill the target
+    Intercept,
+}
+
+/// Damage instance going through the [`DamagePipeline`] stages
+#[derive(Debug, Clone)]
+pub struct DamageContext {
+    pub target: Entity,
+    pub source: Entity,
+    pub damage_type: DamageType,
+    pub origin: DamageOrigin,
+    /// Damage left after the stages run so far
+    pub amount: f32,
+    /// Target health when the damage was created
+    pub health: f32,
+    pub max_health: f32,
+    /// Buffs of the target
+    pub buffs: Vec<Entity>,
+    /// Target loses exactly one health, stages leave the amount alone
+    pub hit_counted: bool,
+    pub resist_reduction_flat: f32,
+    /// Percent resistance reduction, stack with [`DamageContext::add_resist_reduction`]
+    pub resist_reduction_percent: f32,
+    pub penetration_flat: f32,
+    pub penetration_percent: f32,
+    pub result: DamageResult,
+}
+
+impl DamageContext {
+    /// Whether resistances, reductions and shields apply
+    pub fn is_mitigable(&self) -> bool {
+        !self.hit_counted && self.damage_type != DamageType::True
+    }
+
+    /// Whether the damage left would kill the target
+    pub fn is_lethal(&self) -> bool {
+        self.amount >= self.health
+    }
+
+    /// Percent reductions stack multiplicatively
+    pub fn add_resist_reduction(&mut self, flat: f32, percent: f32) {
+        self.resist_reduction_flat += flat;
+        self.resist_reduction_percent =
+            1.0 - (1.0 - self.resist_reduction_percent) * (1.0 - percent);
+    }
+}
+
+/// Damage system - runs the damage through the pipeline and applies it right away, damage
+/// dealt by a modifier is triggered through commands and runs after the current instance
+fn on_command_damage_create(trigger: On<CommandDamageCreate>, mut world: DeferredWorld) {
+    let target = trigger.event_target();
+
     debug!(
         "{:?} dealt {:.1} {:?} damage to {:?}",
-        trigger.source,
-        trigger.amount,
-        trigger.damage_type,
-        trigger.event_target(),
+        trigger.source, trigger.amount, trigger.damage_type, target,
     );
 
-    let Ok((mut health, armor, buffs)) = query.get_mut(trigger.event_target()) else {
-        debug!("Damage target entity not found {:?}", trigger.event_target());
+    let Some(health) = world.get::<Health>(target).cloned() else {
+        debug!("Damage target entity not found {:?}", target);
         return;
     };
 
-    let health_before = health.value;
-    let armor_value = armor.map(|a| a.0);
+    // Dead units waiting to respawn take no damage
+    if health.value <= 0.0 || world.get::<Invulnerable>(target).is_some() {
+        return;
+    }
 
-    let mut remaining_damage = trigger.amount;
-    let mut white_shield_absorbed = 0.0;
-    let mut magic_shield_absorbed = 0.0;
-    let mut reduced_damage = 0.0;
-    let mut armor_reduced_damage = 0.0;
+    let hit_counted = world.get::<HitCounted>(target).is_some();
+    let mut context = DamageContext {
+        target,
+        source: trigger.source,
+        damage_type: trigger.damage_type,
+        origin: trigger.origin,
+        amount: if hit_counted { 1.0 } else { trigger.amount },
+        health: health.value,
+        max_health: health.max,
+        buffs: world
+            .get::<Buffs>(target)
+            .map(|buffs| buffs.iter().collect())
+            .unwrap_or_default(),
+        hit_counted,
+        resist_reduction_flat: 0.0,
+        resist_reduction_percent: 0.0,
+        penetration_flat: 0.0,
+        penetration_percent: 0.0,
+        result: DamageResult {
+            original_damage: trigger.amount,
+            ..default()
+        },
+    };
 
-    // True damage ignores all defense mechanisms
-    if trigger.damage_type == DamageType::True {
-        health.value -= remaining_damage;
-    } else {
-        // Apply armor reduction to physical damage
-        if trigger.damage_type == DamageType::Physical {
-            if let Some(armor_val) = armor_value {
-                if armor_val > 0.0 {
-                    let damage_after_armor = remaining_damage * 100.0 / (100.0 + armor_val);
-                    armor_reduced_damage = remaining_damage - damage_after_armor;
-                    remaining_damage = damage_after_armor;
-                }
-            }
-        }
+    PipelineModifiers::<DamageContext, DamagePipeline>::run(&mut world, &mut context);
+    damage_apply(&mut world, context);
+}
 
-        // Apply damage reduction buffs
-        if let Some(target_buffs) = buffs {
-            let mut total_reduction = 0.0;
-            for buff_entity in target_buffs.iter() {
-                if let Ok(reduction) = q_damage_reduction.get(buff_entity) {
-                    if reduction.applies_to(trigger.damage_type) {
-                        // Use multiplicative stacking formula: total reduction = 1 - (1 - r1) * (1 - r2) * ...
-                        total_reduction =
-                            1.0 - (1.0 - total_reduction) * (1.0 - reduction.percentage);
-                    }
-                }
-            }
-            if total_reduction > 0.0 {
-                let reduction_amount = remaining_damage * total_reduction;
-                reduced_damage = reduction_amount;
-                remaining_damage -= reduction_amount;
-            }
-        }
+fn fixed_update_bonus_damage(
+    mut q_bonus: Query<(&mut BonusDamage, Option<&Buffs>)>,
+    q_buff_damage: Query<&BuffDamage>,
+) {
+    for (mut bonus, buffs) in q_bonus.iter_mut() {
+        let total = buffs
+            .map(|buffs| {
+                q_buff_damage
+                    .iter_many(buffs.iter())
+                    .map(|v| v.bonus_damage)
+                    .sum()
+            })
+            .unwrap_or(0.0);
 
-        // Apply shields (white shield takes priority)
-        if let Some(target_buffs) = buffs {
-            for buff_entity in target_buffs.iter() {
-                if let Ok(mut shield) = q_shield_white.get_mut(buff_entity) {
-                    let before = remaining_damage;
-                    remaining_damage = shield.absorb_damage(remaining_damage);
-                    white_shield_absorbed += before - remaining_damage;
-                    if remaining_damage <= 0.0 {
-                        break;
-                    }
-                }
-            }
-        }
+        bonus.set_if_neq(BonusDamage(total));
+    }
+}
 
-        // If magic damage and there's remaining damage, apply magic shield
-        if trigger.damage_type == DamageType::Magic && remaining_damage > 0.0 {
-            if let Some(target_buffs) = buffs {
-                for buff_entity in target_buffs.iter() {
-                    if let Ok(mut shield) = q_shield_magic.get_mut(buff_entity) {
-                        let before = remaining_damage;
-                        remaining_damage = shield.absorb_magic_damage(remaining_damage);
-                        magic_shield_absorbed += before - remaining_damage;
-                        if remaining_damage <= 0.0 {
-                            break;
-                        }
-                    }
-                }
-            }
-        }
+fn damage_penetration(world: &mut DeferredWorld, context: &mut DamageContext) {
+    if !context.is_mitigable() {
+        return;
+    }
+
+    let Some(penetration) = world.get::<Penetration>(context.source) else {
+        return;
+    };
+
+    context.penetration_flat += penetration.flat(context.damage_type);
+    context.penetration_percent = 1.0
+        - (1.0 - context.penetration_percent) * (1.0 - penetration.percent(context.damage_type));
+}
 
-        health.value -= remaining_damage;
+fn damage_mitigate(world: &mut DeferredWorld, context: &mut DamageContext) {
+    if !context.is_mitigable() {
+        return;
     }
 
-    let result = DamageResult {
-        final_damage: remaining_damage,
-        white_shield_absorbed,
-        magic_shield_absorbed,
-        reduced_damage,
-        armor_reduced_damage,
-        original_damage: trigger.amount,
+    // Apply armor to physical damage and magic resist to magic damage
+    let resist_value = match context.damage_type {
+        DamageType::Physical => world.get::<Armor>(context.target).map(|a| a.0),
+        _ => world.get::<MagicResist>(context.target).map(|m| m.0),
+    };
+    let Some(resist_value) = resist_value else {
+        return;
     };
 
+    let resist_result = calculate_resist(
+        resist_value,
+        context.resist_reduction_flat,
+        context.resist_reduction_percent,
+        context.penetration_percent,
+        context.penetration_flat,
+    );
+
+    let damage_after_resist = context.amount * resist_result.multiplier;
+    let reduced = context.amount - damage_after_resist;
+    match context.damage_type {
+        DamageType::Physical => context.result.armor_reduced_damage = reduced,
+        _ => context.result.magic_resist_reduced_damage = reduced,
+    }
+    context.amount = damage_after_resist;
+    context.result.resist = Some(resist_result);
+}
+
+/// Subtract health and emit [`EventDamageCreate`]
+fn damage_apply(world: &mut DeferredWorld, mut context: DamageContext) {
+    let Some(mut health) = world.get_mut::<Health>(context.target) else {
+        return;
+    };
+
+    let health_before = health.value;
+    let amount = context.amount.max(0.0);
+    health.value -= amount;
+    let health_after = health.value;
+    context.result.final_damage = amount;
+
+    let result = context.result;
+
     debug!(
-        "Damage applied {:?} -> {:?} type {:?} original {:.1} final {:.1} health {:.1} -> {:.1} armor reduced {:.1} white shield {:.1} magic shield {:.1} reduced {:.1}",
-        trigger.source,
-        trigger.event_target(),
-        trigger.damage_type,
+        "Damage applied {:?} -> {:?} type {:?} original {:.1} final {:.1} health {:.1} -> {:.1} armor reduced {:.1} magic resist reduced {:.1} white shield {:.1} magic shield {:.1} reduced {:.1}",
+        context.source,
+        context.target,
+        context.damage_type,
         result.original_damage,
         result.final_damage,
         health_before,
-        health.value,
+        health_after,
         result.armor_reduced_damage,
+        result.magic_resist_reduced_damage,
         result.white_shield_absorbed,
         result.magic_shield_absorbed,
         result.reduced_damage
     );
 
-    commands.trigger(EventDamageCreate {
-        entity: trigger.event_target(),
-        source: trigger.source,
-        damage_type: trigger.damage_type,
+    world.commands().trigger(EventDamageCreate {
+        entity: context.target,
+        source: context.source,
+        damage_type: context.damage_type,
+        origin: context.origin,
         damage_result: result,
     });
 
-    if health.value <= 0.0 {
+    if health_after <= 0.0 {
         debug!(
             "{:?} health dropped to {:.1}, death threshold reached",
-            trigger.event_target(),
-            health.value
+            context.target, health_after
         );
     }
 }
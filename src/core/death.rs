@@ -0,0 +1,243 @@
+use std::collections::{HashMap, VecDeque};
+
+use bevy::prelude::*;
+use lol_core::Team;
+
+use crate::{
+    AbilityResource, Champion, CommandAttackAutoStop, CommandMovement, DamageType,
+    EventDamageCreate, EventDead, Health, Level, MovementAction, State,
+};
+
+/// Damage older than this is dropped from the death recap
+pub const DEATH_RECAP_WINDOW: f32 = 10.0;
+
+/// Base respawn time in seconds for champion levels 1 to 18
+const RESPAWN_TIMES: [f32; 18] = [
+    10.0, 10.0, 12.0, 12.0, 14.0, 16.0, 20.0, 25.0, 28.0, 32.5, 35.0, 37.5, 40.0, 42.5, 45.0, 47.5,
+    50.0, 52.5,
+];
+
+#[derive(Default)]
+pub struct PluginDeath;
+
+impl Plugin for PluginDeath {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<Fountains>();
+
+        app.add_observer(on_event_damage_create);
+        app.add_observer(on_event_dead);
+
+        app.add_systems(FixedUpdate, fixed_update);
+    }
+}
+
+/// Respawn position of each team, read from the map placeables
+#[derive(Resource, Default)]
+pub struct Fountains(pub HashMap<Team, Vec3>);
+
+/// Unit is dead and waiting to respawn, blocks input and targeting
+#[derive(Component, Debug, Clone)]
+pub struct Dead {
+    pub respawn_timer: Timer,
+}
+
+/// Damage taken within the last [`DEATH_RECAP_WINDOW`] seconds
+#[derive(Component, Default, Debug)]
+pub struct DamageHistory(pub VecDeque<DamageRecord>);
+
+#[derive(Debug, Clone)]
+pub struct DamageRecord {
+    pub source: Entity,
+    pub damage_type: DamageType,
+    pub amount: f32,
+    pub time: f32,
+}
+
+/// Damage that led to the champion's last death, grouped by source
+#[derive(Component, Default, Debug, Clone)]
+pub struct DeathRecap {
+    pub entries: Vec<DeathRecapEntry>,
+    pub total: f32,
+}
+
+#[derive(Debug, Clone)]
+pub struct DeathRecapEntry {
+    pub source: Entity,
+    pub physical: f32,
+    pub magic: f32,
+    pub true_damage: f32,
+    pub hits: u32,
+}
+
+impl DeathRecapEntry {
+    pub fn total(&self) -> f32 {
+        self.physical + self.magic + self.true_damage
+    }
+}
+
+#[derive(EntityEvent, Debug)]
+pub struct EventRespawn {
+    pub entity: Entity,
+}
+
+/// Respawn time in seconds for a champion of the given level
+pub fn respawn_time(level: u32) -> f32 {
+    let index = (level.max(1) as usize - 1).min(RESPAWN_TIMES.len() - 1);
+    RESPAWN_TIMES[index]
+}
+
+impl DamageHistory {
+    pub fn prune(&mut self, now: f32) {
+        while let Some(record) = self.0.front() {
+            if now - record.time <= DEATH_RECAP_WINDOW {
+                break;
+            }
+            self.0.pop_front();
+        }
+    }
+
+    pub fn recap(&self, now: f32) -> DeathRecap {
+        let mut entries: Vec<DeathRecapEntry> = Vec::new();
+
+        for record in self
+            .0
+            .iter()
+            .filter(|record| now - record.time <= DEATH_RECAP_WINDOW)
+        {
+            let index = match entries.iter().position(|v| v.source == record.source) {
+                Some(index) => index,
+                None => {
+                    entries.push(DeathRecapEntry {
+                        source: record.source,
+                        physical: 0.0,
+                        magic: 0.0,
+                        true_damage: 0.0,
+                        hits: 0,
+                    });
+                    entries.len() - 1
+                }
+            };
+
+            let entry = &mut entries[index];
+            match record.damage_type {
+                DamageType::Physical => entry.physical += record.amount,
+                DamageType::Magic => entry.magic += record.amount,
+                DamageType::True => entry.true_damage += record.amount,
+            }
+            entry.hits += 1;
+        }
+
+        entries.sort_by(|a, b| b.total().total_cmp(&a.total()));
+
+        DeathRecap {
+            total: entries.iter().map(|v| v.total()).sum(),
+            entries,
+        }
+    }
+}
+
+fn on_event_damage_create(
+    trigger: On<EventDamageCreate>,
+    mut q_history: Query<&mut DamageHistory>,
+    time: Res<Time>,
+) {
+    let Ok(mut history) = q_history.get_mut(trigger.event_target()) else {
+        return;
+    };
+
+    let now = time.elapsed_secs();
+    history.prune(now);
+    history.0.push_back(DamageRecord {
+        source: trigger.source,
+        damage_type: trigger.damage_type,
+        amount: trigger.damage_result.final_damage,
+        time: now,
+    });
+}
+
+fn on_event_dead(
+    trigger: On<EventDead>,
+    mut commands: Commands,
+    q_champion: Query<(Option<&Level>, Option<&DamageHistory>), With<Champion>>,
+    time: Res<Time>,
+) {
+    let entity = trigger.event_target();
+
+    let Ok((level, history)) = q_champion.get(entity) else {
+        return;
+    };
+
+    let duration = respawn_time(level.map(|v| v.value).unwrap_or(1));
+    debug!("{:?} died, respawning in {:.1}s", entity, duration);
+
+    commands.trigger(CommandAttackAutoStop { entity });
+    commands.trigger(CommandMovement {
+        entity,
+        priority: i32::MAX,
+        action: MovementAction::Stop,
+    });
+
+    let recap = history
+        .map(|v| v.recap(time.elapsed_secs()))
+        .unwrap_or_default();
+
+    commands.entity(entity).insert((
+        Dead {
+            respawn_timer: Timer::from_seconds(duration, TimerMode::Once),
+        },
+        DamageHistory::default(),
+        recap,
+        State::Dead,
+    ));
+}
+
+fn fixed_update(
+    mut commands: Commands,
+    mut q_dead: Query<(
+        Entity,
+        &mut Dead,
+        &mut Health,
+        &mut Transform,
+        Option<&mut State>,
+        Option<&Team>,
+        Option<&mut AbilityResource>,
+        Has<Champion>,
+    )>,
+    res_fountains: Res<Fountains>,
+    time: Res<Time<Fixed>>,
+) {
+    for (entity, mut dead, mut health, mut transform, state, team, ability_resource, is_champion) in
+        q_dead.iter_mut()
+    {
+        dead.respawn_timer.tick(time.delta());
+
+        if !dead.respawn_timer.is_finished() {
+            continue;
+        }
+
+        health.value = health.max;
+        if let Some(mut ability_resource) = ability_resource {
+            ability_resource.value = if ability_resource.ar_type.rules().starts_empty {
+                0.0
+            } else {
+                ability_resource.max
+            };
+        }
+
+        // Champions respawn at their fountain, structures respawn in place
+        if is_champion {
+            if let Some(position) = team.and_then(|team| res_fountains.0.get(team)) {
+                transform.translation = *position;
+            }
+        }
+
+        if let Some(mut state) = state {
+            *state = State::Idle;
+        }
+
+        debug!("{:?} respawned at {:?}", entity, transform.translation);
+
+        commands.entity(entity).remove::<Dead>();
+        commands.trigger(EventRespawn { entity });
+    }
+}
@@ -1,5 +1,6 @@
 use bevy::prelude::*;
 use lol_config::ConfigGame;
+use lol_core::Team;
 
 use crate::CommandCharacterSpawn;
 
@@ -17,9 +18,23 @@ impl Plugin for PluginGame {
 
         app.add_systems(Startup, startup.in_set(GameStartupSystems::SpawnChampion));
         app.add_systems(FixedLast, fixed_update_frame);
+
+        app.add_observer(on_event_game_end);
     }
 }
 
+/// A nexus has been destroyed
+#[derive(Event, Debug, Clone)]
+pub struct EventGameEnd {
+    pub winner: Team,
+}
+
+/// Result of the finished game
+#[derive(Resource, Debug, Clone)]
+pub struct GameOver {
+    pub winner: Team,
+}
+
 #[derive(Resource, Default)]
 pub struct FixedFrameCount(pub u32);
 
@@ -37,3 +52,21 @@ fn startup(mut commands: Commands, config_game: Res<ConfigGame>) {
         });
     }
 }
+
+fn on_event_game_end(
+    trigger: On<EventGameEnd>,
+    mut commands: Commands,
+    q_window: Query<(), With<Window>>,
+    mut app_exit: MessageWriter<AppExit>,
+) {
+    info!("Game ended, {:?} wins", trigger.winner);
+
+    commands.insert_resource(GameOver {
+        winner: trigger.winner.clone(),
+    });
+
+    // Headless episodes end with the game, rendered games stay on the end screen
+    if q_window.is_empty() {
+        app_exit.write(AppExit::Success);
+    }
+}
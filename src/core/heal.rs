@@ -0,0 +1,188 @@
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::{BuffGrievousWounds, Buffs, DamageOrigin, EventDamageCreate, Health};
+
+/// Healing system plugin
+#[derive(Default)]
+pub struct PluginHeal;
+
+impl Plugin for PluginHeal {
+    fn build(&self, app: &mut App) {
+        app.add_observer(on_command_heal);
+        app.add_observer(on_event_damage_create);
+    }
+}
+
+/// Heal and shield power of a unit, 0.1 means heals it grants are 10% stronger
+#[derive(Component, Clone, Default, Serialize, Deserialize)]
+pub struct HealShieldPower(pub f32);
+
+/// Ratio of basic attack damage returned as health
+#[derive(Component, Clone, Default, Serialize, Deserialize)]
+pub struct LifeSteal(pub f32);
+
+/// Ratio of all damage returned as health
+#[derive(Component, Clone, Default, Serialize, Deserialize)]
+pub struct Omnivamp(pub f32);
+
+/// What produced the heal
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum HealKind {
+    /// Ability, item or passive heal, amplified by the source's heal and shield power
+    #[default]
+    Ability,
+    /// Lifesteal from a basic attack
+    LifeSteal,
+    /// Omnivamp from any damage
+    Omnivamp,
+}
+
+/// Heal event containing heal source, target and value
+#[derive(EntityEvent, Debug)]
+pub struct CommandHeal {
+    pub entity: Entity,
+    /// Heal source entity
+    pub source: Entity,
+    /// What produced the heal
+    pub kind: HealKind,
+    /// Heal value
+    pub amount: f32,
+}
+
+#[derive(EntityEvent, Debug)]
+pub struct EventHeal {
+    pub entity: Entity,
+    pub source: Entity,
+    pub kind: HealKind,
+    pub heal_result: HealResult,
+}
+
+/// Heal calculation result
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct HealResult {
+    /// Health actually restored
+    pub final_heal: f32,
+    /// Heal added by the source's heal and shield power
+    pub heal_power_bonus: f32,
+    /// Heal removed by grievous wounds
+    pub grievous_wounds_reduced: f32,
+    /// Heal exceeding the target's maximum health
+    pub overheal: f32,
+    /// Original heal
+    pub original_heal: f32,
+}
+
+/// Heal system - handles heal events
+pub fn on_command_heal(
+    trigger: On<CommandHeal>,
+    mut commands: Commands,
+    mut query: Query<(&mut Health, Option<&Buffs>)>,
+    q_heal_shield_power: Query<&HealShieldPower>,
+    q_grievous_wounds: Query<&BuffGrievousWounds>,
+) {
+    let entity = trigger.event_target();
+
+    let Ok((mut health, buffs)) = query.get_mut(entity) else {
+        debug!("Heal target entity not found {:?}", entity);
+        return;
+    };
+
+    if health.value <= 0.0 {
+        return;
+    }
+
+    let mut remaining_heal = trigger.amount;
+    let mut heal_power_bonus = 0.0;
+    let mut grievous_wounds_reduced = 0.0;
+
+    if trigger.kind == HealKind::Ability {
+        if let Ok(heal_shield_power) = q_heal_shield_power.get(trigger.source) {
+            heal_power_bonus = remaining_heal * heal_shield_power.0;
+            remaining_heal += heal_power_bonus;
+        }
+    }
+
+    // Grievous wounds does not stack, the strongest one applies
+    if let Some(target_buffs) = buffs {
+        let reduction = target_buffs
+            .iter()
+            .filter_map(|buff| q_grievous_wounds.get(buff).ok())
+            .map(|grievous_wounds| grievous_wounds.percentage)
+            .fold(0.0, f32::max);
+
+        grievous_wounds_reduced = remaining_heal * reduction;
+        remaining_heal -= grievous_wounds_reduced;
+    }
+
+    let health_before = health.value;
+    health.value = (health.value + remaining_heal).min(health.max);
+    let final_heal = health.value - health_before;
+
+    let result = HealResult {
+        final_heal,
+        heal_power_bonus,
+        grievous_wounds_reduced,
+        overheal: remaining_heal - final_heal,
+        original_heal: trigger.amount,
+    };
+
+    debug!(
+        "Heal applied {:?} -> {:?} kind {:?} original {:.1} final {:.1} health {:.1} -> {:.1} heal power {:.1} grievous wounds {:.1}",
+        trigger.source,
+        entity,
+        trigger.kind,
+        result.original_heal,
+        result.final_heal,
+        health_before,
+        health.value,
+        result.heal_power_bonus,
+        result.grievous_wounds_reduced,
+    );
+
+    commands.trigger(EventHeal {
+        entity,
+        source: trigger.source,
+        kind: trigger.kind,
+        heal_result: result,
+    });
+}
+
+/// Lifesteal and omnivamp - heal the damage source by a ratio of the damage dealt
+fn on_event_damage_create(
+    trigger: On<EventDamageCreate>,
+    mut commands: Commands,
+    q_life_steal: Query<&LifeSteal>,
+    q_omnivamp: Query<&Omnivamp>,
+) {
+    let source = trigger.source;
+    let final_damage = trigger.damage_result.final_damage;
+
+    if final_damage <= 0.0 || source == trigger.event_target() {
+        return;
+    }
+
+    if trigger.origin == DamageOrigin::Attack {
+        if let Ok(life_steal) = q_life_steal.get(source) {
+            if life_steal.0 > 0.0 {
+                commands.trigger(CommandHeal {
+                    entity: source,
+                    source,
+                    kind: HealKind::LifeSteal,
+                    amount: final_damage * life_steal.0,
+                });
+            }
+        }
+    }
+
+    if let Ok(omnivamp) = q_omnivamp.get(source) {
+        if omnivamp.0 > 0.0 {
+            commands.trigger(CommandHeal {
+                entity: source,
+                source,
+                kind: HealKind::Omnivamp,
+                amount: final_damage * omnivamp.0,
+            });
+        }
+    }
+}
@@ -1,7 +1,7 @@
 use bevy::prelude::*;
 use serde::{Deserialize, Serialize};
 
-use crate::EventDamageCreate;
+use crate::{Dead, EventDamageCreate};
 
 #[derive(Default)]
 pub struct PluginLife;
@@ -20,6 +20,10 @@ pub struct Health {
     pub max: f32,
 }
 
+/// Units that are kept on death and respawn later instead of being despawned
+#[derive(Component, Default, Clone)]
+pub struct Respawnable;
+
 #[derive(EntityEvent, Debug)]
 pub struct EventDead {
     pub entity: Entity,
@@ -50,17 +54,20 @@ pub fn spawn_event(mut commands: Commands, q_alive: Query<Entity, Added<Health>>
 fn on_event_damage_create(
     trigger: On<EventDamageCreate>,
     mut commands: Commands,
-    q_health: Query<&Health>,
+    q_health: Query<(&Health, Has<Respawnable>), Without<Dead>>,
 ) {
     let entity = trigger.event_target();
 
-    let Ok(health) = q_health.get(entity) else {
+    let Ok((health, respawnable)) = q_health.get(entity) else {
         return;
     };
 
     if health.value <= 0.0 {
         debug!("{:?} died", entity);
         commands.trigger(EventDead { entity });
-        commands.entity(entity).despawn();
+
+        if !respawnable {
+            commands.entity(entity).despawn();
+        }
     }
 }
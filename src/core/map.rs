@@ -9,8 +9,9 @@ use lol_config::{ConfigMapGeo, HashKey, LoadHashKeyTrait};
 use lol_core::{Lane, Team};
 
 use crate::{
-    get_standard, Action, AssetServerLoadLeague, CommandAction, CommandCharacterSpawn,
-    CommandLoadPropBin, Controller, Loading, PropPath, Turret,
+    get_standard, Action, AssetServerLoadLeague, CampKind, CommandAction, CommandCharacterSpawn,
+    CommandLoadPropBin, Controller, Fountains, JungleCamp, Loading, PropPath, ResourceJungleCamps,
+    Turret, TurretPlating,
 };
 
 pub const MAP_WIDTH: f32 = 14400.0;
@@ -104,6 +105,8 @@ fn update_spawn_map_character(
     map_name: Res<MapName>,
     res_assets_map_container: Res<Assets<MapContainer>>,
     res_assets_map_placeable_container: Res<Assets<MapPlaceableContainer>>,
+    res_jungle_camps: Res<ResourceJungleCamps>,
+    mut res_fountains: ResMut<Fountains>,
 ) {
     let Some(map_container) =
         res_assets_map_container.get(HashKey::from(&map_name.get_materials_path()))
@@ -133,8 +136,22 @@ fn update_spawn_map_character(
                         ))
                         .id();
 
-                    if matches!(unk0xad65d8c4.definition.r#type, Some(0)) {
-                        commands.entity(entity).insert(Turret);
+                    let definition = &unk0xad65d8c4.definition;
+
+                    if let Some(lane) = definition
+                        .unk_0xdbde2288
+                        .as_ref()
+                        .and_then(|v| v.first())
+                        .and_then(|v| Lane::from_index(v.lane))
+                    {
+                        commands.entity(entity).insert(lane);
+                    }
+
+                    // Inhibitors and the nexus are tagged once their character record is loaded
+                    if matches!(definition.r#type, Some(0)) {
+                        commands
+                            .entity(entity)
+                            .insert((Turret, TurretPlating::default()));
                     }
 
                     commands.trigger(CommandCharacterSpawn {
@@ -143,6 +160,37 @@ fn update_spawn_map_character(
                         skin: (&unk0xad65d8c4.definition.skin).into(),
                     });
                 }
+                // Jungle camp, monsters spawn from its timers
+                EnumMap::Unk0xd178749c(unk0xd178749c) => {
+                    let definition = &unk0xd178749c.definition;
+                    let Some(kind) = definition
+                        .minimap_icon
+                        .as_deref()
+                        .and_then(CampKind::from_name)
+                    else {
+                        debug!("Unknown jungle camp {:?}", definition.minimap_icon);
+                        continue;
+                    };
+                    let Some(config) = res_jungle_camps.0.get(&kind) else {
+                        debug!("No spawn timers configured for {:?} camp", kind);
+                        continue;
+                    };
+
+                    commands.spawn((
+                        Transform::from_matrix(unk0xd178749c.transform),
+                        JungleCamp::new(kind, config, definition.stop_spawn_time_secs),
+                    ));
+                }
+                // Team spawn point, champions respawn here
+                EnumMap::Unk0xeb997689(unk0xeb997689) => {
+                    let Some(team) = unk0xeb997689.definition.team else {
+                        continue;
+                    };
+                    let transform = Transform::from_matrix(unk0xeb997689.transform);
+                    res_fountains
+                        .0
+                        .insert(Team::from(team), transform.translation);
+                }
                 _ => {}
             }
         }
@@ -1,14 +1,16 @@
 use bevy::animation::AnimationTarget;
 use bevy::color::palettes::tailwind::RED_500;
 use bevy::prelude::*;
-use league_core::{EnumMovement, SpellObject};
+use league_core::{EnumCastOnHit, EnumHeightSolver, EnumMovement, SpellObject};
 use league_utils::hash_joint;
-use lol_config::{HashKey, LoadHashKeyTrait};
+use lol_config::{ConfigNavigationGrid, HashKey, LoadHashKeyTrait};
+use lol_core::Team;
 use serde::{Deserialize, Serialize};
 
 use crate::{
-    CommandDamageCreate, CommandMovement, CommandSkinParticleSpawn, Damage, DamageType,
-    DebugSphere, EntityCommandsTrigger, EventMovementEnd, Movement, MovementAction, MovementWay,
+    rank_value, Bounding, CommandDamageCreate, CommandMovement, CommandSkinParticleSpawn,
+    DamageOrigin, DamageType, Dead, DebugSphere, EntityCommandsTrigger, EventMovementEnd, Health,
+    Movement, MovementAction, MovementWay, ResourceGrid,
 };
 
 #[derive(Default)]
@@ -18,8 +20,9 @@ impl Plugin for PluginMissile {
     fn build(&self, app: &mut App) {
         app.add_observer(on_command_missile_create);
         app.add_observer(on_event_movement_end);
+        app.add_observer(on_command_skillshot_create);
 
-        app.add_systems(FixedUpdate, fixed_update);
+        app.add_systems(FixedUpdate, (fixed_update, fixed_update_skillshot));
     }
 }
 
@@ -37,6 +40,8 @@ pub struct MissileState {
     /// Attack target
     pub target: Option<Entity>,
     pub target_bone: Option<Entity>,
+    /// Attack damage dealt on impact
+    pub damage: f32,
 }
 
 #[derive(EntityEvent, Debug)]
@@ -44,6 +49,120 @@ pub struct CommandMissileCreate {
     pub entity: Entity,
     pub target: Entity,
     pub spell_key: HashKey<SpellObject>,
+    /// Attack damage when the missile was fired, later changes don't affect it
+    pub damage: f32,
+}
+
+/// Non-targeted missile flying in a straight line from its cast position
+#[derive(Component, Debug, Clone)]
+pub struct Skillshot {
+    pub source: Entity,
+    pub team: Team,
+    pub key: HashKey<SpellObject>,
+    pub direction: Vec2,
+    pub spec: SkillshotSpec,
+    pub speed: f32,
+    pub traveled: f32,
+    pub hit: Vec<Entity>,
+}
+
+/// Flight parameters of a skillshot read from the spell's missile spec
+#[derive(Debug, Clone, PartialEq)]
+pub struct SkillshotSpec {
+    /// Full width of the missile
+    pub width: f32,
+    pub range: f32,
+    pub initial_speed: f32,
+    pub acceleration: f32,
+    pub min_speed: f32,
+    pub max_speed: f32,
+    /// Keeps flying after hitting a unit
+    pub pierce: bool,
+    /// Destroyed when entering a wall cell of the navigation grid
+    pub destroy_on_wall: bool,
+}
+
+impl SkillshotSpec {
+    pub fn from_spell_object(spell_object: &SpellObject, level: usize) -> Option<Self> {
+        let spell = spell_object.m_spell.as_ref()?;
+        let missile_spec = spell.m_missile_spec.as_ref();
+
+        let range = rank_value(spell.cast_range.as_ref(), level)?;
+
+        let width = missile_spec
+            .and_then(|v| v.m_missile_width)
+            .or(spell.m_line_width)
+            .unwrap_or(0.0);
+
+        let pierce = !missile_spec
+            .and_then(|v| v.behaviors.as_ref())
+            .is_some_and(|v| v.iter().any(|v| matches!(v, EnumCastOnHit::DestroyOnHit)));
+
+        // Missiles following the terrain height can't climb over walls
+        let destroy_on_wall = matches!(
+            missile_spec.and_then(|v| v.height_solver.as_ref()),
+            Some(EnumHeightSolver::FollowTerrainHeightSolver(_))
+        );
+
+        let missile_speed = spell.missile_speed.unwrap_or(0.0);
+
+        let (initial_speed, acceleration, min_speed, max_speed) =
+            match missile_spec.map(|v| &v.movement_component) {
+                Some(EnumMovement::AcceleratingMovement(movement)) => (
+                    movement.m_initial_speed.unwrap_or(movement.m_max_speed),
+                    movement.m_acceleration.unwrap_or(0.0),
+                    movement.m_min_speed.unwrap_or(0.0),
+                    movement.m_max_speed,
+                ),
+                Some(EnumMovement::FixedSpeedMovement(movement)) => {
+                    let speed = movement.m_speed.unwrap_or(missile_speed);
+                    (speed, 0.0, speed, speed)
+                }
+                _ => (missile_speed, 0.0, missile_speed, missile_speed),
+            };
+
+        if max_speed <= 0.0 {
+            return None;
+        }
+
+        Some(Self {
+            width,
+            range,
+            initial_speed,
+            acceleration,
+            min_speed,
+            max_speed,
+            pierce,
+            destroy_on_wall,
+        })
+    }
+}
+
+/// Fire a skillshot from `source` towards `point`, `entity` becomes the missile so callers can
+/// spawn it with extra components first
+#[derive(EntityEvent, Debug)]
+pub struct CommandSkillshotCreate {
+    pub entity: Entity,
+    pub source: Entity,
+    pub spell_key: HashKey<SpellObject>,
+    pub level: usize,
+    pub point: Vec2,
+}
+
+/// A missile reached its target or a skillshot collided with an enemy
+#[derive(EntityEvent, Debug, Clone)]
+pub struct EventMissileHit {
+    pub entity: Entity,
+    pub source: Entity,
+    pub target: Entity,
+    pub spell_key: HashKey<SpellObject>,
+}
+
+/// A skillshot reached its range or was destroyed by terrain
+#[derive(EntityEvent, Debug, Clone)]
+pub struct EventSkillshotEnd {
+    pub entity: Entity,
+    pub source: Entity,
 }
 
 fn fixed_update(
@@ -152,6 +271,7 @@ fn on_command_missile_create(
                 source: entity,
                 target: Some(trigger.target),
                 target_bone: Some(end_entity),
+                damage: trigger.damage,
             },
             Transform::from_translation(translation),
             Movement { speed },
@@ -186,7 +306,7 @@ fn on_event_movement_end(
     trigger: On<EventMovementEnd>,
     mut commands: Commands,
     q_missile: Query<&MissileState>,
-    q_damage: Query<&Damage>,
+    q_missile_key: Query<&Missile>,
 ) {
     let Ok(state) = q_missile.get(trigger.entity) else {
         return;
@@ -198,17 +318,168 @@ fn on_event_movement_end(
         return;
     };
 
-    if let Ok(damage) = q_damage.get(state.source) {
-        debug!("{} deals {} damage to {}", state.source, damage.0, target);
-        commands.try_trigger(CommandDamageCreate {
-            entity: target,
-            source: state.source,
-            damage_type: DamageType::Physical,
-            amount: damage.0,
+    let Ok(missile) = q_missile_key.get(trigger.entity) else {
+        return;
+    };
+    commands.trigger(EventMissileHit {
+        entity: trigger.entity,
+        source: state.source,
+        target,
+        spell_key: missile.key,
+    });
+
+    debug!(
+        "{} deals {} damage to {}",
+        state.source, state.damage, target
+    );
+    commands.try_trigger(CommandDamageCreate {
+        entity: target,
+        source: state.source,
+        damage_type: DamageType::Physical,
+        origin: DamageOrigin::Attack,
+        amount: state.damage,
+    });
+}
+
+fn on_command_skillshot_create(
+    trigger: On<CommandSkillshotCreate>,
+    mut commands: Commands,
+    res_assets_spell_object: Res<Assets<SpellObject>>,
+    q_source: Query<(&Transform, &Team)>,
+) {
+    let entity = trigger.event_target();
+
+    let Some(spell_object) = res_assets_spell_object.load_hash(trigger.spell_key) else {
+        commands.entity(entity).despawn();
+        return;
+    };
+    let Some(spec) = SkillshotSpec::from_spell_object(spell_object, trigger.level) else {
+        debug!("{:?} has no skillshot missile spec", trigger.spell_key);
+        commands.entity(entity).despawn();
+        return;
+    };
+    let Ok((transform, team)) = q_source.get(trigger.source) else {
+        commands.entity(entity).despawn();
+        return;
+    };
+
+    let position = transform.translation.xz();
+    let direction = (trigger.point - position)
+        .try_normalize()
+        .unwrap_or(transform.forward().xz().normalize());
+
+    debug!("{} fires skillshot {:?}", trigger.source, trigger.spell_key);
+    commands.entity(entity).insert((
+        Skillshot {
+            source: trigger.source,
+            team: team.clone(),
+            key: trigger.spell_key,
+            direction,
+            speed: spec.initial_speed,
+            spec,
+            traveled: 0.0,
+            hit: Vec::new(),
+        },
+        Transform::from_translation(transform.translation)
+            .looking_to(Vec3::new(direction.x, 0.0, direction.y), Vec3::Y),
+    ));
+
+    let spell = spell_object.m_spell.as_ref().unwrap();
+    if let Some(particle) = spell.m_missile_effect_key {
+        commands.trigger(CommandSkinParticleSpawn {
+            entity,
+            hash: particle,
+        });
+    } else {
+        commands.entity(entity).insert(DebugSphere {
+            color: RED_500.into(),
+            radius: spell.m_line_width.unwrap_or(10.0) / 2.0,
         });
     }
 }
 
+fn fixed_update_skillshot(
+    mut commands: Commands,
+    mut q_skillshot: Query<(Entity, &mut Skillshot, &mut Transform)>,
+    q_target: Query<
+        (Entity, &Transform, &Team, Option<&Bounding>),
+        (With<Health>, Without<Dead>, Without<Skillshot>),
+    >,
+    res_grid: Option<Res<ResourceGrid>>,
+    res_assets_grid: Option<Res<Assets<ConfigNavigationGrid>>>,
+    time: Res<Time>,
+) {
+    let grid = match (&res_grid, &res_assets_grid) {
+        (Some(res_grid), Some(assets)) => assets.get(&res_grid.0),
+        _ => None,
+    };
+
+    let dt = time.delta_secs();
+
+    for (entity, mut skillshot, mut transform) in q_skillshot.iter_mut() {
+        let spec = &skillshot.spec;
+        let speed =
+            (skillshot.speed + spec.acceleration * dt).clamp(spec.min_speed, spec.max_speed);
+        let step = (speed * dt).min(spec.range - skillshot.traveled);
+        skillshot.speed = speed;
+
+        let start = transform.translation.xz();
+        let end = start + skillshot.direction * step;
+
+        // Stop at the first wall so units behind it can't be hit
+        let wall = grid
+            .filter(|_| skillshot.spec.destroy_on_wall)
+            .and_then(|grid| grid.raycast_walls(start, end));
+        let (end, step) = wall.map_or((end, step), |hit| (hit.position, hit.distance));
+
+        let mut hits: Vec<(f32, Entity)> = q_target
+            .iter()
+            .filter(|(target, _, team, _)| {
+                **team != skillshot.team && !skillshot.hit.contains(target)
+            })
+            .filter_map(|(target, target_transform, _, bounding)| {
+                let position = target_transform.translation.xz();
+                let radius = skillshot.spec.width / 2.0 + bounding.map_or(0.0, |v| v.radius);
+                let along = (position - start).dot(skillshot.direction).clamp(0.0, step);
+                let closest = start + skillshot.direction * along;
+                (closest.distance(position) <= radius).then_some((along, target))
+            })
+            .collect();
+        hits.sort_by(|a, b| a.0.total_cmp(&b.0));
+
+        if !skillshot.spec.pierce {
+            hits.truncate(1);
+        }
+
+        for &(_, target) in hits.iter() {
+            skillshot.hit.push(target);
+            commands.trigger(EventMissileHit {
+                entity,
+                source: skillshot.source,
+                target,
+                spell_key: skillshot.key,
+            });
+        }
+
+        transform.translation.x = end.x;
+        transform.translation.z = end.y;
+        skillshot.traveled += step;
+
+        if !skillshot.spec.pierce && !hits.is_empty() {
+            commands.entity(entity).despawn();
+            continue;
+        }
+
+        if wall.is_some() || skillshot.traveled >= skillshot.spec.range {
+            commands.trigger(EventSkillshotEnd {
+                entity,
+                source: skillshot.source,
+            });
+            commands.entity(entity).despawn();
+        }
+    }
+}
+
 fn find_bone_translation(
     entity: Entity,
     bone_name: &str,
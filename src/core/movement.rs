@@ -7,11 +7,14 @@ use lol_config::ConfigNavigationGrid;
 use serde::{Deserialize, Serialize};
 
 use crate::{
-    get_nav_path_with_debug, is_path_blocked, world_pos_to_grid_xy, ArbitrationPipelinePlugin,
-    Bounding, CommandRotate, FinalDecision, LastDecision, NavigationDebug, NavigationStats,
-    PipelineStages, RequestBuffer, ResourceGrid,
+    get_nav_path_with_debug, update_avoidance, world_pos_to_grid_xy, ArbitrationPipelinePlugin,
+    AvoidanceState, Bounding, CommandRotate, FinalDecision, LastDecision, NavigationDebug,
+    NavigationHierarchies, NavigationStats, PipelineStages, RequestBuffer, ResourceGrid,
 };
 
+/// Seconds before a path blocked by other units is checked again, they usually move out of the way
+const BLOCKED_PATH_RECHECK: f32 = 0.25;
+
 #[derive(Default)]
 pub struct PluginMovement;
 
@@ -27,6 +30,9 @@ impl Plugin for PluginMovement {
                 reduce_movement_by_priority.in_set(MovementPipeline::Reduce),
                 (
                     apply_final_movement_decision.run_if(resource_exists::<ResourceGrid>),
+                    update_avoidance
+                        .after(apply_final_movement_decision)
+                        .before(update_path_movement),
                     update_path_movement,
                 )
                     .in_set(MovementPipeline::Apply),
@@ -172,13 +178,21 @@ fn calculate_and_set_exclude_cells(grid: &mut ConfigNavigationGrid, entity_pos:
 
 fn update_path_movement(
     mut commands: Commands,
-    mut query: Query<(Entity, &Movement, &mut MovementState), Without<MovementBlock>>,
+    mut query: Query<
+        (
+            Entity,
+            &Movement,
+            &mut MovementState,
+            Option<&AvoidanceState>,
+        ),
+        Without<MovementBlock>,
+    >,
     mut q_transform: Query<&mut Transform>,
     time: Res<Time<Fixed>>,
 ) {
     let dt = time.delta_secs();
 
-    for (entity, movement, mut movement_state) in query.iter_mut() {
+    for (entity, movement, mut movement_state, avoidance) in query.iter_mut() {
         if movement_state.completed || movement_state.path.is_empty() {
             continue;
         }
@@ -191,6 +205,11 @@ fn update_path_movement(
 
         let mut last_direction = Vec2::ZERO;
 
+        if let Some(velocity) = avoidance.and_then(|v| v.steering()) {
+            last_direction = steer_along_path(&mut movement_state, &mut transform, velocity * dt);
+            remaining_distance_this_frame = 0.0;
+        }
+
         while remaining_distance_this_frame > 0.0 {
             let target = match movement_state.path.get(movement_state.current_target_index) {
                 Some(p) => *p,
@@ -225,14 +244,20 @@ fn update_path_movement(
                 let new_pos_xz = current_pos_xz + last_direction * remaining_distance_this_frame;
                 let new_y = transform.translation.y.lerp(target.y, move_fraction);
 
-                debug!("{} moving a small step {}", entity, remaining_distance_this_frame);
+                debug!(
+                    "{} moving a small step {}",
+                    entity, remaining_distance_this_frame
+                );
                 transform.translation.x = new_pos_xz.x;
                 transform.translation.z = new_pos_xz.y;
                 transform.translation.y = new_y;
 
                 remaining_distance_this_frame = 0.0;
             } else {
-                debug!("{} moving the last small step to reach turning point {}", entity, target);
+                debug!(
+                    "{} moving the last small step to reach turning point {}",
+                    entity, target
+                );
                 transform.translation.x = target.x;
                 transform.translation.z = target.z;
                 transform.translation.y = target.y;
@@ -274,6 +299,33 @@ fn update_path_movement(
     }
 }
 
+/// Move by the avoidance step instead of straight at the turning point, turning points within
+/// the step count as reached
+fn steer_along_path(
+    movement_state: &mut MovementState,
+    transform: &mut Transform,
+    step: Vec2,
+) -> Vec2 {
+    let position = transform.translation.xz() + step;
+    transform.translation.x = position.x;
+    transform.translation.z = position.y;
+
+    while let Some(target) = movement_state.path.get(movement_state.current_target_index) {
+        if target.xz().distance(position) > step.length() {
+            break;
+        }
+
+        if movement_state.current_target_index + 1 >= movement_state.path.len() {
+            transform.translation = *target;
+            movement_state.completed = true;
+            break;
+        }
+        movement_state.current_target_index += 1;
+    }
+
+    step.normalize_or_zero()
+}
+
 fn reduce_movement_by_priority(
     mut commands: Commands,
     query: Query<(
@@ -340,10 +392,15 @@ fn apply_final_movement_decision(
     )>,
     res_grid: Res<ResourceGrid>,
     mut assets_grid: ResMut<Assets<ConfigNavigationGrid>>,
+    mut hierarchies: ResMut<NavigationHierarchies>,
     mut stats: ResMut<NavigationStats>,
     mut nav_debug: ResMut<NavigationDebug>,
     time: Res<Time>,
 ) {
+    // Taking the grid mutably marks the asset as changed
+    if query.is_empty() {
+        return;
+    }
     let Some(grid) = assets_grid.get_mut(&res_grid.0) else {
         return;
     };
@@ -361,23 +418,35 @@ fn apply_final_movement_decision(
                                 bounding.radius,
                             );
                         };
+                        // Bigger units need more room around walls
+                        let clearance =
+                            grid.get_required_clearance(bounding.map_or(0.0, |v| v.radius));
 
                         stats.exclude_time += start.elapsed();
                         stats.exclude_count += 1;
 
                         // Check if path replanning is needed
-                        let need_replan = if let Some((last_target, _)) = movement_state.pathfind {
+                        let need_replan = if let Some((last_target, planned_at)) =
+                            movement_state.pathfind
+                        {
                             let start = Instant::now();
 
                             // Target position changed
                             let target_changed =
                                 (target - last_target).xz().length() > f32::EPSILON;
                             // Path is blocked by obstacles
-                            let path_blocked = is_path_blocked(
-                                &grid,
-                                &movement_state.path,
-                                movement_state.current_target_index,
-                            );
+                            let recheck = time.elapsed_secs() - planned_at >= BLOCKED_PATH_RECHECK;
+                            let path_blocked = recheck
+                                && grid
+                                    .raycast_path(
+                                        movement_state
+                                            .path
+                                            .iter()
+                                            .skip(movement_state.current_target_index)
+                                            .map(|v| v.xz()),
+                                        clearance,
+                                    )
+                                    .is_some();
 
                             if target_changed {
                                 debug!("{} target position changed: {}", entity, target_changed);
@@ -413,6 +482,8 @@ fn apply_final_movement_decision(
                             &transform.translation.xz(),
                             &target.xz(),
                             grid,
+                            clearance,
+                            &mut hierarchies,
                             &mut stats,
                             debug_ref,
                         ) {
@@ -5,10 +5,10 @@ use bevy::prelude::*;
 use lol_config::ConfigNavigationGrid;
 
 #[derive(Debug, Clone)]
-struct AStarNode {
-    pos: (usize, usize),
-    g_cost: f32,
-    h_cost: f32,
+pub(crate) struct AStarNode {
+    pub(crate) pos: (usize, usize),
+    pub(crate) g_cost: f32,
+    pub(crate) h_cost: f32,
 }
 
 impl AStarNode {
@@ -50,19 +50,22 @@ pub fn find_grid_path(
     grid: &ConfigNavigationGrid,
     start: &Vec2,
     end: &Vec2,
+    clearance: f32,
 ) -> Option<Vec<(usize, usize)>> {
-    find_grid_path_with_result(grid, start, end).map(|result| result.path)
+    find_grid_path_with_result(grid, start, end, clearance).map(|result| result.path)
 }
 
 pub fn find_grid_path_with_result(
     grid: &ConfigNavigationGrid,
     start: &Vec2,
     end: &Vec2,
+    clearance: f32,
 ) -> Option<AStarResult> {
     let start_pos = grid.get_cell_xy_by_position(start);
     let end_pos = grid.get_cell_xy_by_position(end);
 
-    if !grid.is_walkable_by_xy(start_pos) || !grid.is_walkable_by_xy(end_pos) {
+    if !grid.is_walkable_by_xy(start_pos, clearance) || !grid.is_walkable_by_xy(end_pos, clearance)
+    {
         warn!("Bidirectional A*: start or end position is invalid");
         return None;
     }
@@ -195,7 +198,7 @@ pub fn find_grid_path_with_result(
             }
         };
 
-        for neighbor_pos in get_neighbors(grid, current_node.pos) {
+        for neighbor_pos in get_neighbors(grid, current_node.pos, clearance) {
             process_neighbor(neighbor_pos);
         }
     }
@@ -241,7 +244,11 @@ fn reconstruct_bidirectional_path(
     path
 }
 
-fn get_neighbors(grid: &ConfigNavigationGrid, pos: (usize, usize)) -> Vec<(usize, usize)> {
+fn get_neighbors(
+    grid: &ConfigNavigationGrid,
+    pos: (usize, usize),
+    clearance: f32,
+) -> Vec<(usize, usize)> {
     let mut neighbors = Vec::with_capacity(8);
     let directions = [
         (-1, 0),
@@ -263,14 +270,14 @@ fn get_neighbors(grid: &ConfigNavigationGrid, pos: (usize, usize)) -> Vec<(usize
         }
 
         let new_pos = (new_x as usize, new_y as usize);
-        if grid.is_walkable_by_xy(new_pos) {
+        if grid.is_walkable_by_xy(new_pos, clearance) {
             neighbors.push(new_pos);
         }
     }
     neighbors
 }
 
-fn distance_cost(cell_size: f32, from: (usize, usize), to: (usize, usize)) -> f32 {
+pub(crate) fn distance_cost(cell_size: f32, from: (usize, usize), to: (usize, usize)) -> f32 {
     let dx = (to.0 as i32 - from.0 as i32).abs();
     let dy = (to.1 as i32 - from.1 as i32).abs();
 
@@ -282,13 +289,17 @@ fn distance_cost(cell_size: f32, from: (usize, usize), to: (usize, usize)) -> f3
 }
 
 /// Calculate the actual cost of moving from `from` to `to` (including dynamic obstacle costs)
-fn movement_cost(grid: &ConfigNavigationGrid, from: (usize, usize), to: (usize, usize)) -> f32 {
+pub(crate) fn movement_cost(
+    grid: &ConfigNavigationGrid,
+    from: (usize, usize),
+    to: (usize, usize),
+) -> f32 {
     let base_cost = distance_cost(grid.cell_size, from, to);
     let cell_cost = grid.get_cell_cost(to);
     base_cost + cell_cost
 }
 
-fn heuristic_cost(cell_size: f32, from: (usize, usize), to: (usize, usize)) -> f32 {
+pub(crate) fn heuristic_cost(cell_size: f32, from: (usize, usize), to: (usize, usize)) -> f32 {
     let dx = (to.0 as i32 - from.0 as i32).abs() as f32;
     let dy = (to.1 as i32 - from.1 as i32).abs() as f32;
     let euclidean = (dx * dx + dy * dy).sqrt() * cell_size;
@@ -0,0 +1,740 @@
+use std::collections::{BinaryHeap, HashMap, HashSet};
+use std::time::Instant;
+
+use bevy::prelude::*;
+use lol_config::ConfigNavigationGrid;
+
+use crate::{distance_cost, heuristic_cost, AStarNode, NavigationStats};
+
+/// Width and height of a cluster in cells
+pub const HPA_CLUSTER_SIZE: usize = 16;
+
+/// Cached paths kept before the least recently used one is evicted
+const HPA_PATH_CACHE_CAPACITY: usize = 1024;
+
+/// Entrances at least this wide get a portal at both ends instead of one in the middle
+const HPA_WIDE_ENTRANCE: usize = 6;
+
+type Cell = (usize, usize);
+
+#[derive(Default)]
+struct HpaCluster {
+    /// Hash of the occupied cells and their costs at the last sync
+    occupancy: u64,
+    /// Soft cost of the units standing in this cluster, added to the abstract edges entering it
+    penalty: f32,
+    portals: Vec<Cell>,
+    /// Abstract edges leaving each portal, to portals of this cluster and across its borders
+    edges: HashMap<Cell, Vec<(Cell, f32)>>,
+    /// Cells between two portals of this cluster
+    paths: HashMap<(Cell, Cell), Vec<Cell>>,
+}
+
+struct CachedPath {
+    /// Smoothed world path
+    path: Vec<Vec2>,
+    clusters: HashSet<usize>,
+    /// Lookup tick of the last hit, the oldest path is evicted when the cache is full
+    last_used: u64,
+}
+
+/// Result of a hierarchical search
+#[derive(Debug, Clone)]
+pub struct HpaResult {
+    pub path: Vec<Cell>,
+    pub visited_cells: Vec<Cell>,
+}
+
+/// HPA* abstraction of the navigation grid: clusters linked by portals on their borders, with a
+/// cache of smoothed paths keyed by start and goal cell
+///
+/// Portals and refinements only see walls and clearance, so the abstraction doesn't depend on
+/// which unit asks or where units stand. Units are a soft cost on the abstract edges entering
+/// their cluster, the any-angle refinement walks around them with the exclusions of the unit
+/// asking. Clusters are built once, the first time a search goes through them, and cached paths
+/// are dropped once the occupied cells of a cluster they cross change.
+pub struct NavigationHierarchy {
+    pub cluster_size: usize,
+    /// Clearance cells need to be walkable in this hierarchy
+    pub clearance: f32,
+    x_len: usize,
+    y_len: usize,
+    clusters_x: usize,
+    clusters_y: usize,
+    clusters: Vec<HpaCluster>,
+    /// Clusters whose borders and edges must be rebuilt
+    dirty: HashSet<usize>,
+    /// Clusters whose borders are up to date but whose portals moved
+    stale: HashSet<usize>,
+    /// Portal pairs on the border of two clusters, lower cluster index first
+    borders: HashMap<(usize, usize), Vec<(Cell, Cell)>>,
+    cache: HashMap<(Cell, Cell), CachedPath>,
+    /// Cache lookups so far, orders cached paths by last use
+    lookups: u64,
+}
+
+/// One hierarchy per clearance level, since units of different sizes can't walk the same cells
+#[derive(Resource, Default)]
+pub struct NavigationHierarchies {
+    layers: HashMap<u32, NavigationHierarchy>,
+}
+
+impl NavigationHierarchies {
+    /// Hierarchy for units that need `clearance`, see `get_required_clearance`
+    pub fn layer(&mut self, clearance: f32) -> &mut NavigationHierarchy {
+        self.layers
+            .entry(clearance.to_bits())
+            .or_insert_with(|| NavigationHierarchy::new(HPA_CLUSTER_SIZE, clearance))
+    }
+
+    pub fn layer_count(&self) -> usize {
+        self.layers.len()
+    }
+
+    /// Sync every layer with the occupied cells of the grid
+    pub fn sync(&mut self, grid: &ConfigNavigationGrid) {
+        for layer in self.layers.values_mut() {
+            layer.sync(grid);
+        }
+    }
+}
+
+impl Default for NavigationHierarchy {
+    fn default() -> Self {
+        Self::new(HPA_CLUSTER_SIZE, 0.0)
+    }
+}
+
+impl NavigationHierarchy {
+    pub fn new(cluster_size: usize, clearance: f32) -> Self {
+        Self {
+            cluster_size: cluster_size.max(2),
+            clearance,
+            x_len: 0,
+            y_len: 0,
+            clusters_x: 0,
+            clusters_y: 0,
+            clusters: Vec::new(),
+            dirty: HashSet::new(),
+            stale: HashSet::new(),
+            borders: HashMap::new(),
+            cache: HashMap::new(),
+            lookups: 0,
+        }
+    }
+
+    pub fn cluster_count(&self) -> usize {
+        self.clusters.len()
+    }
+
+    pub fn cached_paths(&self) -> usize {
+        self.cache.len()
+    }
+
+    pub fn cluster_of(&self, (x, y): Cell) -> usize {
+        (y / self.cluster_size) * self.clusters_x + x / self.cluster_size
+    }
+
+    /// Inclusive min and exclusive max cell of a cluster
+    fn bounds(&self, cluster: usize) -> (Cell, Cell) {
+        let cx = cluster % self.clusters_x;
+        let cy = cluster / self.clusters_x;
+        let min = (cx * self.cluster_size, cy * self.cluster_size);
+        let max = (
+            (min.0 + self.cluster_size).min(self.x_len),
+            (min.1 + self.cluster_size).min(self.y_len),
+        );
+        (min, max)
+    }
+
+    pub fn neighbours(&self, cluster: usize) -> impl Iterator<Item = usize> {
+        let clusters_x = self.clusters_x;
+        let cx = cluster % clusters_x;
+        let cy = cluster / clusters_x;
+        [
+            (cx > 0).then(|| cluster - 1),
+            (cx + 1 < clusters_x).then(|| cluster + 1),
+            (cy > 0).then(|| cluster - clusters_x),
+            (cy + 1 < self.clusters_y).then(|| cluster + clusters_x),
+        ]
+        .into_iter()
+        .flatten()
+    }
+
+    /// Reset the clusters when the grid size changed, they are built again on demand
+    fn resize(&mut self, grid: &ConfigNavigationGrid) {
+        if grid.x_len == self.x_len && grid.y_len == self.y_len {
+            return;
+        }
+        self.x_len = grid.x_len;
+        self.y_len = grid.y_len;
+        self.clusters_x = grid.x_len.div_ceil(self.cluster_size);
+        self.clusters_y = grid.y_len.div_ceil(self.cluster_size);
+        self.clusters = (0..self.clusters_x * self.clusters_y)
+            .map(|_| HpaCluster::default())
+            .collect();
+        self.borders.clear();
+        self.cache.clear();
+        self.stale.clear();
+        self.dirty = (0..self.clusters.len()).collect();
+    }
+
+    /// Update the soft cost units add to each cluster and drop the cached paths going through
+    /// clusters whose occupied cells changed. Exclusions are ignored, they belong to the unit asking.
+    pub fn sync(&mut self, grid: &ConfigNavigationGrid) {
+        self.resize(grid);
+
+        let mut occupancy = vec![0u64; self.clusters.len()];
+        let mut penalties = vec![0.0; self.clusters.len()];
+        for (&cell, &cost) in grid.occupied_cells.iter() {
+            if cell.0 >= self.x_len || cell.1 >= self.y_len {
+                continue;
+            }
+            let hash = (cell.0 as u64)
+                .wrapping_mul(0x9E37_79B9_7F4A_7C15)
+                .rotate_left(17)
+                ^ (cell.1 as u64).wrapping_mul(0xC2B2_AE3D_27D4_EB4F);
+            let cluster = self.cluster_of(cell);
+            occupancy[cluster] =
+                occupancy[cluster].wrapping_add(hash.wrapping_mul(cost.to_bits() as u64 | 1));
+            // A blocked cell costs about the detour of one cell around it
+            penalties[cluster] += cost.min(grid.cell_size);
+        }
+
+        let mut changed = HashSet::new();
+        for (index, (occupancy, penalty)) in occupancy.into_iter().zip(penalties).enumerate() {
+            let cluster = &mut self.clusters[index];
+            cluster.penalty = penalty;
+            if cluster.occupancy != occupancy {
+                cluster.occupancy = occupancy;
+                changed.insert(index);
+            }
+        }
+
+        if !changed.is_empty() {
+            self.cache
+                .retain(|_, cached| cached.clusters.is_disjoint(&changed));
+        }
+    }
+
+    /// Rebuild every dirty cluster now instead of on demand, returns the number of clusters rebuilt
+    pub fn rebuild(&mut self, grid: &ConfigNavigationGrid) -> usize {
+        let pending = self
+            .dirty
+            .iter()
+            .chain(self.stale.iter())
+            .copied()
+            .collect::<Vec<_>>();
+        pending
+            .into_iter()
+            .map(|cluster| self.ensure_cluster(grid, cluster))
+            .sum()
+    }
+
+    /// Bring a cluster's portals and edges up to date, returns the number of clusters rebuilt
+    fn ensure_cluster(&mut self, grid: &ConfigNavigationGrid, cluster: usize) -> usize {
+        if self.dirty.remove(&cluster) {
+            for neighbour in self.neighbours(cluster) {
+                let key = (cluster.min(neighbour), cluster.max(neighbour));
+                let portals = self.find_border_portals(grid, key);
+                if self.borders.get(&key) != Some(&portals) {
+                    self.borders.insert(key, portals);
+                    self.stale.insert(neighbour);
+                }
+            }
+        } else if !self.stale.contains(&cluster) {
+            return 0;
+        }
+
+        self.stale.remove(&cluster);
+        self.build_cluster(grid, cluster);
+        1
+    }
+
+    fn find_border_portals(
+        &self,
+        grid: &ConfigNavigationGrid,
+        (a, b): (usize, usize),
+    ) -> Vec<(Cell, Cell)> {
+        let (min_a, max_a) = self.bounds(a);
+        let (min_b, _) = self.bounds(b);
+
+        // Pairs of facing cells along the border, `a` is left of or below `b`
+        let facing = if a / self.clusters_x == b / self.clusters_x {
+            (min_a.1..max_a.1)
+                .map(|y| ((max_a.0 - 1, y), (min_b.0, y)))
+                .collect::<Vec<_>>()
+        } else {
+            (min_a.0..max_a.0)
+                .map(|x| ((x, max_a.1 - 1), (x, min_b.1)))
+                .collect::<Vec<_>>()
+        };
+
+        let mut portals = Vec::new();
+        let mut run: Vec<(Cell, Cell)> = Vec::new();
+
+        let mut close_run = |run: &mut Vec<(Cell, Cell)>| {
+            match run.len() {
+                0 => {}
+                len if len >= HPA_WIDE_ENTRANCE => {
+                    portals.push(run[0]);
+                    portals.push(run[len - 1]);
+                }
+                len => portals.push(run[len / 2]),
+            }
+            run.clear();
+        };
+
+        for pair in facing {
+            if is_open(grid, pair.0, self.clearance) && is_open(grid, pair.1, self.clearance) {
+                run.push(pair);
+            } else {
+                close_run(&mut run);
+            }
+        }
+        close_run(&mut run);
+
+        portals
+    }
+
+    fn build_cluster(&mut self, grid: &ConfigNavigationGrid, cluster: usize) {
+        let mut edges: HashMap<Cell, Vec<(Cell, f32)>> = HashMap::new();
+
+        for neighbour in self.neighbours(cluster) {
+            let key = (cluster.min(neighbour), cluster.max(neighbour));
+            for &(a, b) in self.borders.get(&key).into_iter().flatten() {
+                let (inside, outside) = if self.cluster_of(a) == cluster {
+                    (a, b)
+                } else {
+                    (b, a)
+                };
+                edges
+                    .entry(inside)
+                    .or_default()
+                    .push((outside, distance_cost(grid.cell_size, inside, outside)));
+            }
+        }
+
+        let portals = edges.keys().copied().collect::<Vec<_>>();
+        let cells = ClusterCells::new(grid, self.bounds(cluster), self.clearance);
+        let mut paths = HashMap::new();
+
+        for (index, &portal) in portals.iter().enumerate() {
+            // Edges are symmetric, search only towards the portals after this one
+            let targets = &portals[index + 1..];
+            if targets.is_empty() {
+                break;
+            }
+            let tree = ClusterTree::search(&cells, portal, targets);
+            for &other in targets {
+                let (Some(cost), Some(path)) = (tree.cost(other), tree.path(other)) else {
+                    continue;
+                };
+                edges.entry(portal).or_default().push((other, cost));
+                edges.entry(other).or_default().push((portal, cost));
+                paths.insert((other, portal), path.iter().rev().copied().collect());
+                paths.insert((portal, other), path);
+            }
+        }
+
+        let entry = &mut self.clusters[cluster];
+        entry.portals = portals;
+        entry.edges = edges;
+        entry.paths = paths;
+    }
+
+    /// Cell path from `start` to `end` through the portal graph, `None` when the hierarchy can't
+    /// connect them
+    pub fn find_path(
+        &mut self,
+        grid: &ConfigNavigationGrid,
+        start: Cell,
+        end: Cell,
+        stats: &mut NavigationStats,
+    ) -> Option<HpaResult> {
+        self.resize(grid);
+
+        if start.0 >= self.x_len
+            || start.1 >= self.y_len
+            || end.0 >= self.x_len
+            || end.1 >= self.y_len
+        {
+            return None;
+        }
+
+        self.search(grid, start, end, stats)
+    }
+
+    /// Smoothed path cached for a start and goal cell
+    pub fn cached_path(&mut self, start: Cell, end: Cell) -> Option<&[Vec2]> {
+        self.lookups += 1;
+        let cached = self.cache.get_mut(&(start, end))?;
+        cached.last_used = self.lookups;
+        Some(cached.path.as_slice())
+    }
+
+    /// Cache the smoothed `path` found along `cells`, dropped once a cluster it crosses changes
+    pub fn cache_path(&mut self, start: Cell, end: Cell, cells: &[Cell], path: Vec<Vec2>) {
+        if self.clusters.is_empty() {
+            return;
+        }
+        if self.cache.len() >= HPA_PATH_CACHE_CAPACITY {
+            let oldest = self
+                .cache
+                .iter()
+                .min_by_key(|(_, cached)| cached.last_used)
+                .map(|(&key, _)| key);
+            if let Some(oldest) = oldest {
+                self.cache.remove(&oldest);
+            }
+        }
+        let clusters = cells.iter().map(|&cell| self.cluster_of(cell)).collect();
+        self.cache.insert(
+            (start, end),
+            CachedPath {
+                path,
+                clusters,
+                last_used: self.lookups,
+            },
+        );
+    }
+
+    fn ensure_cluster_timed(
+        &mut self,
+        grid: &ConfigNavigationGrid,
+        cluster: usize,
+        stats: &mut NavigationStats,
+    ) {
+        if !self.dirty.contains(&cluster) && !self.stale.contains(&cluster) {
+            return;
+        }
+        let start = Instant::now();
+        stats.hierarchy_rebuild_count += self.ensure_cluster(grid, cluster) as u32;
+        stats.hierarchy_rebuild_time += start.elapsed();
+    }
+
+    fn search(
+        &mut self,
+        grid: &ConfigNavigationGrid,
+        start: Cell,
+        end: Cell,
+        stats: &mut NavigationStats,
+    ) -> Option<HpaResult> {
+        if start == end {
+            return Some(HpaResult {
+                path: vec![start],
+                visited_cells: vec![start],
+            });
+        }
+
+        let start_cluster = self.cluster_of(start);
+        let end_cluster = self.cluster_of(end);
+        let clearance = self.clearance;
+
+        if start_cluster == end_cluster {
+            let cells = ClusterCells::new(grid, self.bounds(start_cluster), clearance);
+            let tree = ClusterTree::search(&cells, start, &[end]);
+            if let Some(path) = tree.path(end) {
+                return Some(HpaResult {
+                    path,
+                    visited_cells: tree.visited(),
+                });
+            }
+        }
+
+        // Connect start and goal to the portals of their clusters
+        self.ensure_cluster_timed(grid, start_cluster, stats);
+        self.ensure_cluster_timed(grid, end_cluster, stats);
+
+        let from_start = ClusterTree::search(
+            &ClusterCells::new(grid, self.bounds(start_cluster), clearance),
+            start,
+            &self.clusters[start_cluster].portals,
+        );
+        let from_end = ClusterTree::search(
+            &ClusterCells::new(grid, self.bounds(end_cluster), clearance),
+            end,
+            &self.clusters[end_cluster].portals,
+        );
+
+        let mut visited_cells = Vec::new();
+        let mut g_costs: HashMap<Cell, f32> = HashMap::from([(start, 0.0)]);
+        let mut came_from: HashMap<Cell, Cell> = HashMap::new();
+        let mut open = BinaryHeap::from([AStarNode {
+            pos: start,
+            g_cost: 0.0,
+            h_cost: heuristic_cost(grid.cell_size, start, end),
+        }]);
+
+        while let Some(node) = open.pop() {
+            if node.pos == end {
+                break;
+            }
+            if g_costs.get(&node.pos).is_some_and(|&g| node.g_cost > g) {
+                continue;
+            }
+            visited_cells.push(node.pos);
+
+            let mut successors = if node.pos == start {
+                self.clusters[start_cluster]
+                    .portals
+                    .iter()
+                    .filter_map(|&portal| from_start.cost(portal).map(|cost| (portal, cost)))
+                    .collect::<Vec<_>>()
+            } else {
+                let cluster = self.cluster_of(node.pos);
+                self.ensure_cluster_timed(grid, cluster, stats);
+                self.clusters[cluster]
+                    .edges
+                    .get(&node.pos)
+                    .cloned()
+                    .unwrap_or_default()
+            };
+
+            if node.pos != start && self.cluster_of(node.pos) == end_cluster {
+                if let Some(cost) = from_end.cost(node.pos) {
+                    successors.push((end, cost));
+                }
+            }
+
+            for (next, cost) in successors {
+                let entered = self.cluster_of(next);
+                let penalty = if entered != self.cluster_of(node.pos) {
+                    self.clusters[entered].penalty
+                } else {
+                    0.0
+                };
+                let g_cost = node.g_cost + cost + penalty;
+                if g_costs.get(&next).is_some_and(|&g| g_cost >= g) {
+                    continue;
+                }
+                g_costs.insert(next, g_cost);
+                came_from.insert(next, node.pos);
+                open.push(AStarNode {
+                    pos: next,
+                    g_cost,
+                    h_cost: heuristic_cost(grid.cell_size, next, end),
+                });
+            }
+        }
+
+        if !came_from.contains_key(&end) {
+            return None;
+        }
+
+        let mut abstract_path = vec![end];
+        let mut current = end;
+        while let Some(&previous) = came_from.get(&current) {
+            abstract_path.push(previous);
+            current = previous;
+        }
+        abstract_path.reverse();
+
+        // Refine each abstract edge into cells
+        let mut path = vec![start];
+        for step in abstract_path.windows(2) {
+            let (from, to) = (step[0], step[1]);
+
+            let segment = if from == start {
+                from_start.path(to)?
+            } else if to == end && from_end.cost(from).is_some() {
+                let mut segment = from_end.path(from)?;
+                segment.reverse();
+                segment
+            } else if self.cluster_of(from) != self.cluster_of(to) {
+                vec![from, to]
+            } else {
+                self.clusters[self.cluster_of(from)]
+                    .paths
+                    .get(&(from, to))?
+                    .clone()
+            };
+
+            path.extend(segment.into_iter().skip(1));
+        }
+
+        Some(HpaResult {
+            path,
+            visited_cells,
+        })
+    }
+}
+
+/// Walls and clearance only, units are soft cost in the hierarchy and only block the refinement
+fn is_open(grid: &ConfigNavigationGrid, cell: Cell, clearance: f32) -> bool {
+    cell.0 < grid.x_len
+        && cell.1 < grid.y_len
+        && grid.get_cell_by_xy(cell).is_walkable()
+        && grid.get_clearance_by_xy(cell) >= clearance
+}
+
+/// Walkability of the cells of one cluster, read once per search
+struct ClusterCells {
+    min: Cell,
+    width: usize,
+    height: usize,
+    cell_size: f32,
+    walkable: Vec<bool>,
+}
+
+impl ClusterCells {
+    fn new(grid: &ConfigNavigationGrid, (min, max): (Cell, Cell), clearance: f32) -> Self {
+        let cells = (min.1..max.1)
+            .flat_map(|y| (min.0..max.0).map(move |x| (x, y)))
+            .collect::<Vec<_>>();
+
+        Self {
+            min,
+            width: max.0 - min.0,
+            height: max.1 - min.1,
+            cell_size: grid.cell_size,
+            walkable: cells
+                .iter()
+                .map(|&cell| is_open(grid, cell, clearance))
+                .collect(),
+        }
+    }
+
+    fn index(&self, cell: Cell) -> Option<usize> {
+        let x = cell.0.checked_sub(self.min.0)?;
+        let y = cell.1.checked_sub(self.min.1)?;
+        (x < self.width && y < self.height).then_some(y * self.width + x)
+    }
+}
+
+/// Shortest path tree of a Dijkstra search bounded to one cluster
+struct ClusterTree {
+    min: Cell,
+    width: usize,
+    height: usize,
+    from: Cell,
+    costs: Vec<f32>,
+    parents: Vec<u32>,
+}
+
+impl ClusterTree {
+    /// Search from `from` inside the cluster, stops once every target is reached
+    fn search(cells: &ClusterCells, from: Cell, targets: &[Cell]) -> Self {
+        let size = cells.walkable.len();
+        let mut tree = Self {
+            min: cells.min,
+            width: cells.width,
+            height: cells.height,
+            from,
+            costs: vec![f32::INFINITY; size],
+            parents: vec![u32::MAX; size],
+        };
+
+        let Some(from_index) = cells.index(from) else {
+            return tree;
+        };
+        tree.costs[from_index] = 0.0;
+
+        let mut closed = vec![false; size];
+        let mut remaining = targets
+            .iter()
+            .filter(|&&v| v != from && cells.index(v).is_some())
+            .count();
+        let mut open = BinaryHeap::from([AStarNode {
+            pos: from,
+            g_cost: 0.0,
+            h_cost: 0.0,
+        }]);
+
+        while remaining > 0 {
+            let Some(node) = open.pop() else {
+                break;
+            };
+            let index = cells.index(node.pos).unwrap();
+            if closed[index] {
+                continue;
+            }
+            closed[index] = true;
+
+            if node.pos != from && targets.contains(&node.pos) {
+                remaining -= 1;
+            }
+
+            for dx in -1i32..=1 {
+                for dy in -1i32..=1 {
+                    if dx == 0 && dy == 0 {
+                        continue;
+                    }
+                    let x = node.pos.0 as i32 + dx;
+                    let y = node.pos.1 as i32 + dy;
+                    if x < 0 || y < 0 {
+                        continue;
+                    }
+
+                    let next = (x as usize, y as usize);
+                    let Some(next_index) = cells.index(next) else {
+                        continue;
+                    };
+                    if closed[next_index] || !cells.walkable[next_index] {
+                        continue;
+                    }
+                    // Don't cut corners, diagonal steps need both side cells open
+                    if dx != 0 && dy != 0 {
+                        let side = |cell| cells.index(cell).is_some_and(|i| cells.walkable[i]);
+                        if !side((next.0, node.pos.1)) || !side((node.pos.0, next.1)) {
+                            continue;
+                        }
+                    }
+
+                    let g_cost = node.g_cost + distance_cost(cells.cell_size, node.pos, next);
+                    if g_cost >= tree.costs[next_index] {
+                        continue;
+                    }
+                    tree.costs[next_index] = g_cost;
+                    tree.parents[next_index] = index as u32;
+                    open.push(AStarNode {
+                        pos: next,
+                        g_cost,
+                        h_cost: 0.0,
+                    });
+                }
+            }
+        }
+
+        tree
+    }
+
+    fn index(&self, cell: Cell) -> Option<usize> {
+        let x = cell.0.checked_sub(self.min.0)?;
+        let y = cell.1.checked_sub(self.min.1)?;
+        (x < self.width && y < self.height).then_some(y * self.width + x)
+    }
+
+    fn cell(&self, index: usize) -> Cell {
+        (
+            self.min.0 + index % self.width,
+            self.min.1 + index / self.width,
+        )
+    }
+
+    fn cost(&self, cell: Cell) -> Option<f32> {
+        self.index(cell)
+            .map(|index| self.costs[index])
+            .filter(|cost| cost.is_finite())
+    }
+
+    /// Cells from the search origin to `to`
+    fn path(&self, to: Cell) -> Option<Vec<Cell>> {
+        self.cost(to)?;
+        let mut index = self.index(to)?;
+        let mut path = vec![to];
+        while self.cell(index) != self.from {
+            index = self.parents[index] as usize;
+            path.push(self.cell(index));
+        }
+        path.reverse();
+        Some(path)
+    }
+
+    fn visited(&self) -> Vec<Cell> {
+        (0..self.costs.len())
+            .filter(|&index| self.costs[index].is_finite())
+            .map(|index| self.cell(index))
+            .collect()
+    }
+}
@@ -1,9 +1,13 @@
 mod astar;
 mod grid;
+mod hpa;
 mod navigation;
 mod smoother;
+mod theta;
 
 pub use astar::*;
 pub use grid::*;
+pub use hpa::*;
 pub use navigation::*;
 pub use smoother::*;
+pub use theta::*;
@@ -7,7 +7,8 @@ use bevy::render::render_resource::Face;
 use lol_config::{ConfigNavigationGrid, CELL_COST_IMPASSABLE};
 
 use crate::{
-    find_grid_path_with_result, update_load_grid, Bounding, Character, MapState, ResourceGrid,
+    find_any_angle_path_with_result, find_any_angle_path_within, update_load_grid, AnyAngleResult,
+    Bounding, Character, MapState, NavigationHierarchies, NavigationHierarchy, ResourceGrid, Ward,
 };
 
 #[derive(Default)]
@@ -17,6 +18,7 @@ impl Plugin for PluginNavigaton {
     fn build(&self, app: &mut App) {
         app.init_resource::<NavigationStats>();
         app.init_resource::<NavigationDebug>();
+        app.init_resource::<NavigationHierarchies>();
 
         app.add_systems(First, |mut res_stats: ResMut<NavigationStats>| {
             *res_stats = Default::default();
@@ -70,6 +72,12 @@ pub struct NavigationStats {
 
     pub check_path_count: u32,
     pub check_path_time: Duration,
+
+    pub hierarchy_rebuild_count: u32,
+    pub hierarchy_rebuild_time: Duration,
+
+    pub path_cache_hits: u32,
+    pub path_cache_misses: u32,
 }
 
 /// A* visualization debug resource
@@ -104,28 +112,43 @@ fn update_y(
     }
 }
 
+/// Path for a unit that needs `clearance`, planned through the hierarchy layer of that clearance
 pub fn get_nav_path(
     start_pos: &Vec2,
     end_pos: &Vec2,
     grid: &ConfigNavigationGrid,
+    clearance: f32,
+    hierarchies: &mut NavigationHierarchies,
     stats: &mut NavigationStats,
 ) -> Option<Vec<Vec2>> {
-    get_nav_path_with_debug(start_pos, end_pos, grid, stats, None)
+    get_nav_path_with_debug(
+        start_pos,
+        end_pos,
+        grid,
+        clearance,
+        hierarchies,
+        stats,
+        None,
+    )
 }
 
 pub fn get_nav_path_with_debug(
     start_pos: &Vec2,
     end_pos: &Vec2,
     grid: &ConfigNavigationGrid,
+    clearance: f32,
+    hierarchies: &mut NavigationHierarchies,
     stats: &mut NavigationStats,
     mut debug: Option<&mut NavigationDebug>,
 ) -> Option<Vec<Vec2>> {
     let start = Instant::now();
 
     let start_grid_pos = grid.get_cell_xy_by_position(start_pos);
-    let adjusted_start_pos = if !grid.is_walkable_by_xy(start_grid_pos) {
+    let adjusted_start_pos = if !grid.is_walkable_by_xy(start_grid_pos, clearance) {
         let start_time = Instant::now();
-        if let Some(new_start_grid_pos) = find_nearest_walkable_cell(grid, start_grid_pos) {
+        if let Some(new_start_grid_pos) =
+            find_nearest_walkable_cell(grid, start_grid_pos, clearance)
+        {
             debug!(
                 "Pathfinding: start point ({}, {}) is not walkable, using nearest walkable cell ({}, {})",
                 start_grid_pos.0, start_grid_pos.1, new_start_grid_pos.0, new_start_grid_pos.1
@@ -145,9 +168,9 @@ pub fn get_nav_path_with_debug(
 
     // Check if the end point is walkable, if not, find the nearest reachable cell
     let end_grid_pos = grid.get_cell_xy_by_position(end_pos);
-    let adjusted_end_pos = if !grid.is_walkable_by_xy(end_grid_pos) {
+    let adjusted_end_pos = if !grid.is_walkable_by_xy(end_grid_pos, clearance) {
         let start_time = Instant::now();
-        if let Some(new_end_grid_pos) = find_nearest_walkable_cell(grid, end_grid_pos) {
+        if let Some(new_end_grid_pos) = find_nearest_walkable_cell(grid, end_grid_pos, clearance) {
             debug!(
                 "Pathfinding: end point ({}, {}) is not walkable, using nearest walkable cell ({}, {})",
                 end_grid_pos.0, end_grid_pos.1, new_end_grid_pos.0, new_end_grid_pos.1
@@ -166,11 +189,11 @@ pub fn get_nav_path_with_debug(
     };
 
     // Check if start and end points have direct line of sight
-    let adjusted_start_grid_pos = (adjusted_start_pos - grid.min_position) / grid.cell_size;
-    let adjusted_end_grid_pos = (adjusted_end_pos - grid.min_position) / grid.cell_size;
-
-    if has_line_of_sight(&grid, adjusted_start_grid_pos, adjusted_end_grid_pos) {
-        debug!("Direct path found, took {:.6}ms", start.elapsed().as_millis());
+    if grid.has_line_of_sight(adjusted_start_pos, adjusted_end_pos, clearance) {
+        debug!(
+            "Direct path found, took {:.6}ms",
+            start.elapsed().as_millis()
+        );
         {
             stats.get_nav_path_count += 1;
             stats.get_nav_path_time += start.elapsed();
@@ -187,8 +210,15 @@ pub fn get_nav_path_with_debug(
         return Some(vec![adjusted_start_pos, adjusted_end_pos]);
     }
 
-    // If not directly reachable, use A* algorithm to plan the path (with debug info)
-    let result = find_path_with_result(&grid, &adjusted_start_pos, &adjusted_end_pos);
+    // If not directly reachable, plan the path through the cluster hierarchy (with debug info)
+    let result = find_path_with_hierarchy(
+        grid,
+        hierarchies.layer(clearance),
+        &adjusted_start_pos,
+        &adjusted_end_pos,
+        clearance,
+        stats,
+    );
 
     debug!("A* path found, took {:.6}ms", start.elapsed().as_millis());
 
@@ -219,9 +249,14 @@ pub struct FindPathResult {
     pub unoptimized_path: Vec<Vec2>,
 }
 
-/// Main pathfinding function, combining A* and funnel algorithm
-pub fn find_path(grid: &ConfigNavigationGrid, start: &Vec2, end: &Vec2) -> Option<Vec<Vec2>> {
-    find_path_with_result(grid, start, end).map(|result| result.path)
+/// Main pathfinding function, any-angle search over the whole grid
+pub fn find_path(
+    grid: &ConfigNavigationGrid,
+    start: &Vec2,
+    end: &Vec2,
+    clearance: f32,
+) -> Option<Vec<Vec2>> {
+    find_path_with_result(grid, start, end, clearance).map(|result| result.path)
 }
 
 /// Main pathfinding function, returns complete debug information
@@ -229,184 +264,142 @@ pub fn find_path_with_result(
     grid: &ConfigNavigationGrid,
     start: &Vec2,
     end: &Vec2,
+    clearance: f32,
 ) -> Option<FindPathResult> {
-    let astar_result = find_grid_path_with_result(grid, start, end)?;
-
-    let unoptimized_path = astar_result
-        .path
-        .iter()
-        .map(|&(x, y)| grid.get_position_by_float_xy(&vec2(x as f32 + 0.5, y as f32 + 0.5)))
-        .collect::<Vec<_>>();
-
-    let optimized_path = post_process_path(grid, &astar_result.path, start, end);
-
-    Some(FindPathResult {
-        path: optimized_path,
-        visited_cells: astar_result.visited_cells,
-        path_cells: astar_result.path.clone(),
-        unoptimized_path,
-    })
+    let result = find_any_angle_path_with_result(grid, start, end, clearance)?;
+    Some(build_find_path_result(grid, result, start, end, clearance))
 }
 
-pub fn post_process_path(
+/// Pathfinding through the cluster hierarchy, refined by an any-angle search inside the clusters
+/// the hierarchy went through. Paths are cached by start and goal cell.
+pub fn find_path_with_hierarchy(
     grid: &ConfigNavigationGrid,
-    path: &Vec<(usize, usize)>,
+    hierarchy: &mut NavigationHierarchy,
     start: &Vec2,
     end: &Vec2,
-) -> Vec<Vec2> {
-    if path.is_empty() {
-        return Vec::new();
+    clearance: f32,
+    stats: &mut NavigationStats,
+) -> Option<FindPathResult> {
+    let start_cell = grid.get_cell_xy_by_position(start);
+    let end_cell = grid.get_cell_xy_by_position(end);
+
+    if let Some(cached) = hierarchy.cached_path(start_cell, end_cell) {
+        let mut path = cached.to_vec();
+        let last = path.len() - 1;
+        path[0] = *start;
+        path[last] = *end;
+
+        // Only reuse it if the exact endpoints still see their neighbouring waypoints
+        if path.len() < 2
+            || (grid.has_line_of_sight(path[0], path[1], clearance)
+                && grid.has_line_of_sight(path[last - 1], path[last], clearance))
+        {
+            stats.path_cache_hits += 1;
+            return Some(FindPathResult {
+                unoptimized_path: path.clone(),
+                path,
+                visited_cells: Vec::new(),
+                path_cells: Vec::new(),
+            });
+        }
     }
+    stats.path_cache_misses += 1;
 
-    let mut path = path
-        .iter()
-        .map(|&(x, y)| vec2(x as f32 + 0.5, y as f32 + 0.5))
-        .collect::<Vec<_>>();
-
-    path.remove(0);
-    path.insert(0, (start - grid.min_position) / grid.cell_size);
-
-    path.pop();
-    path.push((end - grid.min_position) / grid.cell_size);
-
-    let path = optimize_path(&grid, &path);
-
-    let path = path
-        .into_iter()
-        .map(|v| grid.get_position_by_float_xy(&v))
-        .collect::<Vec<_>>();
-
-    return path;
-}
-
-fn optimize_path(grid: &ConfigNavigationGrid, path: &Vec<Vec2>) -> Vec<Vec2> {
-    if path.len() <= 2 {
-        return path.clone();
-    }
+    // Every entrance between clusters has a portal, so the hierarchy failing means no path exists
+    let Some(cells) = hierarchy.find_path(grid, start_cell, end_cell, stats) else {
+        debug!("Hierarchical path not found");
+        return None;
+    };
 
-    let mut optimized_path = vec![path[0]];
-    let mut current_index = 0;
-    while current_index < path.len() - 1 {
-        // Default furthest reachable point is the next point
-        let mut furthest_visible_index = current_index + 1;
-
-        // Iterate backwards from the end of the path to find the first visible point
-        for lookahead_index in ((current_index + 2)..path.len()).rev() {
-            let start_pos = path[current_index];
-            let end_pos = path[lookahead_index];
-
-            // Once we find a visible point, it must be the "furthest" point when looking backwards
-            if has_line_of_sight(grid, start_pos, end_pos) {
-                furthest_visible_index = lookahead_index;
-                break;
-            }
-        }
+    let corridor = cells
+        .path
+        .iter()
+        .map(|&cell| hierarchy.cluster_of(cell))
+        .collect::<HashSet<_>>();
+    let within = |corridor: &HashSet<usize>| {
+        find_any_angle_path_within(grid, start_cell, end_cell, clearance, |cell| {
+            corridor.contains(&hierarchy.cluster_of(cell))
+        })
+    };
+    // Units are only soft cost in the hierarchy, widen the corridor once when they block it
+    let result = within(&corridor).or_else(|| {
+        let widened = corridor
+            .iter()
+            .flat_map(|&cluster| hierarchy.neighbours(cluster).chain([cluster]))
+            .collect();
+        within(&widened)
+    });
+    let Some(result) = result else {
+        debug!("Any-angle path not found in the hierarchy corridor");
+        return None;
+    };
 
-        optimized_path.push(path[furthest_visible_index]);
-        current_index = furthest_visible_index;
+    let result = build_find_path_result(grid, result, start, end, clearance);
+    if !result.path.is_empty() {
+        hierarchy.cache_path(
+            start_cell,
+            end_cell,
+            &result.path_cells,
+            result.path.clone(),
+        );
     }
 
-    optimized_path
+    Some(result)
 }
 
-/// Detect if there are obstacles blocking the given path
-/// Starting from the current position, check if the remaining path is still passable
-pub fn is_path_blocked(grid: &ConfigNavigationGrid, path: &[Vec3], current_index: usize) -> bool {
-    if path.is_empty() || current_index >= path.len() {
-        return false;
-    }
-
-    // Check if each segment from the current point to the path end is blocked
-    for i in current_index..path.len().saturating_sub(1) {
-        let start = path[i].xz();
-        let end = path[i + 1].xz();
-
-        // Convert to grid coordinates
-        let start_grid = (start - grid.min_position) / grid.cell_size;
-        let end_grid = (end - grid.min_position) / grid.cell_size;
+fn build_find_path_result(
+    grid: &ConfigNavigationGrid,
+    result: AnyAngleResult,
+    start: &Vec2,
+    end: &Vec2,
+    clearance: f32,
+) -> FindPathResult {
+    let unoptimized_path = result
+        .path
+        .iter()
+        .map(|&(x, y)| grid.get_position_by_float_xy(&vec2(x as f32 + 0.5, y as f32 + 0.5)))
+        .collect::<Vec<_>>();
 
-        if !has_line_of_sight(grid, start_grid, end_grid) {
-            return true;
-        }
+    FindPathResult {
+        path: waypoints_to_path(grid, &result.waypoints, start, end, clearance),
+        visited_cells: result.visited_cells,
+        path_cells: result.path,
+        unoptimized_path,
     }
-
-    false
 }
 
-pub fn has_line_of_sight(grid: &ConfigNavigationGrid, start: Vec2, end: Vec2) -> bool {
-    const CORNER_EPSILON: f32 = 1e-6;
-
-    let start_grid_x = start.x.floor() as isize;
-    let start_grid_y = start.y.floor() as isize;
-    let end_grid_x = end.x.floor() as isize;
-    let end_grid_y = end.y.floor() as isize;
-
-    let mut current_grid_x = start_grid_x;
-    let mut current_grid_y = start_grid_y;
-
-    if current_grid_x == end_grid_x && current_grid_y == end_grid_y {
-        return true;
+/// World path through the turning points of an any-angle search. The exact start and end take
+/// the place of their cell centers unless that would cut through a blocked cell.
+fn waypoints_to_path(
+    grid: &ConfigNavigationGrid,
+    waypoints: &[(usize, usize)],
+    start: &Vec2,
+    end: &Vec2,
+    clearance: f32,
+) -> Vec<Vec2> {
+    if waypoints.len() < 2 {
+        return vec![*start, *end];
     }
 
-    let direction = end - start;
-    let step_x = direction.x.signum() as isize;
-    let step_y = direction.y.signum() as isize;
+    let mut path = waypoints
+        .iter()
+        .map(|&(x, y)| grid.get_position_by_float_xy(&vec2(x as f32 + 0.5, y as f32 + 0.5)))
+        .collect::<Vec<_>>();
 
-    let t_delta_x = if direction.x.abs() < CORNER_EPSILON {
-        f32::MAX
-    } else {
-        (1.0 / direction.x).abs()
-    };
-    let t_delta_y = if direction.y.abs() < CORNER_EPSILON {
-        f32::MAX
+    if grid.has_line_of_sight(*start, path[1], clearance) {
+        path[0] = *start;
     } else {
-        (1.0 / direction.y).abs()
-    };
+        path.insert(0, *start);
+    }
 
-    let mut t_max_x = if direction.x > 0.0 {
-        ((start_grid_x + 1) as f32 - start.x) / direction.x
-    } else if direction.x < 0.0 {
-        (start.x - start_grid_x as f32) / -direction.x
-    } else {
-        f32::MAX
-    };
-    let mut t_max_y = if direction.y > 0.0 {
-        ((start_grid_y + 1) as f32 - start.y) / direction.y
-    } else if direction.y < 0.0 {
-        (start.y - start_grid_y as f32) / -direction.y
+    let last = path.len() - 1;
+    if grid.has_line_of_sight(path[last - 1], *end, clearance) {
+        path[last] = *end;
     } else {
-        f32::MAX
-    };
-
-    let steps_to_take = (end_grid_x - start_grid_x).abs() + (end_grid_y - start_grid_y).abs();
-
-    for _ in 0..steps_to_take {
-        // --- Core algorithm logic ---
-        if (t_max_x - t_max_y).abs() < CORNER_EPSILON {
-            current_grid_x += step_x;
-            current_grid_y += step_y;
-            t_max_x += t_delta_x;
-            t_max_y += t_delta_y;
-        } else if t_max_x < t_max_y {
-            current_grid_x += step_x;
-            t_max_x += t_delta_x;
-        } else {
-            current_grid_y += step_y;
-            t_max_y += t_delta_y;
-        }
-
-        // Check if the new position is walkable
-        if !grid.is_walkable_by_xy((current_grid_x as usize, current_grid_y as usize)) {
-            return false;
-        }
-
-        // Check if we have reached the destination
-        if current_grid_x == end_grid_x && current_grid_y == end_grid_y {
-            return true;
-        }
+        path.push(*end);
     }
 
-    true
+    path
 }
 
 /// Helper function to convert world coordinates to grid coordinates
@@ -420,8 +413,9 @@ pub fn world_pos_to_grid_xy(grid: &ConfigNavigationGrid, world_pos: Vec2) -> (us
 pub fn find_nearest_walkable_cell(
     grid: &ConfigNavigationGrid,
     start: (usize, usize),
+    clearance: f32,
 ) -> Option<(usize, usize)> {
-    if grid.is_walkable_by_xy(start) {
+    if grid.is_walkable_by_xy(start, clearance) {
         return Some(start);
     }
 
@@ -461,7 +455,7 @@ pub fn find_nearest_walkable_cell(
                 continue;
             }
 
-            if grid.is_walkable_by_xy(new_pos) {
+            if grid.is_walkable_by_xy(new_pos, clearance) {
                 return Some(new_pos);
             }
 
@@ -476,12 +470,17 @@ pub fn find_nearest_walkable_cell(
 fn pre_update_global_occupied_cells(
     res_grid: Res<ResourceGrid>,
     mut assets_grid: ResMut<Assets<ConfigNavigationGrid>>,
-    entities_with_bounding: Query<(Entity, &GlobalTransform, &Bounding)>,
+    // Wards can be attacked but don't block pathing
+    entities_with_bounding: Query<(Entity, &GlobalTransform, &Bounding), Without<Ward>>,
+    mut hierarchies: ResMut<NavigationHierarchies>,
     mut stats: ResMut<NavigationStats>,
 ) {
     let Some(grid) = assets_grid.get_mut(&res_grid.0) else {
         return;
     };
+    if grid.clearance.len() != grid.x_len * grid.y_len {
+        grid.update_clearance();
+    }
     let start = Instant::now();
 
     // Calculate occupied_cells for all entities (without excluding any entity)
@@ -491,6 +490,8 @@ fn pre_update_global_occupied_cells(
     stats.calculate_occupied_grid_cells_time += start.elapsed();
     stats.calculate_occupied_grid_cells_count += 1;
     stats.occupied_grid_cells_num = grid.occupied_cells.len() as u32;
+
+    hierarchies.sync(grid);
 }
 
 /// Calculate occupied grid cells and their traversal costs based on all entities with Bounding components
@@ -504,7 +505,7 @@ fn pre_update_global_occupied_cells(
 /// - Mapping from cell coordinates to traversal cost, higher cost means greater traversal difficulty
 pub fn calculate_occupied_grid_cells(
     grid: &ConfigNavigationGrid,
-    entities_with_bounding: &Query<(Entity, &GlobalTransform, &Bounding)>,
+    entities_with_bounding: &Query<(Entity, &GlobalTransform, &Bounding), Without<Ward>>,
     exclude_entities: &[Entity],
 ) -> HashMap<(usize, usize), f32> {
     let mut occupied_cells: HashMap<(usize, usize), f32> = HashMap::new();
@@ -0,0 +1,273 @@
+use std::collections::{BinaryHeap, HashMap};
+
+use bevy::prelude::*;
+use lol_config::ConfigNavigationGrid;
+
+use crate::{heuristic_cost, movement_cost, AStarNode};
+
+/// Maximum number of expanded cells before the any-angle search gives up
+pub const THETA_ITERATION_LIMIT: usize = 40000;
+
+/// Weight on the heuristic, trades a few percent of path length for not flooding wide lanes
+/// around small detours
+pub const THETA_HEURISTIC_WEIGHT: f32 = 1.05;
+
+/// Longest line of sight in cells a node may have to its parent, longer segments get split
+pub const THETA_SIGHT_LIMIT: usize = 24;
+
+type Cell = (usize, usize);
+
+/// Result of an any-angle search
+#[derive(Debug, Clone)]
+pub struct AnyAngleResult {
+    /// Cells the path turns at, start and end included
+    pub waypoints: Vec<Cell>,
+    /// Contiguous cells crossed by the path
+    pub path: Vec<Cell>,
+    pub visited_cells: Vec<Cell>,
+}
+
+struct ThetaNode {
+    g: f32,
+    parent: Cell,
+    closed: bool,
+}
+
+/// Any-angle path between two world positions using Lazy Theta*, returns the grid cells
+/// crossed by the path
+pub fn find_any_angle_path(
+    grid: &ConfigNavigationGrid,
+    start: &Vec2,
+    end: &Vec2,
+    clearance: f32,
+) -> Option<Vec<Cell>> {
+    find_any_angle_path_with_result(grid, start, end, clearance).map(|result| result.path)
+}
+
+/// Lazy Theta* over the whole grid, see `find_any_angle_path_within`
+pub fn find_any_angle_path_with_result(
+    grid: &ConfigNavigationGrid,
+    start: &Vec2,
+    end: &Vec2,
+    clearance: f32,
+) -> Option<AnyAngleResult> {
+    let start_pos = grid.get_cell_xy_by_position(start);
+    let end_pos = grid.get_cell_xy_by_position(end);
+
+    if !grid.is_walkable_by_xy(start_pos, clearance) || !grid.is_walkable_by_xy(end_pos, clearance)
+    {
+        warn!("Lazy Theta*: start or end position is invalid");
+        return None;
+    }
+
+    find_any_angle_path_within(grid, start_pos, end_pos, clearance, |_| true)
+}
+
+/// Lazy Theta* over cell centers, expanding only the cells `allowed` accepts. Nodes inherit the
+/// parent of the cell that expanded them and line of sight is only checked once a node is
+/// popped, falling back to the best closed neighbour when the straight segment is blocked. Line
+/// of sight isn't limited to allowed cells, so the path may cut through cells around them.
+pub fn find_any_angle_path_within(
+    grid: &ConfigNavigationGrid,
+    start_pos: Cell,
+    end_pos: Cell,
+    clearance: f32,
+    allowed: impl Fn(Cell) -> bool,
+) -> Option<AnyAngleResult> {
+    if start_pos == end_pos {
+        return Some(AnyAngleResult {
+            waypoints: vec![start_pos],
+            path: vec![start_pos],
+            visited_cells: vec![start_pos],
+        });
+    }
+
+    let mut nodes = HashMap::from([(
+        start_pos,
+        ThetaNode {
+            g: 0.0,
+            parent: start_pos,
+            closed: false,
+        },
+    )]);
+    let mut open = BinaryHeap::from([AStarNode {
+        pos: start_pos,
+        g_cost: 0.0,
+        h_cost: heuristic_cost(grid.cell_size, start_pos, end_pos) * THETA_HEURISTIC_WEIGHT,
+    }]);
+    let mut visited_cells = Vec::new();
+
+    let mut iterations = 0;
+    while let Some(node) = open.pop() {
+        let current = node.pos;
+        let Some(&ThetaNode { g, parent, closed }) = nodes.get(&current) else {
+            continue;
+        };
+        if closed || node.g_cost > g {
+            continue;
+        }
+
+        iterations += 1;
+        if iterations > THETA_ITERATION_LIMIT {
+            warn!("Lazy Theta*: exceeded iteration limit");
+            return None;
+        }
+
+        // Set vertex: the optimistic parent has to actually see this cell
+        let Some((g, parent)) = set_vertex(grid, &nodes, current, parent, clearance) else {
+            continue;
+        };
+        nodes.insert(
+            current,
+            ThetaNode {
+                g,
+                parent,
+                closed: true,
+            },
+        );
+        visited_cells.push(current);
+
+        if current == end_pos {
+            debug!("Lazy Theta* found path, iterations: {}", iterations);
+            let waypoints = turning_points(&nodes, start_pos, end_pos);
+            return Some(AnyAngleResult {
+                path: crossed_cells(grid, &waypoints),
+                waypoints,
+                visited_cells,
+            });
+        }
+
+        for neighbor in neighbors(grid, current, clearance) {
+            if !allowed(neighbor) {
+                continue;
+            }
+            let neighbor_node = nodes.get(&neighbor);
+            if neighbor_node.is_some_and(|v| v.closed) {
+                continue;
+            }
+
+            // Keep line of sight checks short by splitting long segments at the current cell
+            let parent = if chebyshev_distance(parent, neighbor) > THETA_SIGHT_LIMIT {
+                current
+            } else {
+                parent
+            };
+            let tentative_g = nodes[&parent].g
+                + heuristic_distance(grid.cell_size, parent, neighbor)
+                + grid.get_cell_cost(neighbor);
+            if neighbor_node.is_some_and(|v| tentative_g >= v.g) {
+                continue;
+            }
+
+            nodes.insert(
+                neighbor,
+                ThetaNode {
+                    g: tentative_g,
+                    parent,
+                    closed: false,
+                },
+            );
+            open.push(AStarNode {
+                pos: neighbor,
+                g_cost: tentative_g,
+                h_cost: heuristic_cost(grid.cell_size, neighbor, end_pos) * THETA_HEURISTIC_WEIGHT,
+            });
+        }
+    }
+
+    debug!("Lazy Theta*: no path found, iterations: {}", iterations);
+    None
+}
+
+/// Cost and parent of a popped cell once its parent's line of sight is checked, `None` when
+/// neither the parent nor a closed neighbour can reach it
+fn set_vertex(
+    grid: &ConfigNavigationGrid,
+    nodes: &HashMap<Cell, ThetaNode>,
+    current: Cell,
+    parent: Cell,
+    clearance: f32,
+) -> Option<(f32, Cell)> {
+    if parent == current {
+        return Some((nodes[&current].g, parent));
+    }
+    if let Some(cost) = segment_cost(grid, parent, current, clearance) {
+        return Some((nodes[&parent].g + cost, parent));
+    }
+
+    neighbors(grid, current, clearance)
+        .filter_map(|neighbor| {
+            let node = nodes.get(&neighbor).filter(|v| v.closed)?;
+            Some((node.g + movement_cost(grid, neighbor, current), neighbor))
+        })
+        .min_by(|a, b| a.0.total_cmp(&b.0))
+}
+
+/// Cost of walking straight between two cell centers, including the dynamic cost of every
+/// cell on the way, `None` when the segment is blocked
+fn segment_cost(grid: &ConfigNavigationGrid, from: Cell, to: Cell, clearance: f32) -> Option<f32> {
+    let mut cost = heuristic_distance(grid.cell_size, from, to);
+    let hit = grid.raycast_by(center(grid, from), center(grid, to), |cell| {
+        if !grid.is_walkable_by_xy(cell, clearance) {
+            return true;
+        }
+        cost += grid.get_cell_cost(cell);
+        false
+    });
+    hit.is_none().then_some(cost)
+}
+
+fn heuristic_distance(cell_size: f32, from: Cell, to: Cell) -> f32 {
+    let dx = to.0 as f32 - from.0 as f32;
+    let dy = to.1 as f32 - from.1 as f32;
+    (dx * dx + dy * dy).sqrt() * cell_size
+}
+
+fn chebyshev_distance(from: Cell, to: Cell) -> usize {
+    from.0.abs_diff(to.0).max(from.1.abs_diff(to.1))
+}
+
+fn center(grid: &ConfigNavigationGrid, (x, y): Cell) -> Vec2 {
+    grid.get_position_by_float_xy(&vec2(x as f32 + 0.5, y as f32 + 0.5))
+}
+
+fn neighbors(
+    grid: &ConfigNavigationGrid,
+    (x, y): Cell,
+    clearance: f32,
+) -> impl Iterator<Item = Cell> + '_ {
+    (-1..=1)
+        .flat_map(|dy| (-1..=1).map(move |dx| (dx, dy)))
+        .filter(|&offset| offset != (0, 0))
+        .filter_map(move |(dx, dy)| {
+            let neighbor = (x.checked_add_signed(dx)?, y.checked_add_signed(dy)?);
+            let open = grid.is_walkable_by_xy(neighbor, clearance)
+                && (dx == 0
+                    || dy == 0
+                    || (grid.is_walkable_by_xy((neighbor.0, y), clearance)
+                        && grid.is_walkable_by_xy((x, neighbor.1), clearance)));
+            open.then_some(neighbor)
+        })
+}
+
+fn turning_points(nodes: &HashMap<Cell, ThetaNode>, start: Cell, end: Cell) -> Vec<Cell> {
+    let mut points = vec![end];
+    let mut current = end;
+    while current != start {
+        current = nodes[&current].parent;
+        points.push(current);
+    }
+    points.reverse();
+    points
+}
+
+fn crossed_cells(grid: &ConfigNavigationGrid, points: &[Cell]) -> Vec<Cell> {
+    let mut cells = vec![points[0]];
+    for pair in points.windows(2) {
+        grid.raycast_by(center(grid, pair[0]), center(grid, pair[1]), |cell| {
+            cells.push(cell);
+            false
+        });
+    }
+    cells
+}
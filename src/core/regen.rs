@@ -0,0 +1,99 @@
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::{AbilityResource, Dead, EventDamageCreate, Health, Level};
+
+/// Health and ability resource regeneration on the fixed step
+#[derive(Default)]
+pub struct PluginRegen;
+
+impl Plugin for PluginRegen {
+    fn build(&self, app: &mut App) {
+        app.add_observer(on_event_damage_create);
+
+        app.add_systems(
+            FixedUpdate,
+            (fixed_update_health_regen, fixed_update_ability_resource),
+        );
+    }
+}
+
+/// Health regenerated per second
+#[derive(Component, Clone, Default, Serialize, Deserialize)]
+pub struct HealthRegen {
+    pub base: f32,
+    pub per_level: f32,
+}
+
+impl HealthRegen {
+    pub fn at_level(&self, level: u32) -> f32 {
+        self.base + self.per_level * level.saturating_sub(1) as f32
+    }
+}
+
+fn fixed_update_health_regen(
+    mut q_health: Query<(&mut Health, &HealthRegen, Option<&Level>), Without<Dead>>,
+    time: Res<Time<Fixed>>,
+) {
+    let dt = time.delta_secs();
+
+    for (mut health, regen, level) in q_health.iter_mut() {
+        if health.value <= 0.0 || health.value >= health.max {
+            continue;
+        }
+
+        let regen = regen.at_level(level.map_or(1, |v| v.value));
+        health.value = (health.value + regen * dt).min(health.max);
+    }
+}
+
+fn fixed_update_ability_resource(
+    mut q_ability_resource: Query<(&mut AbilityResource, Option<&Level>), Without<Dead>>,
+    time: Res<Time<Fixed>>,
+) {
+    let dt = time.delta_secs();
+
+    for (mut ability_resource, level) in q_ability_resource.iter_mut() {
+        let rules = ability_resource.ar_type.rules();
+        ability_resource.out_of_combat += dt;
+
+        let mut delta = 0.0;
+        if rules.regenerates {
+            delta += ability_resource.regen(level.map_or(1, |v| v.value)) * dt;
+        }
+        if ability_resource.out_of_combat >= rules.decay_delay {
+            delta -= rules.decay * dt;
+        }
+        if delta == 0.0 {
+            continue;
+        }
+
+        let max = ability_resource.max;
+        ability_resource.value = (ability_resource.value + delta).clamp(0.0, max);
+    }
+}
+
+/// Fury style resources build up while fighting
+fn on_event_damage_create(
+    trigger: On<EventDamageCreate>,
+    mut q_ability_resource: Query<&mut AbilityResource>,
+) {
+    let event = trigger.event();
+    if event.damage_result.final_damage <= 0.0 {
+        return;
+    }
+
+    for entity in [event.entity, event.source] {
+        let Ok(mut ability_resource) = q_ability_resource.get_mut(entity) else {
+            continue;
+        };
+
+        ability_resource.out_of_combat = 0.0;
+
+        let gain = ability_resource.ar_type.rules().gain_on_damage;
+        if gain > 0.0 {
+            let max = ability_resource.max;
+            ability_resource.value = (ability_resource.value + gain).min(max);
+        }
+    }
+}
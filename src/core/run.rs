@@ -1,6 +1,9 @@
 use bevy::prelude::*;
 
-use crate::{CommandMovement, EventMovementEnd, MovementAction, MovementWay};
+use crate::{CommandMovement, EventMovementEnd, MovementAction, MovementState, MovementWay};
+
+/// Distance the destination has to move before the path to it is planned again
+pub const RUN_REPATH_DISTANCE: f32 = 50.0;
 
 #[derive(Default)]
 pub struct PluginRun;
@@ -18,6 +21,24 @@ impl Plugin for PluginRun {
 #[derive(Component)]
 pub struct Run {
     pub target: RunTarget,
+    /// Destination of the last pathfind
+    destination: Option<Vec2>,
+}
+
+impl Run {
+    /// Whether the unit should pathfind to `destination` again, it only does when it stopped
+    /// following the last path or the destination moved away from it
+    fn should_repath(&mut self, destination: Vec2, movement_state: Option<&MovementState>) -> bool {
+        let following = movement_state.is_some_and(|v| v.source == "Run" && !v.path.is_empty());
+        let moved = self
+            .destination
+            .is_none_or(|v| v.distance(destination) >= RUN_REPATH_DISTANCE);
+        if following && !moved {
+            return false;
+        }
+        self.destination = Some(destination);
+        true
+    }
 }
 
 #[derive(EntityEvent)]
@@ -51,6 +72,7 @@ fn on_command_run_start(trigger: On<CommandRunStart>, mut commands: Commands) {
     let entity = trigger.event_target();
     commands.entity(entity).insert(Run {
         target: trigger.target.clone(),
+        destination: None,
     });
     commands.trigger(EventRunStart { entity });
 }
@@ -65,13 +87,20 @@ fn on_command_run_stop(trigger: On<CommandRunStop>, mut commands: Commands) {
     });
 }
 
-fn fixed_update(mut commands: Commands, q: Query<(Entity, &Run)>, q_transform: Query<&Transform>) {
-    for (entity, run) in q.iter() {
+fn fixed_update(
+    mut commands: Commands,
+    mut q: Query<(Entity, &mut Run, Option<&MovementState>)>,
+    q_transform: Query<&Transform>,
+) {
+    for (entity, mut run, movement_state) in q.iter_mut() {
         match run.target {
             RunTarget::Position(position) => {
                 let Ok(transform) = q_transform.get(entity) else {
                     return;
                 };
+                if !run.should_repath(position, movement_state) {
+                    continue;
+                }
 
                 debug!("{} pathfinding to Vec3({})", entity, position);
                 commands.trigger(CommandMovement {
@@ -92,6 +121,9 @@ fn fixed_update(mut commands: Commands, q: Query<(Entity, &Run)>, q_transform: Q
                 let Ok(transform) = q_transform.get(target) else {
                     return;
                 };
+                if !run.should_repath(transform.translation.xz(), movement_state) {
+                    continue;
+                }
 
                 debug!(
                     "{} pathfinding to entity {} Vec3({})",
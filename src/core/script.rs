@@ -0,0 +1,687 @@
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use bevy::asset::io::Reader;
+use bevy::asset::{AssetLoader, LoadContext, LoadedFolder};
+use bevy::ecs::component::Tick;
+use bevy::ecs::reflect::ReflectCommandExt;
+use bevy::ecs::system::{SystemChangeTick, SystemParam};
+use bevy::prelude::*;
+use bevy::reflect::serde::TypedReflectDeserializer;
+use bevy::reflect::{PartialReflect, ReflectFromReflect, TypeRegistry};
+use league_utils::hash_bin;
+use lol_core::Team;
+use rand::random;
+use rhai::serde::DynamicDeserializer;
+use rhai::{
+    Array, CallFnOptions, Dynamic, Engine, EvalAltResult, FuncArgs, Map, Scope, AST, FLOAT, INT,
+};
+use serde::de::DeserializeSeed;
+use thiserror::Error;
+
+use crate::{
+    component_registration, Armor, BonusDamage, Buff, BuffRegistry, Buffs, Champion,
+    CommandBuffApply, CommandDamageCreate, CommandMovement, CommandSkillBeforeStart,
+    CommandSkinParticleDespawn, CommandSkinParticleSpawn, Damage, DamageOrigin, DamageType, Dead,
+    Direction, EntityCommandsTrigger, EventAttackEnd, EventDamageCreate, Health, MagicResist,
+    MovementAction, MovementWay, Skill, SkillEffectError, Skills, Vital,
+};
+
+/// Folder under `assets` scanned for `*.rhai` champion scripts
+pub const SCRIPT_FOLDER: &str = "scripts";
+
+/// Runs Rhai champion scripts, a script named after the champion is attached to it.
+///
+/// Scripts define any of the hooks below and act on the champion through `this`:
+/// `on_skill_cast(index)`, `on_attack_end(target)`, `on_damage_dealt(target, amount)` and
+/// `on_update(delta)`. Build with the `dev` feature to hot reload them.
+#[derive(Default)]
+pub struct PluginScript;
+
+impl Plugin for PluginScript {
+    fn build(&self, app: &mut App) {
+        app.init_asset::<Script>();
+        app.init_resource::<ScriptEngine>();
+        app.init_asset_loader::<ScriptLoader>();
+
+        app.add_observer(on_command_skill_before_start);
+        app.add_observer(on_event_attack_end);
+        app.add_observer(on_event_damage_create);
+
+        app.add_systems(Startup, startup_load_scripts);
+        app.add_systems(Update, update_attach_script);
+        app.add_systems(FixedUpdate, fixed_update_script);
+    }
+}
+
+#[derive(Asset, TypePath)]
+pub struct Script {
+    /// File stem, matched case-insensitively against the champion's `Name`
+    pub name: String,
+    pub ast: AST,
+}
+
+#[derive(Resource)]
+pub struct ScriptFolder(pub Handle<LoadedFolder>);
+
+/// Engine with the `Context` API registered, shared with the loader
+#[derive(Resource)]
+pub struct ScriptEngine(pub Arc<Engine>);
+
+/// Script driving this entity, kept across hot reloads
+#[derive(Component, Debug)]
+#[require(ScriptState)]
+pub struct ChampionScript(pub AssetId<Script>);
+
+/// Values kept by the script between calls, exposed as `this.state`
+#[derive(Component, Debug, Default)]
+pub struct ScriptState(pub Map);
+
+#[derive(Error, Debug)]
+pub enum ScriptError {
+    #[error("{0}")]
+    Io(#[from] std::io::Error),
+
+    #[error("{0}")]
+    Utf8(#[from] std::string::FromUtf8Error),
+
+    #[error("{0}")]
+    Parse(#[from] rhai::ParseError),
+}
+
+/// Snapshot of the scripted entity bound to `this`, requested changes are applied afterwards
+#[derive(Debug, Clone)]
+pub struct ScriptContext {
+    entity: Entity,
+    health: f32,
+    max_health: f32,
+    damage: f32,
+    armor: f32,
+    magic_resist: f32,
+    position: Vec2,
+    buffs: Vec<(&'static str, Entity)>,
+    enemies: Arc<Array>,
+    state: Map,
+    ops: Vec<ScriptOp>,
+}
+
+#[derive(Debug, Clone)]
+enum ScriptOp {
+    SpawnBuff(String, Dynamic),
+    DespawnBuff(Entity),
+    Damage {
+        target: Entity,
+        amount: f32,
+        damage_type: DamageType,
+    },
+    Move(Vec2),
+    Stop,
+    SetSkillEffect(usize, String),
+    SpawnParticle(Entity, u32),
+    DespawnParticle(Entity, u32),
+    SetVital(Entity, Direction, bool),
+    RemoveVital(Entity),
+}
+
+/// Enemy arrays shared by the script calls of one system run, keyed by the caller's team
+#[derive(Default)]
+pub struct ScriptEnemies {
+    tick: Option<Tick>,
+    by_team: HashMap<Option<Team>, Arc<Array>>,
+}
+
+impl ScriptEnemies {
+    fn get(
+        &mut self,
+        tick: Tick,
+        team: Option<&Team>,
+        enemies: impl FnOnce() -> Array,
+    ) -> Arc<Array> {
+        if self.tick != Some(tick) {
+            self.tick = Some(tick);
+            self.by_team.clear();
+        }
+
+        self.by_team
+            .entry(team.cloned())
+            .or_insert_with(|| Arc::new(enemies()))
+            .clone()
+    }
+}
+
+impl Default for ScriptEngine {
+    fn default() -> Self {
+        let mut engine = Engine::new();
+        // Keep the release limits, debug builds default to a quarter of them
+        engine.set_max_expr_depths(64, 32);
+
+        engine
+            .register_type_with_name::<ScriptContext>("Context")
+            .register_get("entity", |ctx: &mut ScriptContext| {
+                ctx.entity.to_bits() as INT
+            })
+            .register_get("health", |ctx: &mut ScriptContext| ctx.health as FLOAT)
+            .register_get("max_health", |ctx: &mut ScriptContext| {
+                ctx.max_health as FLOAT
+            })
+            .register_get("damage", |ctx: &mut ScriptContext| ctx.damage as FLOAT)
+            .register_get("armor", |ctx: &mut ScriptContext| ctx.armor as FLOAT)
+            .register_get("magic_resist", |ctx: &mut ScriptContext| {
+                ctx.magic_resist as FLOAT
+            })
+            .register_get("x", |ctx: &mut ScriptContext| ctx.position.x as FLOAT)
+            .register_get("z", |ctx: &mut ScriptContext| ctx.position.y as FLOAT)
+            .register_get("enemies", |ctx: &mut ScriptContext| {
+                ctx.enemies.as_ref().clone()
+            })
+            .register_get_set(
+                "state",
+                |ctx: &mut ScriptContext| ctx.state.clone(),
+                |ctx: &mut ScriptContext, state: Map| ctx.state = state,
+            )
+            .register_fn("has_buff", |ctx: &mut ScriptContext, name: &str| {
+                ctx.buffs.iter().any(|(buff, _)| *buff == name)
+            })
+            .register_fn("remove_buff", |ctx: &mut ScriptContext, name: &str| {
+                let removed: Vec<Entity> = ctx
+                    .buffs
+                    .iter()
+                    .filter(|(buff, _)| *buff == name)
+                    .map(|(_, entity)| *entity)
+                    .collect();
+                ctx.buffs.retain(|(buff, _)| *buff != name);
+                ctx.ops
+                    .extend(removed.into_iter().map(ScriptOp::DespawnBuff));
+            })
+            .register_fn("spawn_buff", |ctx: &mut ScriptContext, type_path: &str| {
+                ctx.ops
+                    .push(ScriptOp::SpawnBuff(type_path.to_string(), Dynamic::UNIT));
+            })
+            .register_fn(
+                "spawn_buff",
+                |ctx: &mut ScriptContext, type_path: &str, value: Dynamic| {
+                    ctx.ops
+                        .push(ScriptOp::SpawnBuff(type_path.to_string(), value));
+                },
+            )
+            .register_fn(
+                "damage",
+                |ctx: &mut ScriptContext,
+                 target: INT,
+                 amount: FLOAT,
+                 damage_type: &str|
+                 -> Result<(), Box<EvalAltResult>> {
+                    let damage_type = match damage_type {
+                        "Physical" => DamageType::Physical,
+                        "Magic" => DamageType::Magic,
+                        "True" => DamageType::True,
+                        _ => return Err(format!("Unknown damage type {damage_type}").into()),
+                    };
+                    ctx.ops.push(ScriptOp::Damage {
+                        target: script_entity(target)?,
+                        amount,
+                        damage_type,
+                    });
+                    Ok(())
+                },
+            )
+            .register_fn("move_to", |ctx: &mut ScriptContext, x: FLOAT, z: FLOAT| {
+                ctx.ops.push(ScriptOp::Move(Vec2::new(x, z)));
+            })
+            .register_fn("stop", |ctx: &mut ScriptContext| {
+                ctx.ops.push(ScriptOp::Stop);
+            })
+            .register_fn(
+                "set_skill_effect",
+                |ctx: &mut ScriptContext, index: INT, key: &str| {
+                    ctx.ops
+                        .push(ScriptOp::SetSkillEffect(index as usize, key.to_string()));
+                },
+            )
+            .register_fn(
+                "spawn_particle",
+                |ctx: &mut ScriptContext,
+                 target: INT,
+                 name: &str|
+                 -> Result<(), Box<EvalAltResult>> {
+                    ctx.ops.push(ScriptOp::SpawnParticle(
+                        script_entity(target)?,
+                        hash_bin(name),
+                    ));
+                    Ok(())
+                },
+            )
+            .register_fn(
+                "despawn_particle",
+                |ctx: &mut ScriptContext,
+                 target: INT,
+                 name: &str|
+                 -> Result<(), Box<EvalAltResult>> {
+                    ctx.ops.push(ScriptOp::DespawnParticle(
+                        script_entity(target)?,
+                        hash_bin(name),
+                    ));
+                    Ok(())
+                },
+            )
+            .register_fn(
+                "set_vital",
+                |ctx: &mut ScriptContext,
+                 target: INT,
+                 direction: &str,
+                 active: bool|
+                 -> Result<(), Box<EvalAltResult>> {
+                    let direction = match direction {
+                        "Left" => Direction::Left,
+                        "Right" => Direction::Right,
+                        "Up" => Direction::Up,
+                        "Down" => Direction::Down,
+                        _ => return Err(format!("Unknown direction {direction}").into()),
+                    };
+                    ctx.ops.push(ScriptOp::SetVital(
+                        script_entity(target)?,
+                        direction,
+                        active,
+                    ));
+                    Ok(())
+                },
+            )
+            .register_fn(
+                "remove_vital",
+                |ctx: &mut ScriptContext, target: INT| -> Result<(), Box<EvalAltResult>> {
+                    ctx.ops.push(ScriptOp::RemoveVital(script_entity(target)?));
+                    Ok(())
+                },
+            )
+            .register_fn("random", random::<FLOAT>);
+
+        Self(Arc::new(engine))
+    }
+}
+
+fn script_entity(bits: INT) -> Result<Entity, Box<EvalAltResult>> {
+    Entity::try_from_bits(bits as u64).ok_or_else(|| format!("Invalid entity {bits}").into())
+}
+
+/// Deserialize a component from a script value, `()` builds a unit struct. Values missing fields
+/// the type can't default are rejected here instead of when the component is inserted.
+fn script_component(
+    type_path: &str,
+    value: &Dynamic,
+    registry: &TypeRegistry,
+) -> Result<Box<dyn PartialReflect>, SkillEffectError> {
+    let registration = component_registration(type_path, registry)?;
+    let value = match value.is_unit() {
+        true => Dynamic::from_map(Map::new()),
+        false => value.clone(),
+    };
+
+    let component = TypedReflectDeserializer::new(registration, registry)
+        .deserialize(DynamicDeserializer::new(&value))
+        .map_err(|e| SkillEffectError::InvalidComponent(type_path.to_string(), e.to_string()))?;
+
+    registration
+        .data::<ReflectFromReflect>()
+        .and_then(|v| v.from_reflect(component.as_ref()))
+        .map(|v| v.into_partial_reflect())
+        .ok_or_else(|| {
+            SkillEffectError::InvalidComponent(type_path.to_string(), "missing fields".to_string())
+        })
+}
+
+pub struct ScriptLoader {
+    engine: Arc<Engine>,
+}
+
+impl FromWorld for ScriptLoader {
+    fn from_world(world: &mut World) -> Self {
+        Self {
+            engine: world.resource::<ScriptEngine>().0.clone(),
+        }
+    }
+}
+
+impl AssetLoader for ScriptLoader {
+    type Asset = Script;
+
+    type Settings = ();
+
+    type Error = ScriptError;
+
+    async fn load(
+        &self,
+        reader: &mut dyn Reader,
+        _settings: &Self::Settings,
+        load_context: &mut LoadContext<'_>,
+    ) -> Result<Self::Asset, Self::Error> {
+        let mut buf = Vec::new();
+        reader.read_to_end(&mut buf).await?;
+        let source = String::from_utf8(buf)?;
+
+        let name = load_context
+            .path()
+            .file_stem()
+            .map(|v| v.to_string_lossy().to_string())
+            .unwrap_or_default();
+
+        Ok(Script {
+            name,
+            ast: self.engine.compile(source)?,
+        })
+    }
+
+    fn extensions(&self) -> &[&str] {
+        &["rhai"]
+    }
+}
+
+/// Calls script hooks and applies what they request
+#[derive(SystemParam)]
+pub struct ScriptRunner<'w, 's> {
+    commands: Commands<'w, 's>,
+    engine: Res<'w, ScriptEngine>,
+    type_registry: Res<'w, AppTypeRegistry>,
+    res_buff_registry: Res<'w, BuffRegistry>,
+    res_assets_script: Res<'w, Assets<Script>>,
+    q_script: Query<'w, 's, (&'static ChampionScript, &'static mut ScriptState)>,
+    q_stats: Query<
+        'w,
+        's,
+        (
+            Option<&'static Health>,
+            Option<(&'static Damage, &'static BonusDamage)>,
+            Option<&'static Armor>,
+            Option<&'static MagicResist>,
+            Option<&'static Transform>,
+            Option<&'static Buffs>,
+            Option<&'static Team>,
+        ),
+    >,
+    q_champion: Query<
+        'w,
+        's,
+        (
+            Entity,
+            &'static Team,
+            &'static Transform,
+            &'static Health,
+            Option<&'static Buffs>,
+        ),
+        (With<Champion>, Without<Dead>),
+    >,
+    q_buff: Query<'w, 's, &'static Buff>,
+    q_skills: Query<'w, 's, &'static Skills>,
+    q_skill: Query<'w, 's, &'static mut Skill>,
+    enemies: Local<'s, ScriptEnemies>,
+    tick: SystemChangeTick,
+}
+
+impl ScriptRunner<'_, '_> {
+    /// Call `hook` of the entity's script if it defines one with a matching arity
+    pub fn call(&mut self, entity: Entity, hook: &str, args: impl FuncArgs) {
+        let Ok((script, _)) = self.q_script.get(entity) else {
+            return;
+        };
+        let Some(script) = self.res_assets_script.get(script.0) else {
+            return;
+        };
+
+        let mut arg_values = Vec::new();
+        args.parse(&mut arg_values);
+
+        if !script
+            .ast
+            .iter_functions()
+            .any(|f| f.name == hook && f.params.len() == arg_values.len())
+        {
+            return;
+        }
+
+        let Ok((health, damage, armor, magic_resist, transform, buffs, team)) =
+            self.q_stats.get(entity)
+        else {
+            return;
+        };
+
+        let mut this = Dynamic::from(ScriptContext {
+            entity,
+            health: health.map(|v| v.value).unwrap_or_default(),
+            max_health: health.map(|v| v.max).unwrap_or_default(),
+            damage: damage.map(|(v, bonus)| v.total(bonus)).unwrap_or_default(),
+            armor: armor.map(|v| v.0).unwrap_or_default(),
+            magic_resist: magic_resist.map(|v| v.0).unwrap_or_default(),
+            position: transform.map(|v| v.translation.xz()).unwrap_or_default(),
+            buffs: buffs
+                .iter()
+                .flat_map(|v| v.iter())
+                .filter_map(|buff| Some((self.q_buff.get(buff).ok()?.name, buff)))
+                .collect(),
+            enemies: self.enemies.get(self.tick.this_run(), team, || {
+                Self::enemies(&self.q_champion, &self.q_buff, team)
+            }),
+            state: self.q_script.get(entity).unwrap().1 .0.clone(),
+            ops: Vec::new(),
+        });
+
+        let result = self.engine.0.call_fn_with_options::<Dynamic>(
+            CallFnOptions::new()
+                .eval_ast(false)
+                .bind_this_ptr(&mut this),
+            &mut Scope::new(),
+            &script.ast,
+            hook,
+            arg_values,
+        );
+
+        if let Err(e) = result {
+            warn!("{:?} Script {} {} failed: {}", entity, script.name, hook, e);
+            return;
+        }
+
+        let Some(ctx) = this.try_cast::<ScriptContext>() else {
+            return;
+        };
+
+        if let Ok((_, mut state)) = self.q_script.get_mut(entity) {
+            state.0 = ctx.state;
+        }
+
+        for op in ctx.ops {
+            self.apply(entity, op);
+        }
+    }
+
+    /// Living champions of other teams as `#{ entity, x, z, health, max_health, buffs }`
+    fn enemies(
+        q_champion: &Query<
+            (Entity, &Team, &Transform, &Health, Option<&Buffs>),
+            (With<Champion>, Without<Dead>),
+        >,
+        q_buff: &Query<&Buff>,
+        team: Option<&Team>,
+    ) -> Array {
+        q_champion
+            .iter()
+            .filter(|(_, enemy_team, ..)| Some(*enemy_team) != team)
+            .map(|(enemy, _, transform, health, buffs)| {
+                let buffs: Array = buffs
+                    .iter()
+                    .flat_map(|v| v.iter())
+                    .filter_map(|buff| Some(q_buff.get(buff).ok()?.name.into()))
+                    .collect();
+
+                let mut map = Map::new();
+                map.insert("entity".into(), (enemy.to_bits() as INT).into());
+                map.insert("x".into(), (transform.translation.x as FLOAT).into());
+                map.insert("z".into(), (transform.translation.z as FLOAT).into());
+                map.insert("health".into(), (health.value as FLOAT).into());
+                map.insert("max_health".into(), (health.max as FLOAT).into());
+                map.insert("buffs".into(), buffs.into());
+                map.into()
+            })
+            .collect()
+    }
+
+    fn apply(&mut self, entity: Entity, op: ScriptOp) {
+        match op {
+            ScriptOp::SpawnBuff(type_path, value) => {
+                let registry = self.type_registry.read();
+                let component = script_component(&type_path, &value, &registry);
+
+                let component = match component {
+                    Ok(component) => component,
+                    Err(e) => {
+                        warn!("{:?} Script buff failed: {}", entity, e);
+                        return;
+                    }
+                };
+
+                let types = component
+                    .get_represented_type_info()
+                    .map(|v| v.type_id())
+                    .into_iter()
+                    .collect::<Vec<_>>();
+                let Some(name) = self.res_buff_registry.name_of(&types) else {
+                    warn!("{:?} Script buff {} is not registered", entity, type_path);
+                    return;
+                };
+
+                let component: Arc<dyn PartialReflect> = component.into();
+                self.commands.trigger(
+                    CommandBuffApply::new(entity, name)
+                        .with_source(entity)
+                        .with_insert(Arc::new(move |commands: &mut EntityCommands| {
+                            commands.insert_reflect(component.as_ref().to_dynamic());
+                        })),
+                );
+            }
+            ScriptOp::DespawnBuff(buff) => {
+                self.commands.entity(buff).try_despawn();
+            }
+            ScriptOp::Damage {
+                target,
+                amount,
+                damage_type,
+            } => {
+                self.commands.trigger(CommandDamageCreate {
+                    entity: target,
+                    source: entity,
+                    damage_type,
+                    origin: DamageOrigin::Spell,
+                    amount,
+                });
+            }
+            ScriptOp::Move(position) => {
+                self.commands.trigger(CommandMovement {
+                    entity,
+                    priority: 0,
+                    action: MovementAction::Start {
+                        way: MovementWay::Pathfind(Vec3::new(position.x, 0.0, position.y)),
+                        speed: None,
+                        source: "Script".to_string(),
+                    },
+                });
+            }
+            ScriptOp::Stop => {
+                self.commands.trigger(CommandMovement {
+                    entity,
+                    priority: 0,
+                    action: MovementAction::Stop,
+                });
+            }
+            ScriptOp::SetSkillEffect(index, key) => {
+                let Some(&skill_entity) = self
+                    .q_skills
+                    .get(entity)
+                    .ok()
+                    .and_then(|skills| skills.get(index))
+                else {
+                    return;
+                };
+                if let Ok(mut skill) = self.q_skill.get_mut(skill_entity) {
+                    debug!("{:?} Script set skill {} effect to {}", entity, index, key);
+                    skill.key_skill_effect = key.as_str().into();
+                }
+            }
+            ScriptOp::SpawnParticle(target, hash) => {
+                self.commands.try_trigger(CommandSkinParticleSpawn {
+                    entity: target,
+                    hash,
+                });
+            }
+            ScriptOp::DespawnParticle(target, hash) => {
+                self.commands.try_trigger(CommandSkinParticleDespawn {
+                    entity: target,
+                    hash,
+                });
+            }
+            ScriptOp::SetVital(target, direction, active) => {
+                self.commands
+                    .entity(target)
+                    .try_insert(Vital { direction, active });
+            }
+            ScriptOp::RemoveVital(target) => {
+                self.commands.entity(target).try_remove::<Vital>();
+            }
+        }
+    }
+}
+
+fn startup_load_scripts(mut commands: Commands, asset_server: Res<AssetServer>) {
+    commands.insert_resource(ScriptFolder(asset_server.load_folder(SCRIPT_FOLDER)));
+}
+
+fn update_attach_script(
+    mut commands: Commands,
+    q_champion: Query<(Entity, &Name), (With<Champion>, Without<ChampionScript>)>,
+    res_assets_script: Res<Assets<Script>>,
+) {
+    for (entity, name) in q_champion.iter() {
+        let Some((id, _)) = res_assets_script
+            .iter()
+            .find(|(_, script)| script.name.eq_ignore_ascii_case(name.as_str()))
+        else {
+            continue;
+        };
+
+        debug!("{:?} Attaching script {:?}", entity, id);
+        commands.entity(entity).insert(ChampionScript(id));
+    }
+}
+
+fn on_command_skill_before_start(trigger: On<CommandSkillBeforeStart>, mut runner: ScriptRunner) {
+    runner.call(
+        trigger.event_target(),
+        "on_skill_cast",
+        (trigger.index as INT,),
+    );
+}
+
+fn on_event_attack_end(trigger: On<EventAttackEnd>, mut runner: ScriptRunner) {
+    runner.call(
+        trigger.event_target(),
+        "on_attack_end",
+        (trigger.target.to_bits() as INT,),
+    );
+}
+
+fn on_event_damage_create(trigger: On<EventDamageCreate>, mut runner: ScriptRunner) {
+    runner.call(
+        trigger.source,
+        "on_damage_dealt",
+        (
+            trigger.event_target().to_bits() as INT,
+            trigger.damage_result.final_damage as FLOAT,
+        ),
+    );
+}
+
+fn fixed_update_script(
+    q_script: Query<Entity, With<ChampionScript>>,
+    mut runner: ScriptRunner,
+    time: Res<Time<Fixed>>,
+) {
+    for entity in q_script.iter() {
+        runner.call(entity, "on_update", (time.delta_secs() as FLOAT,));
+    }
+}
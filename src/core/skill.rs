@@ -1,18 +1,22 @@
 use std::ops::Deref;
+use std::time::Duration;
 
 use bevy::prelude::*;
-use bevy_behave::prelude::{BehavePlugin, BehaveTree, Tree};
+use bevy_behave::prelude::{BehavePlugin, Tree};
 use bevy_behave::Behave;
 use league_core::{
-    EffectValueCalculationPart, EnumAbilityResourceByCoefficientCalculationPart,
-    EnumGameCalculation, NamedDataValueCalculationPart, SpellObject,
+    EffectValueCalculationPart, EnumAbilityResourceByCoefficientCalculationPart, EnumArea,
+    EnumGameCalculation, NamedDataValueCalculationPart, SpellDataResource, SpellObject,
     StatByCoefficientCalculationPart, StatByNamedDataValueCalculationPart,
     StatBySubPartCalculationPart,
 };
 use league_utils::hash_bin;
 use lol_config::{HashKey, LoadHashKeyTrait};
 
-use crate::{AbilityResource, EventLevelUp, Level};
+use crate::{
+    cooldown_with_haste, AbilityResource, AttackBlock, CommandAttackStop, CommandSkillRelease,
+    EventLevelUp, EventSkillCastStart, Health, Level, MovementBlock, SkillAmmo, SkillCast,
+};
 
 #[derive(Default)]
 pub struct PluginSkill;
@@ -21,7 +25,9 @@ impl Plugin for PluginSkill {
     fn build(&self, app: &mut App) {
         app.init_asset::<SkillEffect>();
 
-        app.add_plugins(BehavePlugin::default());
+        if !app.is_plugin_added::<BehavePlugin>() {
+            app.add_plugins(BehavePlugin::default());
+        }
 
         app.add_observer(on_skill_cast);
         app.add_observer(on_skill_level_up);
@@ -59,6 +65,133 @@ pub struct CoolDown {
     pub duration: f32,
 }
 
+impl CoolDown {
+    /// Share of the cooldown still to run, 0 when ready
+    pub fn remaining_fraction(&self) -> f32 {
+        if self.timer.is_finished() || self.timer.duration().is_zero() {
+            return 0.0;
+        }
+        1.0 - self.timer.fraction()
+    }
+
+    pub fn reset(&mut self) {
+        let remaining = self.timer.remaining();
+        self.timer.tick(remaining);
+    }
+
+    /// Shorten the running cooldown by `seconds`
+    pub fn refund(&mut self, seconds: f32) {
+        let seconds = seconds.clamp(0.0, self.timer.remaining_secs());
+        self.timer.tick(Duration::from_secs_f32(seconds));
+    }
+}
+
+/// Values of a skill at one rank, read from its spell object
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct SkillRank {
+    /// Base cooldown before ability haste, `None` when the spell has none
+    pub cooldown: Option<f32>,
+    pub cooldown_affected_by_haste: bool,
+    pub cost: SkillCost,
+    pub range: Option<f32>,
+}
+
+/// What one cast of a rank spends
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum SkillCost {
+    /// Paid from the caster's ability resource, whichever type it is
+    Resource(f32),
+    /// Charges of the skill's own [`SkillAmmo`], one comes back every `recharge` seconds
+    Ammo { used: u32, max: u32, recharge: f32 },
+}
+
+impl Default for SkillCost {
+    fn default() -> Self {
+        Self::Resource(0.0)
+    }
+}
+
+impl SkillRank {
+    pub fn from_spell(spell: &SpellDataResource, level: usize) -> Self {
+        let max_ammo = rank_value(spell.m_max_ammo.as_ref(), level).filter(|v| *v > 0);
+        let cost = match max_ammo {
+            Some(max) => SkillCost::Ammo {
+                used: rank_value(spell.m_ammo_used.as_ref(), level).map_or(1, |v| v.max(0) as u32),
+                max: max as u32,
+                recharge: rank_value(spell.m_ammo_recharge_time.as_ref(), level).unwrap_or(0.0),
+            },
+            None => SkillCost::Resource(rank_value(spell.mana.as_ref(), level).unwrap_or(0.0)),
+        };
+
+        Self {
+            cooldown: rank_value(spell.cooldown_time.as_ref(), level),
+            cooldown_affected_by_haste: !spell.m_cooldown_not_affected_by_cdr.unwrap_or(false),
+            cost,
+            range: rank_value(spell.cast_range.as_ref(), level).filter(|v| *v > 0.0),
+        }
+    }
+
+    /// Cooldown after applying the caster's ability haste
+    pub fn effective_cooldown(&self, haste: f32) -> Option<f32> {
+        let cooldown = self.cooldown?;
+        if !self.cooldown_affected_by_haste {
+            return Some(cooldown);
+        }
+        Some(cooldown_with_haste(cooldown, haste))
+    }
+}
+
+/// Value of a per-rank spell field, ranks are 1-based and a single entry applies to every rank
+pub fn rank_value<T: Copy>(values: Option<&Vec<T>>, level: usize) -> Option<T> {
+    let values = values?;
+    if let [value] = values.as_slice() {
+        return Some(*value);
+    }
+
+    let value = values.get(level.checked_sub(1)?).copied();
+    if value.is_none() {
+        warn!(
+            "Rank {} out of range of {} per-rank values",
+            level,
+            values.len()
+        );
+    }
+    value
+}
+
+/// Where the cast lands, `None` when the point is out of range and the spell can't be clamped
+fn cast_point(
+    spell: &SpellDataResource,
+    range: Option<f32>,
+    origin: Vec2,
+    point: Vec2,
+) -> Option<Vec2> {
+    let Some(range) = range else {
+        return Some(point);
+    };
+    if origin.distance(point) <= range {
+        return Some(point);
+    }
+
+    match spell.m_targeting_type_data {
+        // Aimed by direction, the effect itself stops at its range
+        Some(
+            EnumArea::Direction
+            | EnumArea::DragDirection
+            | EnumArea::Cone
+            | EnumArea::MySelf
+            | EnumArea::SelfAoe,
+        ) => Some(point),
+        Some(EnumArea::LocationClamped | EnumArea::AreaClamped) => {
+            Some(origin + (point - origin).clamp_length_max(range))
+        }
+        _ if spell.m_project_target_to_cast_range.unwrap_or(false) => {
+            Some(origin + (point - origin).clamp_length_max(range))
+        }
+        _ => None,
+    }
+}
+
 #[derive(Component)]
 #[require(CoolDown)]
 pub struct Skill {
@@ -77,7 +210,7 @@ impl Default for Skill {
     }
 }
 
-#[derive(Asset, TypePath)]
+#[derive(Asset, TypePath, Clone)]
 pub struct SkillEffect(pub Tree<Behave>);
 
 #[derive(Component)]
@@ -108,14 +241,17 @@ pub struct CommandSkillBeforeStart {
     pub point: Vec2,
 }
 
+/// Validate the cast and enter the windup, cooldown and mana are consumed once it ends
 fn on_skill_cast(
     trigger: On<CommandSkillStart>,
     mut commands: Commands,
     skills: Query<&Skills>,
     res_assets_spell_object: Res<Assets<SpellObject>>,
-    res_assets_skill_effect: Res<Assets<SkillEffect>>,
-    mut q_skill: Query<(&Skill, &mut CoolDown)>,
-    mut q_ability_resource: Query<&mut AbilityResource>,
+    q_skill: Query<(&Skill, &CoolDown, Option<&SkillAmmo>)>,
+    q_ability_resource: Query<&AbilityResource>,
+    q_health: Query<&Health>,
+    q_cast: Query<(), With<SkillCast>>,
+    q_transform: Query<&Transform>,
 ) {
     let entity = trigger.event_target();
     let Ok(skills) = skills.get(entity) else {
@@ -124,10 +260,18 @@ fn on_skill_cast(
     let Some(&skill_entity) = skills.0.get(trigger.index) else {
         return;
     };
-    let Ok((skill, mut cooldown)) = q_skill.get_mut(skill_entity) else {
+    let Ok((skill, cooldown, ammo)) = q_skill.get(skill_entity) else {
         return;
     };
 
+    if q_cast.contains(entity) {
+        debug!(
+            "{} skill {} rejected: already casting",
+            entity, trigger.index
+        );
+        return;
+    }
+
     if !cooldown.timer.is_finished() {
         debug!(
             "{} skill {} on cooldown, {:.2}s remaining",
@@ -138,54 +282,80 @@ fn on_skill_cast(
         return;
     }
 
-    let spell_object = res_assets_spell_object
-        .load_hash(skill.key_spell_object)
-        .unwrap();
+    let Some(spell_object) = res_assets_spell_object.load_hash(skill.key_spell_object) else {
+        debug!(
+            "{} skill {} spell object not loaded, cannot cast",
+            entity, trigger.index
+        );
+        return;
+    };
 
     if skill.level == 0 {
         debug!("{} skill {} not learned, cannot cast", entity, trigger.index);
         return;
     }
 
-    let Ok(mut ability_resource) = q_ability_resource.get_mut(entity) else {
+    let Some(spell) = spell_object.m_spell.as_ref() else {
         return;
     };
-
-    if let Some(ref mana) = spell_object.m_spell.as_ref().unwrap().mana {
-        let &current_mana = mana.get(skill.level as usize).unwrap();
-
-        if ability_resource.value < current_mana {
-            debug!(
-                "{} skill {} insufficient mana, requires {:.0}, current {:.0}",
-                entity, trigger.index, current_mana, ability_resource.value
-            );
-            return;
+    let rank = SkillRank::from_spell(spell, skill.level);
+
+    match rank.cost {
+        SkillCost::Resource(cost) => {
+            let Ok(ability_resource) = q_ability_resource.get(entity) else {
+                return;
+            };
+            if !ability_resource.can_pay(cost, q_health.get(entity).ok()) {
+                debug!(
+                    "{} skill {} insufficient {:?}, requires {:.0}, current {:.0}",
+                    entity, trigger.index, ability_resource.ar_type, cost, ability_resource.value
+                );
+                return;
+            }
         }
+        SkillCost::Ammo { used, max, .. } => {
+            let charges = ammo.map_or(max, |v| v.charges);
+            if charges < used {
+                debug!(
+                    "{} skill {} out of ammo, requires {}, current {}",
+                    entity, trigger.index, used, charges
+                );
+                return;
+            }
+        }
+    }
 
-        ability_resource.value -= current_mana;
+    let origin = q_transform
+        .get(entity)
+        .map_or(trigger.point, |v| v.translation.xz());
+    let Some(point) = cast_point(spell, rank.range, origin, trigger.point) else {
         debug!(
-            "{} skill {} consumed {:.0} mana, {:.0} remaining",
-            entity, trigger.index, current_mana, ability_resource.value
+            "{} skill {} rejected: target out of range {:.0}",
+            entity,
+            trigger.index,
+            rank.range.unwrap_or(0.0)
         );
-    }
+        return;
+    };
 
-    let effect_key = skill.key_skill_effect;
+    let cast = SkillCast::new(trigger.index, skill_entity, point, spell, skill.level);
+    let cast_time = cast.timer.duration().as_secs_f32();
 
-    if let Some(effect) = res_assets_skill_effect.load_hash(effect_key) {
-        debug!("{} skill {} starting behavior tree execution", entity, trigger.index);
-        commands.entity(entity).with_child((
-            BehaveTree::new(effect.0.clone()),
-            SkillEffectContext {
-                point: trigger.point,
-            },
-        ));
-    }
+    commands.trigger(EventSkillCastStart {
+        entity,
+        index: trigger.index,
+        cast_time,
+        channel_duration: cast.channel_duration,
+    });
 
-    cooldown.timer = Timer::from_seconds(cooldown.duration, TimerMode::Once);
-    debug!(
-        "{} skill {} starting cooldown {}s",
-        entity, trigger.index, cooldown.duration
-    );
+    commands
+        .entity(entity)
+        .insert((cast, MovementBlock, AttackBlock));
+    commands.trigger(CommandAttackStop { entity });
+
+    if cast_time <= 0.0 {
+        commands.trigger(CommandSkillRelease { entity });
+    }
 }
 
 #[derive(EntityEvent)]
@@ -214,7 +384,10 @@ fn on_skill_level_up(
         return;
     };
 
-    debug!("{} attempting to level up skill: index {}", entity, trigger.index);
+    debug!(
+        "{} attempting to level up skill: index {}",
+        entity, trigger.index
+    );
 
     if skill_points.0 == 0 {
         debug!("{} level up failed: insufficient skill points", entity);
@@ -0,0 +1,394 @@
+use bevy::prelude::*;
+use bevy_behave::prelude::BehaveTree;
+use league_core::SpellDataResource;
+use lol_config::LoadHashKeyTrait;
+
+use crate::{
+    rank_value, AbilityHaste, AbilityResource, AttackBlock, CoolDown, EventDead, Health,
+    MovementBlock, Skill, SkillAmmo, SkillCost, SkillEffect, SkillEffectContext, SkillRank,
+    SkillRecast,
+};
+
+#[derive(Default)]
+pub struct PluginSkillCast;
+
+impl Plugin for PluginSkillCast {
+    fn build(&self, app: &mut App) {
+        app.add_observer(on_command_skill_release);
+        app.add_observer(on_command_skill_interrupt);
+        app.add_observer(on_event_dead);
+
+        app.add_systems(FixedUpdate, fixed_update_skill_cast);
+    }
+}
+
+/// Skill being cast, movement and attacks are blocked until it ends
+#[derive(Component, Debug, Clone)]
+pub struct SkillCast {
+    pub index: usize,
+    pub skill: Entity,
+    pub point: Vec2,
+    pub phase: SkillCastPhase,
+    pub timer: Timer,
+    pub channel_duration: f32,
+    pub flags: SkillCastFlags,
+    /// Cost, cooldown and range of the rank being cast
+    pub rank: SkillRank,
+    /// Behave tree started when the windup ends
+    pub tree: Option<Entity>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SkillCastPhase {
+    Windup,
+    Channel,
+}
+
+/// Cast rules read from the spell data
+#[derive(Debug, Clone, PartialEq)]
+pub struct SkillCastFlags {
+    pub can_move_while_channeling: bool,
+    pub cant_cancel_while_winding_up: bool,
+    pub cant_cancel_while_channeling: bool,
+    pub interrupted_by_attacking: bool,
+    pub interrupted_by_disables: bool,
+    pub consume_cooldown: bool,
+    pub consume_mana: bool,
+}
+
+impl SkillCastFlags {
+    pub fn from_spell(spell: &SpellDataResource) -> Self {
+        Self {
+            can_move_while_channeling: spell.m_can_move_while_channeling.unwrap_or(false),
+            cant_cancel_while_winding_up: spell.m_cant_cancel_while_winding_up.unwrap_or(false),
+            cant_cancel_while_channeling: spell.m_cant_cancel_while_channeling.unwrap_or(false),
+            interrupted_by_attacking: spell.m_channel_is_interrupted_by_attacking.unwrap_or(false),
+            interrupted_by_disables: spell.m_channel_is_interrupted_by_disables.unwrap_or(true),
+            consume_cooldown: !spell.m_does_not_consume_cooldown.unwrap_or(false),
+            consume_mana: !spell.m_does_not_consume_mana.unwrap_or(false),
+        }
+    }
+}
+
+impl SkillCast {
+    pub fn new(
+        index: usize,
+        skill: Entity,
+        point: Vec2,
+        spell: &SpellDataResource,
+        level: usize,
+    ) -> Self {
+        let cast_time = spell.m_cast_time.unwrap_or(0.0).max(0.0);
+        let channel_duration = rank_value(spell.m_channel_duration.as_ref(), level)
+            .unwrap_or(0.0)
+            .max(0.0);
+
+        Self {
+            index,
+            skill,
+            point,
+            phase: SkillCastPhase::Windup,
+            timer: Timer::from_seconds(cast_time, TimerMode::Once),
+            channel_duration,
+            flags: SkillCastFlags::from_spell(spell),
+            rank: SkillRank::from_spell(spell, level),
+            tree: None,
+        }
+    }
+
+    /// Whether `reason` is allowed to cancel the cast in its current phase
+    pub fn can_interrupt(&self, reason: &SkillInterruptReason) -> bool {
+        match (reason, self.phase) {
+            (SkillInterruptReason::Death | SkillInterruptReason::InsufficientResource, _) => true,
+            (SkillInterruptReason::Disable, SkillCastPhase::Windup) => true,
+            (SkillInterruptReason::Disable, SkillCastPhase::Channel) => {
+                self.flags.interrupted_by_disables
+            }
+            (SkillInterruptReason::Move, SkillCastPhase::Windup) => {
+                !self.flags.cant_cancel_while_winding_up
+            }
+            (SkillInterruptReason::Move, SkillCastPhase::Channel) => {
+                !self.flags.cant_cancel_while_channeling && !self.flags.can_move_while_channeling
+            }
+            (SkillInterruptReason::Attack, SkillCastPhase::Windup) => false,
+            (SkillInterruptReason::Attack, SkillCastPhase::Channel) => {
+                self.flags.interrupted_by_attacking
+            }
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SkillInterruptReason {
+    /// Crowd control such as stuns, silences and knock-ups
+    Disable,
+    Move,
+    Attack,
+    Death,
+    /// Not enough mana or other resource left to pay when the windup ends
+    InsufficientResource,
+}
+
+/// End the windup of the current cast: consume cooldown and mana, then start the skill effect
+#[derive(EntityEvent, Debug)]
+pub struct CommandSkillRelease {
+    pub entity: Entity,
+}
+
+/// Try to cancel the current cast, ignored when the spell can't be cancelled for `reason`
+#[derive(EntityEvent, Debug)]
+pub struct CommandSkillInterrupt {
+    pub entity: Entity,
+    pub reason: SkillInterruptReason,
+}
+
+#[derive(EntityEvent, Debug)]
+pub struct EventSkillCastStart {
+    pub entity: Entity,
+    pub index: usize,
+    pub cast_time: f32,
+    pub channel_duration: f32,
+}
+
+#[derive(EntityEvent, Debug)]
+pub struct EventSkillCastEnd {
+    pub entity: Entity,
+    pub index: usize,
+}
+
+#[derive(EntityEvent, Debug)]
+pub struct EventSkillInterrupted {
+    pub entity: Entity,
+    pub index: usize,
+    pub reason: SkillInterruptReason,
+}
+
+fn end_cast(commands: &mut Commands, entity: Entity) {
+    commands
+        .entity(entity)
+        .try_remove::<(SkillCast, MovementBlock, AttackBlock)>();
+}
+
+fn on_command_skill_release(
+    trigger: On<CommandSkillRelease>,
+    mut commands: Commands,
+    mut q_cast: Query<&mut SkillCast>,
+    mut q_skill: Query<(
+        &Skill,
+        &mut CoolDown,
+        Option<&mut SkillRecast>,
+        Option<&mut SkillAmmo>,
+    )>,
+    mut q_ability_resource: Query<&mut AbilityResource>,
+    mut q_health: Query<&mut Health>,
+    q_ability_haste: Query<&AbilityHaste>,
+    res_assets_skill_effect: Res<Assets<SkillEffect>>,
+) {
+    let entity = trigger.event_target();
+
+    let Ok(mut cast) = q_cast.get_mut(entity) else {
+        return;
+    };
+    if cast.phase != SkillCastPhase::Windup {
+        return;
+    }
+    let Ok((skill, mut cooldown, mut recast, mut ammo)) = q_skill.get_mut(cast.skill) else {
+        end_cast(&mut commands, entity);
+        return;
+    };
+
+    // Only the first stage of a recast skill costs mana or ammo
+    let recasting = recast.as_ref().is_some_and(|v| v.is_active());
+    let key_skill_effect = recast
+        .as_ref()
+        .and_then(|v| v.effect())
+        .unwrap_or(skill.key_skill_effect);
+
+    let paid = match cast.rank.cost {
+        _ if !cast.flags.consume_mana || recasting => true,
+        SkillCost::Resource(cost) => pay_resource(
+            &mut q_ability_resource,
+            &mut q_health,
+            entity,
+            cast.index,
+            cost,
+        ),
+        SkillCost::Ammo {
+            used,
+            max,
+            recharge,
+        } => match ammo.as_mut() {
+            Some(ammo) => ammo.spend(used),
+            None => {
+                let mut ammo = SkillAmmo::new(max, recharge);
+                let paid = ammo.spend(used);
+                commands.entity(cast.skill).insert(ammo);
+                paid
+            }
+        },
+    };
+
+    if !paid {
+        end_cast(&mut commands, entity);
+        commands.trigger(EventSkillInterrupted {
+            entity,
+            index: cast.index,
+            reason: SkillInterruptReason::InsufficientResource,
+        });
+        return;
+    }
+
+    let haste = q_ability_haste.get(entity).map_or(0.0, |v| v.0);
+    if let Some(duration) = cast.rank.effective_cooldown(haste) {
+        cooldown.duration = duration;
+    }
+
+    let duration = match recast.as_mut() {
+        Some(recast) => recast.advance(cooldown.duration),
+        None => cooldown.duration,
+    };
+    if cast.flags.consume_cooldown {
+        cooldown.timer = Timer::from_seconds(duration, TimerMode::Once);
+        debug!(
+            "{} skill {} starting cooldown {}s",
+            entity, cast.index, duration
+        );
+    }
+
+    if let Some(effect) = res_assets_skill_effect.load_hash(key_skill_effect) {
+        debug!(
+            "{} skill {} starting behavior tree execution",
+            entity, cast.index
+        );
+        let tree = commands
+            .spawn((
+                BehaveTree::new(effect.0.clone()),
+                SkillEffectContext { point: cast.point },
+                ChildOf(entity),
+            ))
+            .id();
+        cast.tree = Some(tree);
+    }
+
+    if cast.channel_duration <= 0.0 {
+        end_cast(&mut commands, entity);
+        commands.trigger(EventSkillCastEnd {
+            entity,
+            index: cast.index,
+        });
+        return;
+    }
+
+    cast.phase = SkillCastPhase::Channel;
+    cast.timer = Timer::from_seconds(cast.channel_duration, TimerMode::Once);
+    if cast.flags.can_move_while_channeling {
+        commands.entity(entity).remove::<MovementBlock>();
+    }
+}
+
+/// Pay a resource cost at release, false when the caster can no longer afford it
+fn pay_resource(
+    q_ability_resource: &mut Query<&mut AbilityResource>,
+    q_health: &mut Query<&mut Health>,
+    entity: Entity,
+    index: usize,
+    cost: f32,
+) -> bool {
+    if cost <= 0.0 {
+        return true;
+    }
+    let Ok(mut ability_resource) = q_ability_resource.get_mut(entity) else {
+        return false;
+    };
+
+    let mut health = q_health.get_mut(entity).ok();
+    if !ability_resource.can_pay(cost, health.as_deref()) {
+        debug!(
+            "{} skill {} insufficient {:?} at end of cast, requires {:.0}, current {:.0}",
+            entity, index, ability_resource.ar_type, cost, ability_resource.value
+        );
+        return false;
+    }
+
+    ability_resource.pay(cost, health.as_deref_mut());
+    debug!(
+        "{} skill {} paid {:.0} {:?}, {:.0} left",
+        entity, index, cost, ability_resource.ar_type, ability_resource.value
+    );
+    true
+}
+
+fn on_command_skill_interrupt(
+    trigger: On<CommandSkillInterrupt>,
+    mut commands: Commands,
+    q_cast: Query<&SkillCast>,
+) {
+    let entity = trigger.event_target();
+
+    let Ok(cast) = q_cast.get(entity) else {
+        return;
+    };
+
+    if !cast.can_interrupt(&trigger.reason) {
+        return;
+    }
+
+    debug!(
+        "{} skill {} interrupted during {:?}: {:?}",
+        entity, cast.index, cast.phase, trigger.reason
+    );
+
+    // The channel's effect stops with it, a windup hasn't started one
+    if let Some(tree) = cast.tree {
+        commands.entity(tree).try_despawn();
+    }
+
+    end_cast(&mut commands, entity);
+    commands.trigger(EventSkillInterrupted {
+        entity,
+        index: cast.index,
+        reason: trigger.reason.clone(),
+    });
+}
+
+fn on_event_dead(
+    trigger: On<EventDead>,
+    mut commands: Commands,
+    q_cast: Query<(), With<SkillCast>>,
+) {
+    let entity = trigger.event_target();
+
+    if q_cast.contains(entity) {
+        commands.trigger(CommandSkillInterrupt {
+            entity,
+            reason: SkillInterruptReason::Death,
+        });
+    }
+}
+
+fn fixed_update_skill_cast(
+    mut commands: Commands,
+    mut q_cast: Query<(Entity, &mut SkillCast)>,
+    time: Res<Time<Fixed>>,
+) {
+    for (entity, mut cast) in q_cast.iter_mut() {
+        cast.timer.tick(time.delta());
+
+        if !cast.timer.is_finished() {
+            continue;
+        }
+
+        match cast.phase {
+            SkillCastPhase::Windup => {
+                commands.trigger(CommandSkillRelease { entity });
+            }
+            SkillCastPhase::Channel => {
+                end_cast(&mut commands, entity);
+                commands.trigger(EventSkillCastEnd {
+                    entity,
+                    index: cast.index,
+                });
+            }
+        }
+    }
+}
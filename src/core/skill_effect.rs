@@ -0,0 +1,356 @@
+use std::collections::BTreeMap;
+use std::sync::Arc;
+
+use bevy::asset::io::Reader;
+use bevy::asset::{AssetLoader, LoadContext, LoadedFolder};
+use bevy::ecs::reflect::ReflectCommandExt;
+use bevy::prelude::*;
+use bevy::reflect::serde::TypedReflectDeserializer;
+use bevy::reflect::{PartialReflect, TypeRegistration, TypeRegistry, TypeRegistryArc};
+use bevy::scene::ron;
+use bevy_behave::prelude::{NodeMut, Tree};
+use bevy_behave::Behave;
+use league_utils::hash_bin;
+use lol_config::LoadHashKeyTrait;
+use serde::de::DeserializeSeed;
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+use crate::{
+    ActionAnimationPlay, ActionAttackReset, ActionBuffSpawn, ActionCommand, ActionDamage,
+    ActionDamageEffect, ActionDash, ActionParticleDespawn, ActionParticleSpawn, ActionSkillshot,
+    DamageShape, DamageType, DashDamage, DashMoveType, SkillEffect, TargetDamage, TargetFilter,
+};
+
+/// Folder under `assets` scanned for `*.skill.ron` files
+pub const SKILL_EFFECT_FOLDER: &str = "skill_effects";
+
+/// Loads skill effects from RON files, build with the `dev` feature to hot reload them
+#[derive(Default)]
+pub struct PluginSkillEffect;
+
+impl Plugin for PluginSkillEffect {
+    fn build(&self, app: &mut App) {
+        app.init_asset::<SkillEffectSet>();
+        app.init_asset_loader::<SkillEffectLoader>();
+
+        app.add_systems(Startup, startup_load_skill_effects);
+        app.add_systems(Update, update_skill_effect_set);
+    }
+}
+
+/// Skill effects of a `*.skill.ron` file, keyed by spell object path
+#[derive(Asset, TypePath)]
+pub struct SkillEffectSet(pub Vec<(String, SkillEffect)>);
+
+#[derive(Resource)]
+pub struct SkillEffectFolder(pub Handle<LoadedFolder>);
+
+/// Behave tree node as written in a skill effect file
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub enum SkillEffectNode {
+    Sequence(Vec<SkillEffectNode>),
+    Fallback(Vec<SkillEffectNode>),
+    /// Runs the second node when the first succeeds, the optional third one otherwise
+    IfThen(Vec<SkillEffectNode>),
+    Invert(Box<SkillEffectNode>),
+    AlwaysSucceed,
+    AlwaysFail,
+    Wait(f32),
+    AnimationPlay(String),
+    ParticleSpawn(String),
+    ParticleDespawn(String),
+    AttackReset,
+    Dash {
+        skill: String,
+        move_type: DashMoveType,
+        speed: f32,
+        #[serde(default)]
+        damage: Option<SkillEffectDashDamage>,
+    },
+    Damage {
+        skill: String,
+        effects: Vec<SkillEffectDamage>,
+    },
+    Skillshot {
+        skill: String,
+        #[serde(default)]
+        damage: Option<SkillEffectTargetDamage>,
+    },
+    /// Spawn a buff on the caster, components are keyed by their full or short type path
+    BuffSpawn(BTreeMap<String, ron::Value>),
+    /// Insert components on the enemy closest to the cast point
+    Command(BTreeMap<String, ron::Value>),
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SkillEffectTargetDamage {
+    pub filter: TargetFilter,
+    /// Name of the spell data value holding the damage
+    pub amount: String,
+    pub damage_type: DamageType,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SkillEffectDashDamage {
+    pub radius_end: f32,
+    pub damage: SkillEffectTargetDamage,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SkillEffectDamage {
+    pub shape: DamageShape,
+    pub damage_list: Vec<SkillEffectTargetDamage>,
+    #[serde(default)]
+    pub particle: Option<String>,
+}
+
+#[derive(Error, Debug)]
+pub enum SkillEffectError {
+    #[error("{0}")]
+    Io(#[from] std::io::Error),
+
+    #[error("{0}")]
+    Ron(#[from] ron::error::SpannedError),
+
+    #[error("Unknown component type {0}")]
+    UnknownComponent(String),
+
+    #[error("Invalid value for component {0}: {1}")]
+    InvalidComponent(String, String),
+}
+
+impl From<&SkillEffectTargetDamage> for TargetDamage {
+    fn from(value: &SkillEffectTargetDamage) -> Self {
+        Self {
+            filter: value.filter.clone(),
+            amount: hash_bin(&value.amount),
+            damage_type: value.damage_type,
+        }
+    }
+}
+
+impl SkillEffectNode {
+    /// Build the behave tree of this node with buff components resolved from the registry
+    pub fn to_skill_effect(
+        &self,
+        registry: &TypeRegistry,
+    ) -> Result<SkillEffect, SkillEffectError> {
+        let (behave, children) = self.to_behave(registry)?;
+        let mut tree = Tree::new(behave);
+        for child in children {
+            child.append_to(&mut tree.root_mut(), registry)?;
+        }
+        Ok(SkillEffect(tree))
+    }
+
+    fn append_to(
+        &self,
+        parent: &mut NodeMut<Behave>,
+        registry: &TypeRegistry,
+    ) -> Result<(), SkillEffectError> {
+        let (behave, children) = self.to_behave(registry)?;
+        let mut node = parent.append(behave);
+        for child in children {
+            child.append_to(&mut node, registry)?;
+        }
+        Ok(())
+    }
+
+    fn to_behave(
+        &self,
+        registry: &TypeRegistry,
+    ) -> Result<(Behave, &[SkillEffectNode]), SkillEffectError> {
+        let behave = match self {
+            SkillEffectNode::Sequence(children) => return Ok((Behave::Sequence, children)),
+            SkillEffectNode::Fallback(children) => return Ok((Behave::Fallback, children)),
+            SkillEffectNode::IfThen(children) => return Ok((Behave::IfThen, children)),
+            SkillEffectNode::Invert(child) => {
+                return Ok((Behave::Invert, std::slice::from_ref(child.as_ref())))
+            }
+            SkillEffectNode::AlwaysSucceed => Behave::AlwaysSucceed,
+            SkillEffectNode::AlwaysFail => Behave::AlwaysFail,
+            SkillEffectNode::Wait(secs) => Behave::Wait(*secs),
+            SkillEffectNode::AnimationPlay(name) => Behave::trigger(ActionAnimationPlay {
+                hash: hash_bin(name),
+            }),
+            SkillEffectNode::ParticleSpawn(name) => Behave::trigger(ActionParticleSpawn {
+                hash: hash_bin(name),
+            }),
+            SkillEffectNode::ParticleDespawn(name) => Behave::trigger(ActionParticleDespawn {
+                hash: hash_bin(name),
+            }),
+            SkillEffectNode::AttackReset => Behave::trigger(ActionAttackReset),
+            SkillEffectNode::Dash {
+                skill,
+                move_type,
+                speed,
+                damage,
+            } => Behave::trigger(ActionDash {
+                skill: skill.into(),
+                move_type: move_type.clone(),
+                speed: *speed,
+                damage: damage.as_ref().map(|v| DashDamage {
+                    radius_end: v.radius_end,
+                    damage: (&v.damage).into(),
+                }),
+            }),
+            SkillEffectNode::Damage { skill, effects } => Behave::trigger(ActionDamage {
+                entity: Entity::PLACEHOLDER,
+                skill: skill.into(),
+                effects: effects
+                    .iter()
+                    .map(|v| ActionDamageEffect {
+                        shape: v.shape.clone(),
+                        damage_list: v.damage_list.iter().map(Into::into).collect(),
+                        particle: v.particle.as_deref().map(hash_bin),
+                    })
+                    .collect(),
+            }),
+            SkillEffectNode::Skillshot { skill, damage } => Behave::trigger(ActionSkillshot {
+                skill: skill.into(),
+                damage: damage.as_ref().map(Into::into),
+            }),
+            SkillEffectNode::BuffSpawn(components) => {
+                let components = reflect_components(components, registry)?;
+                Behave::trigger(ActionBuffSpawn {
+                    types: components
+                        .iter()
+                        .filter_map(|v| v.get_represented_type_info())
+                        .map(|v| v.type_id())
+                        .collect(),
+                    insert: Arc::new(move |commands: &mut EntityCommands| {
+                        for component in components.iter() {
+                            commands.insert_reflect(component.to_dynamic());
+                        }
+                    }),
+                })
+            }
+            SkillEffectNode::Command(components) => {
+                let components = reflect_components(components, registry)?;
+                Behave::trigger(ActionCommand {
+                    bundle: Arc::new(move |commands: &mut EntityCommands| {
+                        for component in components.iter() {
+                            commands.insert_reflect(component.to_dynamic());
+                        }
+                    }),
+                })
+            }
+        };
+
+        Ok((behave, &[]))
+    }
+}
+
+fn reflect_components(
+    components: &BTreeMap<String, ron::Value>,
+    registry: &TypeRegistry,
+) -> Result<Vec<Box<dyn PartialReflect>>, SkillEffectError> {
+    components
+        .iter()
+        .map(|(type_path, value)| reflect_component(type_path, value.clone(), registry))
+        .collect()
+}
+
+/// Registration of a component by its full or short type path
+pub fn component_registration<'a>(
+    type_path: &str,
+    registry: &'a TypeRegistry,
+) -> Result<&'a TypeRegistration, SkillEffectError> {
+    registry
+        .get_with_type_path(type_path)
+        .or_else(|| registry.get_with_short_type_path(type_path))
+        .filter(|v| v.data::<ReflectComponent>().is_some())
+        .ok_or_else(|| SkillEffectError::UnknownComponent(type_path.to_string()))
+}
+
+/// Deserialize a component by its full or short type path
+pub fn reflect_component(
+    type_path: &str,
+    value: ron::Value,
+    registry: &TypeRegistry,
+) -> Result<Box<dyn PartialReflect>, SkillEffectError> {
+    let registration = component_registration(type_path, registry)?;
+
+    // Unit structs are reflected as structs without fields
+    let value = match value {
+        ron::Value::Unit => ron::Value::Map(default()),
+        value => value,
+    };
+
+    TypedReflectDeserializer::new(registration, registry)
+        .deserialize(value)
+        .map_err(|e| SkillEffectError::InvalidComponent(type_path.to_string(), e.to_string()))
+}
+
+pub struct SkillEffectLoader {
+    type_registry: TypeRegistryArc,
+}
+
+impl FromWorld for SkillEffectLoader {
+    fn from_world(world: &mut World) -> Self {
+        Self {
+            type_registry: world.resource::<AppTypeRegistry>().0.clone(),
+        }
+    }
+}
+
+impl AssetLoader for SkillEffectLoader {
+    type Asset = SkillEffectSet;
+
+    type Settings = ();
+
+    type Error = SkillEffectError;
+
+    async fn load(
+        &self,
+        reader: &mut dyn Reader,
+        _settings: &Self::Settings,
+        _load_context: &mut LoadContext<'_>,
+    ) -> Result<Self::Asset, Self::Error> {
+        let mut buf = Vec::new();
+        reader.read_to_end(&mut buf).await?;
+
+        let nodes: BTreeMap<String, SkillEffectNode> = ron::de::from_bytes(&buf)?;
+
+        let registry = self.type_registry.read();
+        let effects = nodes
+            .into_iter()
+            .map(|(key, node)| Ok((key, node.to_skill_effect(&registry)?)))
+            .collect::<Result<_, SkillEffectError>>()?;
+
+        Ok(SkillEffectSet(effects))
+    }
+
+    fn extensions(&self) -> &[&str] {
+        &["skill.ron"]
+    }
+}
+
+fn startup_load_skill_effects(mut commands: Commands, asset_server: Res<AssetServer>) {
+    commands.insert_resource(SkillEffectFolder(
+        asset_server.load_folder(SKILL_EFFECT_FOLDER),
+    ));
+}
+
+/// Publish loaded and reloaded skill effects under their spell object keys
+fn update_skill_effect_set(
+    mut events: MessageReader<AssetEvent<SkillEffectSet>>,
+    res_assets_skill_effect_set: Res<Assets<SkillEffectSet>>,
+    mut res_assets_skill_effect: ResMut<Assets<SkillEffect>>,
+) {
+    for event in events.read() {
+        let (AssetEvent::Added { id } | AssetEvent::Modified { id }) = event else {
+            continue;
+        };
+
+        let Some(skill_effect_set) = res_assets_skill_effect_set.get(*id) else {
+            continue;
+        };
+
+        for (key, skill_effect) in skill_effect_set.0.iter() {
+            debug!("Skill effect loaded {}", key);
+            res_assets_skill_effect.add_hash(key, skill_effect.clone());
+        }
+    }
+}
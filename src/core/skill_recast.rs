@@ -0,0 +1,93 @@
+use bevy::prelude::*;
+use lol_config::HashKey;
+
+use crate::{CoolDown, SkillEffect};
+
+#[derive(Default)]
+pub struct PluginSkillRecast;
+
+impl Plugin for PluginSkillRecast {
+    fn build(&self, app: &mut App) {
+        app.add_systems(FixedUpdate, fixed_update_skill_recast);
+    }
+}
+
+/// Skill cast in several stages, each recast plays the next effect until the last one or the
+/// recast window runs out, then the skill goes on its full cooldown
+#[derive(Component, Debug, Clone)]
+pub struct SkillRecast {
+    pub stages: Vec<HashKey<SkillEffect>>,
+    /// Seconds allowed between two stages
+    pub window: f32,
+    /// Cooldown before the next stage can be cast
+    pub stage_cooldown: f32,
+    pub stage: usize,
+    pub timer: Option<Timer>,
+}
+
+impl SkillRecast {
+    pub fn new(stages: Vec<HashKey<SkillEffect>>, window: f32, stage_cooldown: f32) -> Self {
+        Self {
+            stages,
+            window,
+            stage_cooldown,
+            stage: 0,
+            timer: None,
+        }
+    }
+
+    /// Effect played by the next cast
+    pub fn effect(&self) -> Option<HashKey<SkillEffect>> {
+        self.stages.get(self.stage).copied()
+    }
+
+    /// Whether the skill is waiting for a recast
+    pub fn is_active(&self) -> bool {
+        self.stage > 0
+    }
+
+    /// Seconds left to recast, `None` when no recast is pending
+    pub fn remaining(&self) -> Option<f32> {
+        self.timer.as_ref().map(|v| v.remaining_secs())
+    }
+
+    /// Move to the next stage after a cast and return the cooldown to start
+    pub fn advance(&mut self, cooldown: f32) -> f32 {
+        if self.stage + 1 < self.stages.len() {
+            self.stage += 1;
+            self.timer = Some(Timer::from_seconds(self.window, TimerMode::Once));
+            self.stage_cooldown
+        } else {
+            self.reset();
+            cooldown
+        }
+    }
+
+    pub fn reset(&mut self) {
+        self.stage = 0;
+        self.timer = None;
+    }
+}
+
+fn fixed_update_skill_recast(
+    mut q_skill: Query<(Entity, &mut SkillRecast, &mut CoolDown)>,
+    time: Res<Time<Fixed>>,
+) {
+    for (entity, mut recast, mut cooldown) in q_skill.iter_mut() {
+        let Some(timer) = recast.timer.as_mut() else {
+            continue;
+        };
+
+        timer.tick(time.delta());
+        if !timer.is_finished() {
+            continue;
+        }
+
+        debug!(
+            "{} recast window expired at stage {}, starting cooldown {}s",
+            entity, recast.stage, cooldown.duration
+        );
+        recast.reset();
+        cooldown.timer = Timer::from_seconds(cooldown.duration, TimerMode::Once);
+    }
+}
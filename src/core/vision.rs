@@ -0,0 +1,161 @@
+use std::collections::HashSet;
+
+use bevy::prelude::*;
+use league_core::VisionPathingFlags;
+use lol_config::ConfigNavigationGrid;
+use lol_core::Team;
+use serde::{Deserialize, Serialize};
+
+use crate::{Controller, Dead, ResourceGrid};
+
+#[derive(Default)]
+pub struct PluginVision;
+
+impl Plugin for PluginVision {
+    fn build(&self, app: &mut App) {
+        app.add_systems(FixedPostUpdate, update_vision);
+        app.add_systems(Update, update_fog_of_war);
+    }
+}
+
+/// Sight radius of a unit, from the CharacterRecord perception bubble
+#[derive(Component, Clone, Serialize, Deserialize)]
+#[require(TeamVisibility)]
+pub struct SightRange(pub f32);
+
+/// Unit can only be seen by enemies with true sight, e.g. stealth wards
+#[derive(Component, Default, Clone, Serialize, Deserialize)]
+pub struct Stealth;
+
+/// Vision of this unit also reveals stealthed units, e.g. control wards
+#[derive(Component, Default, Clone, Serialize, Deserialize)]
+pub struct TrueSight;
+
+/// Teams that currently see the unit, a unit is always visible to its own team
+#[derive(Component, Default, Debug, Clone, PartialEq)]
+pub struct TeamVisibility {
+    pub teams: HashSet<Team>,
+}
+
+impl TeamVisibility {
+    pub fn is_visible_to(&self, team: &Team) -> bool {
+        self.teams.contains(team)
+    }
+}
+
+/// Units without vision info are treated as visible to everyone
+pub fn is_visible_to(visibility: Option<&TeamVisibility>, team: &Team) -> bool {
+    visibility.is_none_or(|v| v.is_visible_to(team))
+}
+
+struct Viewer {
+    team: Team,
+    position: Vec2,
+    range: f32,
+    true_sight: bool,
+}
+
+fn update_vision(
+    q_viewer: Query<(&Team, &Transform, &SightRange, Has<TrueSight>), Without<Dead>>,
+    mut q_target: Query<(&Team, &Transform, &mut TeamVisibility, Has<Stealth>)>,
+    res_grid: Option<Res<ResourceGrid>>,
+    res_assets_grid: Option<Res<Assets<ConfigNavigationGrid>>>,
+) {
+    let grid = match (&res_grid, &res_assets_grid) {
+        (Some(res_grid), Some(assets)) => assets.get(&res_grid.0),
+        _ => None,
+    };
+
+    let viewers: Vec<Viewer> = q_viewer
+        .iter()
+        .map(|(team, transform, sight_range, true_sight)| Viewer {
+            team: team.clone(),
+            position: transform.translation.xz(),
+            range: sight_range.0,
+            true_sight,
+        })
+        .collect();
+
+    for (team, transform, mut visibility, stealth) in q_target.iter_mut() {
+        let position = transform.translation.xz();
+        let flags = grid
+            .map(|grid| grid.get_cell_by_position(&position).vision_pathing_flags)
+            .unwrap_or(VisionPathingFlags::Walkable);
+
+        let mut teams = HashSet::from([team.clone()]);
+
+        if flags.contains(VisionPathingFlags::AlwaysVisible) && !stealth {
+            teams.extend([Team::Order, Team::Chaos, Team::Neutral]);
+        }
+
+        for viewer in viewers.iter() {
+            if teams.contains(&viewer.team) {
+                continue;
+            }
+
+            if stealth && !viewer.true_sight {
+                continue;
+            }
+
+            if viewer.position.distance(position) > viewer.range {
+                continue;
+            }
+
+            if let Some(grid) = grid {
+                if !has_vision(grid, viewer.position, position) {
+                    continue;
+                }
+            }
+
+            teams.insert(viewer.team.clone());
+        }
+
+        // Team only areas such as the fountains can't be seen by the other team
+        if flags.contains(VisionPathingFlags::BlueTeamOnly) {
+            teams.retain(|v| v == team || *v == Team::Order);
+        }
+        if flags.contains(VisionPathingFlags::RedTeamOnly) {
+            teams.retain(|v| v == team || *v == Team::Chaos);
+        }
+
+        let new_visibility = TeamVisibility { teams };
+        if *visibility != new_visibility {
+            *visibility = new_visibility;
+        }
+    }
+}
+
+/// Check if a unit at `from` can see the position `to`, walls block the line of sight
+/// and units in brush can only be seen from inside the same brush
+pub fn has_vision(grid: &ConfigNavigationGrid, from: Vec2, to: Vec2) -> bool {
+    let target_in_brush = grid.get_cell_by_position(&to).is_brush();
+    if target_in_brush && !grid.get_cell_by_position(&from).is_brush() {
+        return false;
+    }
+
+    let target_cell = grid.get_cell_xy_by_position(&to);
+    grid.raycast_by(from, to, |xy| {
+        let cell = grid.get_cell_by_xy(xy);
+        xy != target_cell && (cell.blocks_vision() || (target_in_brush && !cell.is_brush()))
+    })
+    .is_none()
+}
+
+/// Hide units the controlled team can't see
+fn update_fog_of_war(
+    q_controller: Query<&Team, With<Controller>>,
+    mut q_visibility: Query<(&TeamVisibility, &mut Visibility)>,
+) {
+    let Ok(team) = q_controller.single() else {
+        return;
+    };
+
+    for (team_visibility, mut visibility) in q_visibility.iter_mut() {
+        let new_visibility = if team_visibility.is_visible_to(team) {
+            Visibility::Inherited
+        } else {
+            Visibility::Hidden
+        };
+        visibility.set_if_neq(new_visibility);
+    }
+}
@@ -1,17 +1,25 @@
 mod barrack;
+mod bot;
 mod champion;
 mod champions;
 mod inhibitor;
+mod jungle;
 mod minion;
 mod nexus;
 mod shpere;
+mod structure;
 mod turret;
+mod ward;
 
 pub use barrack::*;
+pub use bot::*;
 pub use champion::*;
 pub use champions::*;
 pub use inhibitor::*;
+pub use jungle::*;
 pub use minion::*;
 pub use nexus::*;
 pub use shpere::*;
+pub use structure::*;
 pub use turret::*;
+pub use ward::*;
@@ -10,7 +10,7 @@ use league_core::{
 use lol_config::{HashKey, LoadHashKeyTrait};
 use lol_core::{Lane, Team};
 
-use crate::core::{Armor, CommandCharacterSpawn, Damage, Health, Movement};
+use crate::core::{Armor, CommandCharacterSpawn, Damage, Health, MagicResist, Movement};
 use crate::entities::Minion;
 use crate::{CommandCharacterLoad, MapName, MapState, MinionPath};
 
@@ -58,9 +58,28 @@ pub struct Barrack {
     pub wave_count: u32,
 }
 
+/// Destroyed inhibitors waiting to respawn
 #[derive(Resource, Default)]
 pub struct InhibitorState {
-    pub inhibitors_down: usize,
+    /// Owner team and lane of each destroyed inhibitor
+    pub inhibitors_down: Vec<(Team, Lane)>,
+}
+
+impl InhibitorState {
+    /// Number of destroyed inhibitors that don't belong to `team`
+    pub fn enemy_inhibitors_down(&self, team: &Team) -> usize {
+        self.inhibitors_down
+            .iter()
+            .filter(|(owner, _)| owner != team)
+            .count()
+    }
+
+    /// Whether an enemy inhibitor of the lane is down, letting the lane spawn super minions
+    pub fn is_lane_open(&self, team: &Team, lane: &Lane) -> bool {
+        self.inhibitors_down
+            .iter()
+            .any(|(owner, down_lane)| owner != team && down_lane == lane)
+    }
 }
 
 #[derive(States, Default, Debug, Hash, Eq, Clone, PartialEq)]
@@ -274,13 +293,19 @@ fn barracks_spawning_system(
                     barracks_config.wave_spawn_interval_secs,
                 ));
 
+            let inhibitors_down = if inhibitor_state.is_lane_open(team, lane) {
+                inhibitor_state.enemy_inhibitors_down(team)
+            } else {
+                0
+            };
+
             // Iterate through all minion types in the barrack configuration
             for (index, minion_config) in barracks_config.units.iter().enumerate() {
                 let spawn_count = calculate_spawn_count(
                     &minion_config.wave_behavior,
                     game_time.elapsed_secs(),
                     barrack_state.wave_count,
-                    &inhibitor_state,
+                    inhibitors_down,
                 );
 
                 if spawn_count > 0 {
@@ -346,6 +371,14 @@ fn barracks_spawning_system(
             armor.0 = armor.0.min(max);
         }
 
+        let mut magic_resist = MagicResist(character_record.base_spell_block.unwrap_or(0.0));
+        magic_resist.0 +=
+            upgrade_config.magic_resistance_upgrade.unwrap_or(0.0) * upgrade_count as f32;
+        // The upgrade config has no separate magic resist cap, both resists share the armor one
+        if let Some(max) = upgrade_config.armor_max {
+            magic_resist.0 = magic_resist.0.min(max);
+        }
+
         let mut movement = Movement {
             speed: character_record.base_move_speed.unwrap_or(0.0),
         };
@@ -370,7 +403,7 @@ fn barracks_spawning_system(
 
         commands
             .entity(entity)
-            .insert((health, damage, armor, movement));
+            .insert((health, damage, armor, magic_resist, movement));
 
         // Update queue
         current_spawn.1 -= 1;
@@ -385,7 +418,7 @@ fn calculate_spawn_count(
     behavior: &EnumWaveBehavior,
     game_time_secs: f32,
     wave_count: u32,
-    inhibitor_state: &InhibitorState,
+    inhibitors_down: usize,
 ) -> i32 {
     match behavior {
         EnumWaveBehavior::ConstantWaveBehavior(ConstantWaveBehavior { spawn_count }) => {
@@ -394,12 +427,12 @@ fn calculate_spawn_count(
         EnumWaveBehavior::InhibitorWaveBehavior(InhibitorWaveBehavior {
             spawn_count_per_inhibitor_down,
         }) => {
-            if inhibitor_state.inhibitors_down == 0 {
+            if inhibitors_down == 0 {
                 return 0;
             }
 
             spawn_count_per_inhibitor_down
-                .get(inhibitor_state.inhibitors_down - 1)
+                .get(inhibitors_down - 1)
                 .copied()
                 .unwrap_or(0)
         }
@@ -418,7 +451,7 @@ fn calculate_spawn_count(
 
             if let Some(active_behavior) = active_behavior {
                 // Recursive call
-                calculate_spawn_count(active_behavior, game_time_secs, wave_count, inhibitor_state)
+                calculate_spawn_count(active_behavior, game_time_secs, wave_count, inhibitors_down)
             } else {
                 0
             }
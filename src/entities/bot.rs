@@ -0,0 +1,513 @@
+use bevy::ecs::system::SystemParam;
+use bevy::prelude::*;
+use bevy_behave::prelude::{BehavePlugin, BehaveTree, BehaveTrigger, Tree};
+use bevy_behave::{behave, Behave};
+use lol_core::Team;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    resist_damage_multiplier, Action, Aggro, Armor, Attack, AttackAuto, AttackState, AttackStatus,
+    BonusDamage, Champion, CommandAction, CoolDown, Damage, Dead, Health, Minion, Skill, Skills,
+    Turret, TurretTarget,
+};
+
+/// Units the bot considers part of its lane fight
+const BOT_SIGHT_RANGE: f32 = 1500.0;
+
+/// Distance kept behind the frontmost allied minion
+const BOT_HOLD_DISTANCE: f32 = 300.0;
+
+/// Distance at which the bot considers itself in position
+const BOT_HOLD_TOLERANCE: f32 = 150.0;
+
+/// Speed assumed for ranged basic attack missiles when predicting last hits
+const BOT_MISSILE_SPEED: f32 = 2000.0;
+
+/// Distance the bot runs away from a threat when it has no turret to fall back to
+const BOT_RETREAT_DISTANCE: f32 = 800.0;
+
+#[derive(Default)]
+pub struct PluginBot;
+
+impl Plugin for PluginBot {
+    fn build(&self, app: &mut App) {
+        if !app.is_plugin_added::<BehavePlugin>() {
+            app.add_plugins(BehavePlugin::default());
+        }
+
+        app.add_observer(on_add_bot);
+        app.add_observer(on_bot_retreat);
+        app.add_observer(on_bot_last_hit);
+        app.add_observer(on_bot_trade);
+        app.add_observer(on_bot_hold_lane);
+    }
+}
+
+/// Champion played by a behavior tree, acting through `CommandAction` like a player would
+#[derive(Component, Reflect, Debug, Clone, Default, Serialize, Deserialize)]
+#[reflect(Component, Default)]
+pub struct Bot {
+    pub difficulty: BotDifficulty,
+}
+
+/// Tuning of a bot's decisions
+#[derive(Reflect, Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[reflect(Default)]
+pub struct BotDifficulty {
+    /// Seconds between two decisions
+    pub reaction_time: f32,
+    /// Multiplier on the bot's own damage when judging a last hit, below 1 attacks late
+    pub last_hit_accuracy: f32,
+    /// Health ratio below which the bot retreats
+    pub retreat_health: f32,
+    /// Own health ratio over the enemy's needed to start a trade
+    pub trade_advantage: f32,
+    /// Whether trades open with skills
+    pub use_skills: bool,
+}
+
+impl BotDifficulty {
+    pub const EASY: Self = Self {
+        reaction_time: 0.8,
+        last_hit_accuracy: 0.6,
+        retreat_health: 0.15,
+        trade_advantage: 1.5,
+        use_skills: false,
+    };
+
+    pub const NORMAL: Self = Self {
+        reaction_time: 0.4,
+        last_hit_accuracy: 0.85,
+        retreat_health: 0.25,
+        trade_advantage: 1.1,
+        use_skills: true,
+    };
+
+    pub const HARD: Self = Self {
+        reaction_time: 0.15,
+        last_hit_accuracy: 1.0,
+        retreat_health: 0.3,
+        trade_advantage: 0.9,
+        use_skills: true,
+    };
+}
+
+impl Default for BotDifficulty {
+    fn default() -> Self {
+        Self::NORMAL
+    }
+}
+
+/// Succeeds after ordering a retreat when the bot is low or under enemy turret fire
+#[derive(Clone)]
+pub struct BotRetreat;
+
+/// Succeeds after ordering an attack on a minion predicted to die to it
+#[derive(Clone)]
+pub struct BotLastHit;
+
+/// Succeeds after ordering a skill or attack on an enemy champion worth trading with
+#[derive(Clone)]
+pub struct BotTrade;
+
+/// Moves behind the allied minions, always succeeds
+#[derive(Clone)]
+pub struct BotHoldLane;
+
+/// Brain of a bot: retreat, last hit, trade or hold the lane, then wait for the reaction time
+pub fn bot_tree(difficulty: &BotDifficulty) -> Tree<Behave> {
+    behave! {
+        Behave::Forever => {
+            Behave::Sequence => {
+                Behave::Fallback => {
+                    Behave::trigger(BotRetreat),
+                    Behave::trigger(BotLastHit),
+                    Behave::trigger(BotTrade),
+                    Behave::trigger(BotHoldLane),
+                },
+                Behave::Wait(difficulty.reaction_time),
+            }
+        }
+    }
+}
+
+/// Health of a unit once the attacks landing within `horizon` seconds hit it
+///
+/// `incoming` holds the seconds until each attack lands and its damage
+pub fn predict_health(health: f32, incoming: &[(f32, f32)], horizon: f32) -> f32 {
+    health
+        - incoming
+            .iter()
+            .filter(|(time, _)| *time <= horizon)
+            .map(|(_, damage)| damage)
+            .sum::<f32>()
+}
+
+fn on_add_bot(trigger: On<Add, Bot>, mut commands: Commands, q_bot: Query<&Bot>) {
+    let entity = trigger.event_target();
+    let Ok(bot) = q_bot.get(entity) else {
+        return;
+    };
+
+    commands.spawn((
+        Name::new("BotBrain"),
+        BehaveTree::new(bot_tree(&bot.difficulty)),
+        ChildOf(entity),
+    ));
+}
+
+#[derive(SystemParam)]
+struct BotSenses<'w, 's> {
+    q_bot: Query<
+        'w,
+        's,
+        (
+            &'static Bot,
+            &'static Transform,
+            &'static Team,
+            &'static Health,
+            Option<&'static Attack>,
+            Option<&'static AttackState>,
+            Option<(&'static Damage, &'static BonusDamage)>,
+        ),
+        Without<Dead>,
+    >,
+    q_unit: Query<
+        'w,
+        's,
+        (
+            Entity,
+            &'static Transform,
+            &'static Team,
+            &'static Health,
+            Has<Minion>,
+            Has<Champion>,
+        ),
+        Without<Dead>,
+    >,
+    q_turret: Query<
+        'w,
+        's,
+        (
+            &'static Transform,
+            &'static Team,
+            &'static Aggro,
+            &'static TurretTarget,
+        ),
+        (With<Turret>, Without<Dead>),
+    >,
+    q_attacker: Query<
+        'w,
+        's,
+        (
+            &'static AttackState,
+            (&'static Damage, &'static BonusDamage),
+            Option<&'static Attack>,
+            Option<&'static AttackAuto>,
+        ),
+    >,
+    q_armor: Query<'w, 's, &'static Armor>,
+    q_skills: Query<'w, 's, &'static Skills>,
+    q_skill: Query<'w, 's, (&'static Skill, &'static CoolDown)>,
+    time: Res<'w, Time<Fixed>>,
+}
+
+impl BotSenses<'_, '_> {
+    /// Enemy turret covering `position`
+    fn enemy_turret_in_range(&self, team: &Team, position: Vec2) -> Option<Vec2> {
+        self.q_turret
+            .iter()
+            .filter(|(_, turret_team, ..)| *turret_team != team)
+            .map(|(transform, _, aggro, _)| (transform.translation.xz(), aggro.range))
+            .find(|(turret, range)| turret.distance(position) <= *range)
+            .map(|(turret, _)| turret)
+    }
+
+    /// Seconds until each attack aimed at `target` lands, and its damage after armor
+    fn incoming_attacks(&self, target: Entity) -> Vec<(f32, f32)> {
+        let now = self.time.elapsed_secs();
+        let multiplier = self
+            .q_armor
+            .get(target)
+            .map_or(1.0, |armor| resist_damage_multiplier(armor.0));
+
+        self.q_attacker
+            .iter()
+            .filter_map(|(state, (damage, bonus), attack, attack_auto)| {
+                let lands_in = match state.status {
+                    AttackStatus::Windup {
+                        target: attack_target,
+                        end_time,
+                    } if attack_target == target => end_time - now,
+                    AttackStatus::Cooldown { end_time }
+                        if attack_auto.is_some_and(|v| v.target == target) =>
+                    {
+                        end_time - now + attack.map_or(0.0, |v| v.windup_duration_secs())
+                    }
+                    _ => return None,
+                };
+                Some((lands_in.max(0.0), damage.total(bonus) * multiplier))
+            })
+            .collect()
+    }
+
+    /// Index of a learned skill that is off cooldown
+    fn ready_skill(&self, entity: Entity) -> Option<usize> {
+        let skills = self.q_skills.get(entity).ok()?;
+        skills.iter().enumerate().find_map(|(index, skill)| {
+            let (skill, cooldown) = self.q_skill.get(skill).ok()?;
+            (skill.level > 0 && cooldown.timer.is_finished()).then_some(index)
+        })
+    }
+}
+
+fn on_bot_retreat(
+    trigger: On<BehaveTrigger<BotRetreat>>,
+    mut commands: Commands,
+    senses: BotSenses,
+) {
+    let ctx = trigger.ctx();
+    let entity = ctx.target_entity();
+    let Ok((bot, transform, team, health, ..)) = senses.q_bot.get(entity) else {
+        commands.trigger(ctx.failure());
+        return;
+    };
+    let position = transform.translation.xz();
+
+    let low_health = health.value < health.max * bot.difficulty.retreat_health;
+    let turret_aggro = senses
+        .q_turret
+        .iter()
+        .find(|(_, turret_team, _, target)| *turret_team != team && target.target == Some(entity))
+        .map(|(transform, ..)| transform.translation.xz());
+
+    if !low_health && turret_aggro.is_none() {
+        commands.trigger(ctx.failure());
+        return;
+    }
+
+    let safe_turret = senses
+        .q_turret
+        .iter()
+        .filter(|(_, turret_team, ..)| *turret_team == team)
+        .map(|(transform, ..)| transform.translation.xz())
+        .min_by(|a, b| a.distance(position).total_cmp(&b.distance(position)));
+
+    let destination = match (safe_turret, turret_aggro) {
+        (Some(turret), _) => turret,
+        (None, Some(threat)) => {
+            position + (position - threat).normalize_or_zero() * BOT_RETREAT_DISTANCE
+        }
+        (None, None) => {
+            commands.trigger(ctx.failure());
+            return;
+        }
+    };
+
+    debug!("{} bot retreating to {}", entity, destination);
+    commands.trigger(CommandAction {
+        entity,
+        action: Action::Move(destination),
+    });
+    commands.trigger(ctx.success());
+}
+
+fn on_bot_last_hit(
+    trigger: On<BehaveTrigger<BotLastHit>>,
+    mut commands: Commands,
+    senses: BotSenses,
+) {
+    let ctx = trigger.ctx();
+    let entity = ctx.target_entity();
+    let Ok((bot, transform, team, _, Some(attack), attack_state, Some((damage, bonus)))) =
+        senses.q_bot.get(entity)
+    else {
+        commands.trigger(ctx.failure());
+        return;
+    };
+    let position = transform.translation.xz();
+
+    // Let an attack already on its way finish
+    if attack_state.is_some_and(|v| v.is_windup()) {
+        commands.trigger(ctx.failure());
+        return;
+    }
+
+    let target = senses
+        .q_unit
+        .iter()
+        .filter(|(_, _, unit_team, _, is_minion, _)| *is_minion && *unit_team != team)
+        .filter_map(|(target, target_transform, _, target_health, ..)| {
+            let distance = target_transform.translation.xz().distance(position);
+            if distance > attack.range + BOT_HOLD_DISTANCE {
+                return None;
+            }
+
+            let lands_in = attack.windup_duration_secs()
+                + if attack.spell_key.is_some() {
+                    distance / BOT_MISSILE_SPEED
+                } else {
+                    0.0
+                };
+            let predicted = predict_health(
+                target_health.value,
+                &senses.incoming_attacks(target),
+                lands_in,
+            );
+            let own_damage = damage.total(bonus)
+                * senses
+                    .q_armor
+                    .get(target)
+                    .map_or(1.0, |armor| resist_damage_multiplier(armor.0))
+                * bot.difficulty.last_hit_accuracy;
+
+            // Minions dying to someone else before our hit lands aren't worth the attack
+            (predicted > 0.0 && predicted <= own_damage).then_some((target, predicted))
+        })
+        .min_by(|a, b| a.1.total_cmp(&b.1));
+
+    let Some((target, _)) = target else {
+        commands.trigger(ctx.failure());
+        return;
+    };
+
+    debug!("{} bot last hitting {}", entity, target);
+    commands.trigger(CommandAction {
+        entity,
+        action: Action::Attack(target),
+    });
+    commands.trigger(ctx.success());
+}
+
+fn on_bot_trade(trigger: On<BehaveTrigger<BotTrade>>, mut commands: Commands, senses: BotSenses) {
+    let ctx = trigger.ctx();
+    let entity = ctx.target_entity();
+    let Ok((bot, transform, team, health, Some(attack), ..)) = senses.q_bot.get(entity) else {
+        commands.trigger(ctx.failure());
+        return;
+    };
+    let position = transform.translation.xz();
+
+    let enemy = senses
+        .q_unit
+        .iter()
+        .filter(|(_, _, unit_team, _, _, is_champion)| *is_champion && *unit_team != team)
+        .map(|(enemy, enemy_transform, _, enemy_health, ..)| {
+            (enemy, enemy_transform.translation.xz(), enemy_health)
+        })
+        .filter(|(_, enemy_position, _)| {
+            enemy_position.distance(position) <= attack.range + BOT_HOLD_DISTANCE
+        })
+        .min_by(|a, b| a.1.distance(position).total_cmp(&b.1.distance(position)));
+
+    let Some((enemy, enemy_position, enemy_health)) = enemy else {
+        commands.trigger(ctx.failure());
+        return;
+    };
+
+    // Never chase into the enemy turret
+    if senses.enemy_turret_in_range(team, enemy_position).is_some() {
+        commands.trigger(ctx.failure());
+        return;
+    }
+
+    let own_ratio = health.value / health.max;
+    let enemy_ratio = enemy_health.value / enemy_health.max;
+    if own_ratio < enemy_ratio * bot.difficulty.trade_advantage {
+        commands.trigger(ctx.failure());
+        return;
+    }
+
+    let skill = bot
+        .difficulty
+        .use_skills
+        .then(|| senses.ready_skill(entity))
+        .flatten();
+
+    let action = match skill {
+        Some(index) => Action::Skill {
+            index,
+            point: enemy_position,
+        },
+        None => Action::Attack(enemy),
+    };
+
+    debug!("{} bot trading with {}", entity, enemy);
+    commands.trigger(CommandAction { entity, action });
+    commands.trigger(ctx.success());
+}
+
+fn on_bot_hold_lane(
+    trigger: On<BehaveTrigger<BotHoldLane>>,
+    mut commands: Commands,
+    senses: BotSenses,
+) {
+    let ctx = trigger.ctx();
+    let entity = ctx.target_entity();
+    let Ok((_, transform, team, _, _, attack_state, _)) = senses.q_bot.get(entity) else {
+        commands.trigger(ctx.failure());
+        return;
+    };
+    let position = transform.translation.xz();
+
+    if attack_state.is_some_and(|v| v.is_windup()) {
+        commands.trigger(ctx.success());
+        return;
+    }
+
+    let nearby = senses
+        .q_unit
+        .iter()
+        .filter(|(_, _, _, _, is_minion, _)| *is_minion)
+        .map(|(_, unit_transform, unit_team, ..)| (unit_transform.translation.xz(), unit_team))
+        .filter(|(unit_position, _)| unit_position.distance(position) <= BOT_SIGHT_RANGE)
+        .collect::<Vec<_>>();
+
+    let allies = nearby
+        .iter()
+        .filter(|(_, unit_team)| *unit_team == team)
+        .map(|(v, _)| *v)
+        .collect::<Vec<_>>();
+    if allies.is_empty() {
+        commands.trigger(ctx.success());
+        return;
+    }
+
+    // The lane points from our minions towards the enemy minions or the enemy turret
+    let ally_center = allies.iter().sum::<Vec2>() / allies.len() as f32;
+    let enemies = nearby
+        .iter()
+        .filter(|(_, unit_team)| *unit_team != team)
+        .map(|(v, _)| *v)
+        .collect::<Vec<_>>();
+    let enemy_center = if enemies.is_empty() {
+        senses
+            .q_turret
+            .iter()
+            .filter(|(_, turret_team, ..)| *turret_team != team)
+            .map(|(transform, ..)| transform.translation.xz())
+            .min_by(|a, b| a.distance(ally_center).total_cmp(&b.distance(ally_center)))
+    } else {
+        Some(enemies.iter().sum::<Vec2>() / enemies.len() as f32)
+    };
+    let Some(enemy_center) = enemy_center else {
+        commands.trigger(ctx.success());
+        return;
+    };
+
+    let forward = (enemy_center - ally_center).normalize_or_zero();
+    let front = allies
+        .iter()
+        .copied()
+        .max_by(|a, b| a.dot(forward).total_cmp(&b.dot(forward)))
+        .unwrap_or(ally_center);
+    let destination = front - forward * BOT_HOLD_DISTANCE;
+
+    if destination.distance(position) > BOT_HOLD_TOLERANCE {
+        commands.trigger(CommandAction {
+            entity,
+            action: Action::Move(destination),
+        });
+    }
+    commands.trigger(ctx.success());
+}
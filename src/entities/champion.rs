@@ -1,10 +1,13 @@
 use bevy::prelude::*;
 
-use crate::{Level, SkillPoints, State};
+use crate::{
+    AbilityHaste, Avoidance, ControlWardCharges, DamageHistory, Gold, Level, Respawnable,
+    SkillPoints, State, Trinket,
+};
 
 #[derive(Component, Reflect, Default)]
 #[reflect(Component)]
-#[require(State, Level = Level { value: 1, experience: 0, experience_to_next_level: 280 }, SkillPoints)]
+#[require(State, Avoidance = Avoidance::CHAMPION, Level = Level { value: 1, experience: 0, experience_to_next_level: 280 }, SkillPoints, DamageHistory, Trinket, ControlWardCharges, Gold, Respawnable, AbilityHaste)]
 pub struct Champion;
 
 #[derive(Default)]
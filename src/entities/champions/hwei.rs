@@ -1,119 +1,15 @@
 use bevy::prelude::*;
-use bevy_behave::{behave, Behave};
-use league_core::CharacterRecord;
-use league_utils::{get_asset_id_by_path, hash_bin};
-use lol_config::LoadHashKeyTrait;
 
-use crate::core::{ActionAnimationPlay, ActionParticleSpawn, CoolDown, Skill, SkillOf, Skills};
 use crate::entities::champion::Champion;
-use crate::{PassiveSkillOf, SkillEffect};
 
 #[derive(Default)]
 pub struct PluginHwei;
 
 impl Plugin for PluginHwei {
-    fn build(&self, app: &mut App) {
-        app.add_systems(Startup, startup_load_assets);
-        app.add_systems(FixedUpdate, add_skills);
-    }
+    fn build(&self, _app: &mut App) {}
 }
 
 #[derive(Component, Reflect)]
 #[require(Champion, Name = Name::new("Hwei"))]
 #[reflect(Component)]
 pub struct Hwei;
-
-fn startup_load_assets(mut res_assets_skill_effect: ResMut<Assets<SkillEffect>>) {
-    res_assets_skill_effect
-        .insert(
-            get_asset_id_by_path("Characters/Hwei/Spells/HweiQAbility/HweiQ"),
-            SkillEffect(behave! {
-                Behave::Sequence => {
-                    Behave::trigger(
-                        ActionAnimationPlay { hash: hash_bin("Spell1") }
-                    ),
-                    Behave::trigger(
-                        ActionParticleSpawn { hash: hash_bin("Hwei_Q_Q_Tar") },
-                    ),
-                }
-            }),
-        )
-        .unwrap();
-    res_assets_skill_effect
-        .insert(
-            get_asset_id_by_path("Characters/Hwei/Spells/HweiWAbility/HweiW"),
-            SkillEffect(behave! {
-                Behave::Sequence => {
-                    Behave::trigger(
-                        ActionAnimationPlay { hash: hash_bin("Spell1") }
-                    ),
-                    Behave::trigger(
-                        ActionParticleSpawn { hash: hash_bin("Hwei_Q_W_AoE") },
-                    ),
-                }
-            }),
-        )
-        .unwrap();
-    res_assets_skill_effect
-        .insert(
-            get_asset_id_by_path("Characters/Hwei/Spells/HweiEAbility/HweiE"),
-            SkillEffect(behave! {
-                Behave::Sequence => {
-                    Behave::trigger(
-                        ActionAnimationPlay { hash: hash_bin("Spell1") }
-                    ),
-                    Behave::trigger(
-                        ActionParticleSpawn { hash: hash_bin("Hwei_Q_Q_Tar") },
-                    ),
-                }
-            }),
-        )
-        .unwrap();
-    res_assets_skill_effect
-        .insert(
-            get_asset_id_by_path("Characters/Hwei/Spells/HweiRAbility/HweiR"),
-            SkillEffect(behave! {
-                Behave::Sequence => {
-                    Behave::trigger(
-                        ActionAnimationPlay { hash: hash_bin("Spell1") }
-                    ),
-                    Behave::trigger(
-                        ActionParticleSpawn { hash: hash_bin("Hwei_Q_Q_Tar") },
-                    ),
-                }
-            }),
-        )
-        .unwrap();
-}
-
-fn add_skills(
-    mut commands: Commands,
-    q_hwei: Query<Entity, (With<Hwei>, Without<Skills>)>,
-    res_assets_character_record: Res<Assets<CharacterRecord>>,
-) {
-    for entity in q_hwei.iter() {
-        commands.entity(entity).with_related::<PassiveSkillOf>((
-            Skill {
-                key_spell_object: "Characters/Hwei/Spells/HweiPassiveAbility/HweiPassive".into(),
-                key_skill_effect: "Characters/Hwei/Spells/HweiPassiveAbility/HweiPassive".into(),
-                ..default()
-            },
-            CoolDown::default(),
-        ));
-
-        let character_record = res_assets_character_record
-            .load_hash("Characters/Hwei/CharacterRecords/Root")
-            .unwrap();
-
-        for &skill in character_record.spells.as_ref().unwrap().iter() {
-            commands.entity(entity).with_related::<SkillOf>((
-                Skill {
-                    key_spell_object: skill.into(),
-                    key_skill_effect: skill.into(),
-                    ..default()
-                },
-                CoolDown::default(),
-            ));
-        }
-    }
-}
@@ -1,4 +1,68 @@
 use bevy::prelude::*;
+use lol_core::{Lane, Team};
+
+use crate::{Dead, EventDead, EventRespawn, InhibitorState, Respawnable};
+
+/// Time in seconds for a destroyed inhibitor to respawn
+pub const INHIBITOR_RESPAWN_TIME: f32 = 300.0;
+
+#[derive(Default)]
+pub struct PluginInhibitor;
+
+impl Plugin for PluginInhibitor {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<InhibitorState>();
+
+        app.add_observer(on_event_dead);
+        app.add_observer(on_event_respawn);
+    }
+}
 
 #[derive(Component)]
+#[require(Respawnable)]
 pub struct Inhibitor;
+
+fn on_event_dead(
+    trigger: On<EventDead>,
+    mut commands: Commands,
+    q_inhibitor: Query<(&Team, &Lane), With<Inhibitor>>,
+    mut res_inhibitor_state: ResMut<InhibitorState>,
+) {
+    let entity = trigger.event_target();
+
+    let Ok((team, lane)) = q_inhibitor.get(entity) else {
+        return;
+    };
+
+    debug!("{:?} {:?} inhibitor destroyed", team, lane);
+
+    res_inhibitor_state
+        .inhibitors_down
+        .push((team.clone(), lane.clone()));
+
+    commands.entity(entity).insert(Dead {
+        respawn_timer: Timer::from_seconds(INHIBITOR_RESPAWN_TIME, TimerMode::Once),
+    });
+}
+
+fn on_event_respawn(
+    trigger: On<EventRespawn>,
+    q_inhibitor: Query<(&Team, &Lane), With<Inhibitor>>,
+    mut res_inhibitor_state: ResMut<InhibitorState>,
+) {
+    let entity = trigger.event_target();
+
+    let Ok((team, lane)) = q_inhibitor.get(entity) else {
+        return;
+    };
+
+    debug!("{:?} {:?} inhibitor respawned", team, lane);
+
+    if let Some(index) = res_inhibitor_state
+        .inhibitors_down
+        .iter()
+        .position(|(down_team, down_lane)| down_team == team && down_lane == lane)
+    {
+        res_inhibitor_state.inhibitors_down.remove(index);
+    }
+}
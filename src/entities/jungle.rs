@@ -0,0 +1,445 @@
+use std::collections::HashMap;
+use std::fs::File;
+use std::io::Read;
+
+use bevy::prelude::*;
+use bevy::scene::ron;
+use league_core::CharacterRecord;
+use lol_config::LoadHashKeyTrait;
+use lol_core::Team;
+use serde::Deserialize;
+
+use crate::{
+    BuffDamage, BuffEpicMonster, Champion, Character, CommandAttackAutoStart,
+    CommandAttackAutoStop, CommandBuffApply, CommandCharacterSpawn, CommandMovement, Dead,
+    EventDamageCreate, EventDead, EventLevelUp, Gold, Health, Invulnerable, Level, MovementAction,
+    MovementWay,
+};
+
+/// Monsters reset once they are pulled this far from their spawn point
+pub const MONSTER_LEASH_RADIUS: f32 = 800.0;
+
+/// Share of max health regenerated per second while resetting
+pub const MONSTER_RESET_REGEN: f32 = 0.5;
+
+/// Distance from the spawn point at which a resetting monster is back home
+const MONSTER_HOME_RADIUS: f32 = 50.0;
+
+/// Distance between the monsters of a camp
+const MONSTER_SPACING: f32 = 150.0;
+
+/// Spawn timers of every camp kind, relative to `assets`
+pub const JUNGLE_CAMP_CONFIG_PATH: &str = "jungle/camps.ron";
+
+#[derive(Default)]
+pub struct PluginJungle;
+
+impl Plugin for PluginJungle {
+    fn build(&self, app: &mut App) {
+        let mut file = File::open(format!("assets/{JUNGLE_CAMP_CONFIG_PATH}")).unwrap();
+        let mut data = Vec::new();
+        file.read_to_end(&mut data).unwrap();
+        app.insert_resource(ResourceJungleCamps(ron::de::from_bytes(&data).unwrap()));
+
+        app.add_observer(on_event_damage_create);
+        app.add_observer(on_event_dead);
+
+        app.add_systems(FixedUpdate, (fixed_update_camp, fixed_update_monster));
+        app.add_systems(Update, update_monster_reward);
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Deserialize)]
+pub enum CampKind {
+    Blue,
+    Red,
+    Gromp,
+    Wolves,
+    Raptors,
+    Krugs,
+    Scuttle,
+    Dragon,
+    Herald,
+    Baron,
+}
+
+impl CampKind {
+    /// Camp kind from the minimap icon name of the camp placeable
+    pub fn from_name(name: &str) -> Option<Self> {
+        let name = name.to_lowercase();
+
+        [
+            ("blue", CampKind::Blue),
+            ("red", CampKind::Red),
+            ("gromp", CampKind::Gromp),
+            ("wolf", CampKind::Wolves),
+            ("razorbeak", CampKind::Raptors),
+            ("raptor", CampKind::Raptors),
+            ("krug", CampKind::Krugs),
+            ("crab", CampKind::Scuttle),
+            ("scuttle", CampKind::Scuttle),
+            ("dragon", CampKind::Dragon),
+            ("herald", CampKind::Herald),
+            ("baron", CampKind::Baron),
+        ]
+        .into_iter()
+        .find(|(key, _)| name.contains(key))
+        .map(|(_, kind)| kind)
+    }
+
+    /// Character names of the monsters in the camp, the first one is the big monster
+    pub fn monsters(&self) -> &'static [&'static str] {
+        match self {
+            CampKind::Blue => &["SRU_Blue"],
+            CampKind::Red => &["SRU_Red"],
+            CampKind::Gromp => &["SRU_Gromp"],
+            CampKind::Wolves => &["SRU_Murkwolf", "SRU_MurkwolfMini", "SRU_MurkwolfMini"],
+            CampKind::Raptors => &[
+                "SRU_Razorbeak",
+                "SRU_RazorbeakMini",
+                "SRU_RazorbeakMini",
+                "SRU_RazorbeakMini",
+                "SRU_RazorbeakMini",
+                "SRU_RazorbeakMini",
+            ],
+            CampKind::Krugs => &["SRU_Krug", "SRU_KrugMini"],
+            CampKind::Scuttle => &["Sru_Crab"],
+            CampKind::Dragon => &["SRU_Dragon_Fire"],
+            CampKind::Herald => &["SRU_RiftHerald"],
+            CampKind::Baron => &["SRU_Baron"],
+        }
+    }
+
+    /// Team buff granted to the team that slays the monster and its duration, `None` for
+    /// permanent buffs such as dragon stacks
+    pub fn epic_buff(&self) -> Option<((BuffEpicMonster, BuffDamage), Option<f32>)> {
+        let (bonus_damage, duration) = match self {
+            CampKind::Dragon => (6.0, None),
+            CampKind::Herald => (20.0, Some(240.0)),
+            CampKind::Baron => (40.0, Some(180.0)),
+            _ => return None,
+        };
+
+        Some(((BuffEpicMonster, BuffDamage { bonus_damage }), duration))
+    }
+}
+
+/// Spawn timers of a camp kind
+#[derive(Debug, Clone, Deserialize)]
+pub struct CampConfig {
+    /// Game time in seconds of the first spawn
+    pub initial_spawn_time_secs: f32,
+    /// Time in seconds between the camp being cleared and its next spawn
+    pub respawn_time_secs: f32,
+}
+
+/// Camp configs keyed by kind, loaded from [`JUNGLE_CAMP_CONFIG_PATH`]
+#[derive(Resource, Debug, Default)]
+pub struct ResourceJungleCamps(pub HashMap<CampKind, CampConfig>);
+
+/// Jungle camp spawn point, spawns its monsters when the timer finishes
+#[derive(Component, Debug)]
+pub struct JungleCamp {
+    pub kind: CampKind,
+    pub spawn_timer: Timer,
+    /// Time in seconds between the camp being cleared and its next spawn
+    pub respawn_time: f32,
+    /// Game time in seconds after which the camp no longer respawns
+    pub stop_spawn_time: Option<f32>,
+}
+
+impl JungleCamp {
+    pub fn new(kind: CampKind, config: &CampConfig, stop_spawn_time: Option<f32>) -> Self {
+        Self {
+            kind,
+            spawn_timer: Timer::from_seconds(config.initial_spawn_time_secs, TimerMode::Once),
+            respawn_time: config.respawn_time_secs,
+            stop_spawn_time,
+        }
+    }
+}
+
+#[derive(Component, Debug)]
+#[relationship(relationship_target = CampMonsters)]
+pub struct MonsterOf(pub Entity);
+
+#[derive(Component, Debug)]
+#[relationship_target(relationship = MonsterOf)]
+pub struct CampMonsters(Vec<Entity>);
+
+impl std::ops::Deref for CampMonsters {
+    type Target = Vec<Entity>;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+#[derive(Component, Debug)]
+#[require(MonsterState)]
+pub struct JungleMonster {
+    /// Spawn point the monster leashes back to
+    pub home: Vec3,
+    pub last_attacker: Option<Entity>,
+}
+
+#[derive(Component, Debug, Default, Clone, Copy, PartialEq)]
+pub enum MonsterState {
+    #[default]
+    Idle,
+    Attacking(Entity),
+    /// Walking back home, invulnerable and regenerating
+    Resetting,
+}
+
+/// Gold and experience given to the killer, read from the CharacterRecord
+#[derive(Component, Debug, Default, Clone)]
+pub struct MonsterReward {
+    pub gold: f32,
+    pub experience: f32,
+}
+
+fn fixed_update_camp(
+    mut commands: Commands,
+    mut q_camp: Query<(Entity, &Transform, &mut JungleCamp, Option<&CampMonsters>)>,
+    time: Res<Time<Fixed>>,
+    game_time: Res<Time<Virtual>>,
+) {
+    for (entity, transform, mut camp, monsters) in q_camp.iter_mut() {
+        if monsters.is_some_and(|v| !v.is_empty()) {
+            continue;
+        }
+
+        if camp
+            .stop_spawn_time
+            .is_some_and(|v| game_time.elapsed_secs() >= v)
+        {
+            continue;
+        }
+
+        camp.spawn_timer.tick(time.delta());
+
+        if !camp.spawn_timer.is_finished() {
+            continue;
+        }
+
+        let kind = camp.kind;
+        camp.spawn_timer = Timer::from_seconds(camp.respawn_time, TimerMode::Once);
+
+        debug!("{:?} spawning {:?} camp", entity, kind);
+
+        let names = kind.monsters();
+        for (index, name) in names.iter().enumerate() {
+            // Small monsters stand in a circle around the big one
+            let offset = if index == 0 {
+                Vec3::ZERO
+            } else {
+                let angle = std::f32::consts::TAU * index as f32 / (names.len() - 1) as f32;
+                Vec3::new(angle.cos(), 0.0, angle.sin()) * MONSTER_SPACING
+            };
+            let home = transform.translation + offset;
+
+            let monster = commands
+                .spawn((
+                    Transform::from_translation(home).with_rotation(transform.rotation),
+                    Team::Neutral,
+                    JungleMonster {
+                        home,
+                        last_attacker: None,
+                    },
+                    MonsterOf(entity),
+                ))
+                .id();
+
+            commands.trigger(CommandCharacterSpawn {
+                entity: monster,
+                character_record: format!("Characters/{name}/CharacterRecords/Root"),
+                skin: format!("Characters/{name}/Skins/Skin0"),
+            });
+        }
+    }
+}
+
+fn update_monster_reward(
+    mut commands: Commands,
+    q_monster: Query<(Entity, &Character), (With<JungleMonster>, Without<MonsterReward>)>,
+    res_assets_character_record: Res<Assets<CharacterRecord>>,
+) {
+    for (entity, character) in q_monster.iter() {
+        let Some(record) = res_assets_character_record.load_hash(character.key) else {
+            continue;
+        };
+
+        commands.entity(entity).insert(MonsterReward {
+            gold: record.gold_given_on_death.unwrap_or(0.0),
+            experience: record.exp_given_on_death.unwrap_or(0.0),
+        });
+    }
+}
+
+/// Damaging a monster pulls the whole camp onto the attacker
+fn on_event_damage_create(
+    trigger: On<EventDamageCreate>,
+    mut commands: Commands,
+    q_monster_of: Query<&MonsterOf>,
+    q_camp: Query<&CampMonsters>,
+    mut q_monster: Query<(&mut JungleMonster, &mut MonsterState)>,
+) {
+    let entity = trigger.event_target();
+    let source = trigger.source;
+
+    let Ok((mut monster, state)) = q_monster.get_mut(entity) else {
+        return;
+    };
+
+    monster.last_attacker = Some(source);
+
+    if *state != MonsterState::Idle {
+        return;
+    }
+
+    let monsters = q_monster_of
+        .get(entity)
+        .ok()
+        .and_then(|v| q_camp.get(v.0).ok())
+        .map(|v| v.to_vec())
+        .unwrap_or_else(|| vec![entity]);
+
+    for monster in monsters {
+        let Ok((_, mut state)) = q_monster.get_mut(monster) else {
+            continue;
+        };
+
+        if *state != MonsterState::Idle {
+            continue;
+        }
+
+        debug!("{:?} aggroed on {:?}", monster, source);
+
+        *state = MonsterState::Attacking(source);
+        commands.trigger(CommandAttackAutoStart {
+            entity: monster,
+            target: source,
+        });
+    }
+}
+
+fn fixed_update_monster(
+    mut commands: Commands,
+    mut q_monster: Query<(
+        Entity,
+        &Transform,
+        &JungleMonster,
+        &mut MonsterState,
+        &mut Health,
+    )>,
+    q_target: Query<(), Without<Dead>>,
+    time: Res<Time<Fixed>>,
+) {
+    for (entity, transform, monster, mut state, mut health) in q_monster.iter_mut() {
+        let distance = transform.translation.xz().distance(monster.home.xz());
+
+        match *state {
+            MonsterState::Idle => {}
+            MonsterState::Attacking(target) => {
+                if q_target.contains(target) && distance <= MONSTER_LEASH_RADIUS {
+                    continue;
+                }
+
+                debug!("{:?} leashed, resetting", entity);
+
+                *state = MonsterState::Resetting;
+                commands.trigger(CommandAttackAutoStop { entity });
+                commands.trigger(CommandMovement {
+                    entity,
+                    priority: 0,
+                    action: MovementAction::Start {
+                        way: MovementWay::Pathfind(monster.home),
+                        speed: None,
+                        source: "Monster".to_string(),
+                    },
+                });
+                commands.entity(entity).insert(Invulnerable);
+            }
+            MonsterState::Resetting => {
+                health.value = (health.value
+                    + health.max * MONSTER_RESET_REGEN * time.delta_secs())
+                .min(health.max);
+
+                if distance > MONSTER_HOME_RADIUS {
+                    continue;
+                }
+
+                *state = MonsterState::Idle;
+                health.value = health.max;
+                commands.entity(entity).remove::<Invulnerable>();
+                commands.trigger(CommandMovement {
+                    entity,
+                    priority: 0,
+                    action: MovementAction::Stop,
+                });
+            }
+        }
+    }
+}
+
+fn on_event_dead(
+    trigger: On<EventDead>,
+    mut commands: Commands,
+    q_monster: Query<(&JungleMonster, Option<&MonsterReward>, Option<&MonsterOf>)>,
+    q_camp: Query<&JungleCamp>,
+    mut q_killer: Query<(&Team, Option<&mut Gold>, Option<&mut Level>)>,
+    q_champion: Query<(Entity, &Team), (With<Champion>, Without<Dead>)>,
+) {
+    let entity = trigger.event_target();
+
+    let Ok((monster, reward, monster_of)) = q_monster.get(entity) else {
+        return;
+    };
+
+    let Some(killer) = monster.last_attacker else {
+        return;
+    };
+
+    let Ok((team, gold, level)) = q_killer.get_mut(killer) else {
+        return;
+    };
+    let team = team.clone();
+
+    if let Some(reward) = reward {
+        if let Some(mut gold) = gold {
+            gold.0 += reward.gold;
+        }
+
+        if let Some(mut level) = level {
+            let levels_gained = level.add_experience(reward.experience as u32);
+            if levels_gained > 0 {
+                commands.trigger(EventLevelUp {
+                    entity: killer,
+                    level: level.value,
+                    delta: levels_gained,
+                });
+            }
+        }
+    }
+
+    let Some(camp) = monster_of.and_then(|v| q_camp.get(v.0).ok()) else {
+        return;
+    };
+
+    let Some((buff, duration)) = camp.kind.epic_buff() else {
+        return;
+    };
+
+    debug!("{:?} slain by {:?}", camp.kind, team);
+
+    for (champion, champion_team) in q_champion.iter() {
+        if *champion_team != team {
+            continue;
+        }
+        commands.trigger(CommandBuffApply {
+            duration,
+            ..CommandBuffApply::new(champion, "EpicMonster").with_bundle(buff.clone())
+        });
+    }
+}
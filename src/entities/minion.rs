@@ -4,7 +4,7 @@ use lol_core::{Lane, Team};
 use serde::{Deserialize, Serialize};
 
 use crate::{
-    Aggro, AttackAuto, CommandAttackAutoStart, CommandAttackAutoStop, CommandMovement,
+    Aggro, AttackAuto, Avoidance, CommandAttackAutoStart, CommandAttackAutoStop, CommandMovement,
     EventAggroTargetFound, EventDead, MinionPath, MovementAction, MovementWay, State,
 };
 
@@ -21,7 +21,7 @@ impl Plugin for PluginMinion {
 }
 
 #[derive(Component, Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
-#[require(MinionState, Aggro = Aggro { range: 1000.0 }, State)]
+#[require(MinionState, Aggro = Aggro::new(1000.0), Avoidance = Avoidance::MINION, State)]
 pub enum Minion {
     Siege,
     Melee,
@@ -1,4 +1,35 @@
 use bevy::prelude::*;
+use lol_core::Team;
+
+use crate::{EventDead, EventGameEnd};
+
+#[derive(Default)]
+pub struct PluginNexus;
+
+impl Plugin for PluginNexus {
+    fn build(&self, app: &mut App) {
+        app.add_observer(on_event_dead);
+    }
+}
 
 #[derive(Component)]
 pub struct Nexus;
+
+fn on_event_dead(
+    trigger: On<EventDead>,
+    mut commands: Commands,
+    q_nexus: Query<&Team, With<Nexus>>,
+) {
+    let Ok(team) = q_nexus.get(trigger.event_target()) else {
+        return;
+    };
+
+    let winner = match team {
+        Team::Order => Team::Chaos,
+        _ => Team::Order,
+    };
+
+    debug!("{:?} nexus destroyed", team);
+
+    commands.trigger(EventGameEnd { winner });
+}
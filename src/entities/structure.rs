@@ -0,0 +1,184 @@
+use bevy::prelude::*;
+use league_core::CharacterRecord;
+use lol_config::LoadHashKeyTrait;
+use lol_core::{Lane, Team};
+
+use crate::{Character, Dead, Inhibitor, Invulnerable, Nexus, Turret, TurretPlating};
+
+#[derive(Default)]
+pub struct PluginStructure;
+
+impl Plugin for PluginStructure {
+    fn build(&self, app: &mut App) {
+        app.add_observer(on_add_character);
+        app.add_systems(FixedUpdate, fixed_update_protection);
+    }
+}
+
+/// Structure kind read from the `unit_tags_string` of a character record
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StructureKind {
+    Turret,
+    Inhibitor,
+    Nexus,
+}
+
+impl StructureKind {
+    pub fn from_record(record: &CharacterRecord) -> Option<Self> {
+        record
+            .unit_tags_string
+            .as_deref()?
+            .split('|')
+            .find_map(|tag| match tag.trim() {
+                "Structure_Turret" => Some(Self::Turret),
+                "Structure_Inhibitor" => Some(Self::Inhibitor),
+                "Structure_Nexus" => Some(Self::Nexus),
+                _ => None,
+            })
+    }
+}
+
+fn on_add_character(
+    event: On<Add, Character>,
+    mut commands: Commands,
+    q_character: Query<&Character>,
+    res_assets_character_record: Res<Assets<CharacterRecord>>,
+) {
+    let entity = event.event_target();
+    let Ok(character) = q_character.get(entity) else {
+        return;
+    };
+    let Some(record) = res_assets_character_record.load_hash(character.key) else {
+        return;
+    };
+
+    match StructureKind::from_record(record) {
+        Some(StructureKind::Inhibitor) => {
+            commands.entity(entity).insert(Inhibitor);
+        }
+        Some(StructureKind::Nexus) => {
+            commands.entity(entity).insert(Nexus);
+        }
+        _ => {}
+    }
+}
+
+struct StructureInfo {
+    entity: Entity,
+    team: Team,
+    lane: Option<Lane>,
+    /// Distance to the team's nexus
+    distance: f32,
+}
+
+/// Structures are invulnerable until the ones in front of them fall: lane turrets from the
+/// outside in, then the inhibitor, then the nexus turrets once an inhibitor is down, then the nexus
+fn fixed_update_protection(
+    mut commands: Commands,
+    q_nexus: Query<(Entity, &Team, &Transform), With<Nexus>>,
+    q_inhibitor: Query<(Entity, &Team, &Lane, &Transform, Has<Dead>), With<Inhibitor>>,
+    q_turret: Query<(Entity, &Team, Option<&Lane>, &Transform, Has<TurretPlating>), With<Turret>>,
+    q_invulnerable: Query<(), With<Invulnerable>>,
+) {
+    let set_protected = |commands: &mut Commands, entity: Entity, protected: bool| {
+        if protected == q_invulnerable.contains(entity) {
+            return;
+        }
+        if protected {
+            commands.entity(entity).insert(Invulnerable);
+        } else {
+            commands.entity(entity).remove::<Invulnerable>();
+        }
+    };
+
+    for (nexus, team, nexus_transform) in q_nexus.iter() {
+        let nexus_position = nexus_transform.translation.xz();
+        let distance = |transform: &Transform| transform.translation.xz().distance(nexus_position);
+
+        let inhibitors: Vec<(StructureInfo, bool)> = q_inhibitor
+            .iter()
+            .filter(|(_, inhibitor_team, ..)| *inhibitor_team == team)
+            .map(|(entity, team, lane, transform, dead)| {
+                let info = StructureInfo {
+                    entity,
+                    team: team.clone(),
+                    lane: Some(lane.clone()),
+                    distance: distance(transform),
+                };
+                (info, dead)
+            })
+            .collect();
+
+        let any_inhibitor_down = inhibitors.iter().any(|(_, dead)| *dead);
+
+        let turrets: Vec<(StructureInfo, bool)> = q_turret
+            .iter()
+            .filter(|(_, turret_team, ..)| *turret_team == team)
+            .map(|(entity, team, lane, transform, plating)| {
+                let info = StructureInfo {
+                    entity,
+                    team: team.clone(),
+                    lane: lane.cloned(),
+                    distance: distance(transform),
+                };
+                (info, plating)
+            })
+            .collect();
+
+        // Nexus turrets stand behind the inhibitors
+        let is_nexus_turret = |turret: &StructureInfo| {
+            let lane_inhibitor = inhibitors
+                .iter()
+                .find(|(inhibitor, _)| inhibitor.lane.is_some() && inhibitor.lane == turret.lane);
+
+            match lane_inhibitor {
+                Some((inhibitor, _)) => turret.distance < inhibitor.distance,
+                None => inhibitors
+                    .iter()
+                    .all(|(inhibitor, _)| turret.distance < inhibitor.distance),
+            }
+        };
+
+        let has_turret_in_front = |structure: &StructureInfo| {
+            turrets.iter().any(|(turret, _)| {
+                turret.entity != structure.entity
+                    && turret.team == structure.team
+                    && turret.lane.is_some()
+                    && turret.lane == structure.lane
+                    && turret.distance > structure.distance
+            })
+        };
+
+        let mut nexus_turret_alive = false;
+
+        for (turret, plating) in turrets.iter() {
+            let protected = if !inhibitors.is_empty() && is_nexus_turret(turret) {
+                nexus_turret_alive = true;
+                !any_inhibitor_down
+            } else {
+                has_turret_in_front(turret)
+            };
+
+            // Only the outer turrets, which are never protected, keep their plating
+            if protected && *plating {
+                commands.entity(turret.entity).remove::<TurretPlating>();
+            }
+
+            set_protected(&mut commands, turret.entity, protected);
+        }
+
+        for (inhibitor, dead) in inhibitors.iter() {
+            if *dead {
+                continue;
+            }
+            set_protected(
+                &mut commands,
+                inhibitor.entity,
+                has_turret_in_front(inhibitor),
+            );
+        }
+
+        let nexus_protected = nexus_turret_alive || (!inhibitors.is_empty() && !any_inhibitor_down);
+        set_protected(&mut commands, nexus, nexus_protected);
+    }
+}
@@ -1,6 +1,21 @@
+use bevy::ecs::world::DeferredWorld;
 use bevy::prelude::*;
+use lol_core::Team;
 
-use crate::{Aggro, CommandAttackAutoStart, EventAggroTargetFound};
+use crate::{
+    is_visible_to, Aggro, Champion, CommandAttackAutoStart, DamageContext, DamageOrigin,
+    DamagePipeline, DamageType, Dead, EventAggroTargetFound, EventAttackStart, EventDamageCreate,
+    Gold, Health, Minion, PipelineAppExt, TeamVisibility,
+};
+
+/// Game time in seconds at which turret plating falls off
+pub const TURRET_PLATING_END_TIME: f32 = 14.0 * 60.0;
+
+/// Bonus damage gained per consecutive shot on the same champion
+pub const TURRET_WARM_UP_PER_SHOT: f32 = 0.4;
+
+/// Maximum bonus damage of the warm-up ramp
+pub const TURRET_WARM_UP_MAX: f32 = 1.2;
 
 #[derive(Default)]
 pub struct PluginTurret;
@@ -8,26 +23,242 @@ pub struct PluginTurret;
 impl Plugin for PluginTurret {
     fn build(&self, app: &mut App) {
         app.add_observer(on_event_aggro_target_found);
+        app.add_observer(on_event_damage_create);
+        app.add_observer(on_event_damage_call_for_help);
+        app.add_observer(on_event_attack_start);
+
+        app.add_systems(FixedUpdate, fixed_update_plating);
+        app.add_pipeline_modifier(DamagePipeline::Amplify, damage_turret_shot);
     }
 }
 
 #[derive(Component)]
-#[require(Aggro = Aggro { range: 1000.0 })]
+#[require(Aggro = Aggro::new(1000.0), TurretTarget)]
 pub struct Turret;
 
+/// Target the turret is locked onto, kept until it dies or leaves range
+#[derive(Component, Debug, Default)]
+pub struct TurretTarget {
+    pub target: Option<Entity>,
+    /// Consecutive shots landed on the current champion target
+    pub heat: u32,
+}
+
+/// Damage multiplier of a shot on a champion hit by `heat` consecutive shots
+pub fn turret_warm_up_multiplier(heat: u32) -> f32 {
+    1.0 + (heat as f32 * TURRET_WARM_UP_PER_SHOT).min(TURRET_WARM_UP_MAX)
+}
+
+/// Share of the minion's max health a turret shot deals
+pub fn turret_minion_damage_percent(minion: &Minion) -> f32 {
+    match minion {
+        Minion::Melee => 0.45,
+        Minion::Ranged => 0.7,
+        Minion::Siege => 0.14,
+        Minion::Super => 0.05,
+    }
+}
+
+/// Outer turret plating, each plate destroyed before 14:00 grants gold to the attacker
+#[derive(Component, Debug, Clone)]
+pub struct TurretPlating {
+    pub plates: u32,
+    pub max_plates: u32,
+    pub gold_per_plate: f32,
+}
+
+impl Default for TurretPlating {
+    fn default() -> Self {
+        Self {
+            plates: 5,
+            max_plates: 5,
+            gold_per_plate: 160.0,
+        }
+    }
+}
+
 fn on_event_aggro_target_found(
     trigger: On<EventAggroTargetFound>,
     mut commands: Commands,
-    q_turret: Query<Entity, With<Turret>>,
+    mut q_turret: Query<(&Team, &Transform, &Aggro, &mut TurretTarget)>,
+    q_target: Query<(&Transform, Option<&TeamVisibility>), Without<Dead>>,
 ) {
     let entity = trigger.event_target();
 
-    if q_turret.get(entity).is_ok() {
-        debug!("{} initiating attack on aggro target {}", entity, trigger.target);
+    let Ok((team, transform, aggro, mut turret_target)) = q_turret.get_mut(entity) else {
+        return;
+    };
+
+    // Stay on the current target while it is alive, visible and in range
+    let locked = turret_target.target.is_some_and(|target| {
+        q_target
+            .get(target)
+            .is_ok_and(|(target_transform, visibility)| {
+                is_visible_to(visibility, team)
+                    && transform.translation.distance(target_transform.translation) < aggro.range
+            })
+    });
+
+    if locked {
+        return;
+    }
+
+    debug!(
+        "{} initiating attack on aggro target {}",
+        entity, trigger.target
+    );
+
+    turret_target.target = Some(trigger.target);
+    turret_target.heat = 0;
+
+    commands.trigger(CommandAttackAutoStart {
+        entity,
+        target: trigger.target,
+    });
+}
+
+/// Turrets switch to an enemy champion that damages an allied champion in range
+fn on_event_damage_call_for_help(
+    trigger: On<EventDamageCreate>,
+    mut commands: Commands,
+    mut q_turret: Query<(Entity, &Team, &Transform, &Aggro, &mut TurretTarget)>,
+    q_champion: Query<
+        (&Team, &Transform, Option<&TeamVisibility>),
+        (With<Champion>, Without<Dead>),
+    >,
+) {
+    let source = trigger.source;
+    let target = trigger.event_target();
+
+    if source == target {
+        return;
+    }
+
+    let Ok((source_team, source_transform, source_visibility)) = q_champion.get(source) else {
+        return;
+    };
+
+    let Ok((target_team, target_transform, _)) = q_champion.get(target) else {
+        return;
+    };
+
+    if source_team == target_team {
+        return;
+    }
+
+    for (entity, team, transform, aggro, mut turret_target) in q_turret.iter_mut() {
+        if team != target_team || turret_target.target == Some(source) {
+            continue;
+        }
+
+        if !is_visible_to(source_visibility, team) {
+            continue;
+        }
+
+        let in_range = |position: Vec3| transform.translation.distance(position) < aggro.range;
+        if !in_range(source_transform.translation) || !in_range(target_transform.translation) {
+            continue;
+        }
+
+        debug!("{} called for help against {}", entity, source);
+
+        turret_target.target = Some(source);
+        turret_target.heat = 0;
 
         commands.trigger(CommandAttackAutoStart {
             entity,
-            target: trigger.target,
+            target: source,
         });
     }
 }
+
+/// A shot on a new target resets the warm-up ramp
+fn on_event_attack_start(
+    trigger: On<EventAttackStart>,
+    mut q_turret: Query<&mut TurretTarget, With<Turret>>,
+) {
+    let Ok(mut turret_target) = q_turret.get_mut(trigger.event_target()) else {
+        return;
+    };
+
+    if turret_target.target != Some(trigger.target) {
+        turret_target.target = Some(trigger.target);
+        turret_target.heat = 0;
+    }
+}
+
+/// Turret shots deal a share of a minion's max health as true damage and ramp up on the
+/// locked champion
+fn damage_turret_shot(world: &mut DeferredWorld, context: &mut DamageContext) {
+    if context.origin != DamageOrigin::Attack
+        || context.hit_counted
+        || world.get::<Turret>(context.source).is_none()
+    {
+        return;
+    }
+
+    if let Some(minion) = world.get::<Minion>(context.target) {
+        context.damage_type = DamageType::True;
+        context.amount = context.max_health * turret_minion_damage_percent(minion);
+        return;
+    }
+
+    let is_champion = world.get::<Champion>(context.target).is_some();
+    let Some(mut turret_target) = world.get_mut::<TurretTarget>(context.source) else {
+        return;
+    };
+
+    // Shots still flying from before a retarget don't ramp
+    if !is_champion || turret_target.target != Some(context.target) {
+        return;
+    }
+
+    context.amount *= turret_warm_up_multiplier(turret_target.heat);
+    turret_target.heat += 1;
+}
+
+fn on_event_damage_create(
+    trigger: On<EventDamageCreate>,
+    mut q_plating: Query<(&Health, &mut TurretPlating)>,
+    mut q_gold: Query<&mut Gold>,
+) {
+    let entity = trigger.event_target();
+
+    let Ok((health, mut plating)) = q_plating.get_mut(entity) else {
+        return;
+    };
+
+    let health_per_plate = health.max / plating.max_plates as f32;
+    let remaining = (health.value.max(0.0) / health_per_plate).ceil() as u32;
+
+    if remaining >= plating.plates {
+        return;
+    }
+
+    let destroyed = plating.plates - remaining;
+    plating.plates = remaining;
+
+    debug!(
+        "{:?} lost {} plates, {} remaining",
+        entity, destroyed, plating.plates
+    );
+
+    if let Ok(mut gold) = q_gold.get_mut(trigger.source) {
+        gold.0 += plating.gold_per_plate * destroyed as f32;
+    }
+}
+
+fn fixed_update_plating(
+    mut commands: Commands,
+    q_plating: Query<Entity, With<TurretPlating>>,
+    game_time: Res<Time<Virtual>>,
+) {
+    if game_time.elapsed_secs() < TURRET_PLATING_END_TIME {
+        return;
+    }
+
+    for entity in q_plating.iter() {
+        debug!("{:?} plating fell off", entity);
+        commands.entity(entity).remove::<TurretPlating>();
+    }
+}
@@ -0,0 +1,256 @@
+use bevy::prelude::*;
+use lol_config::ConfigNavigationGrid;
+use lol_core::Team;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    Bounding, Gold, Health, HitCounted, Lifetime, ResourceGrid, SightRange, Stealth, TrueSight,
+};
+
+/// Maximum distance from the owner a ward can be placed at
+pub const WARD_CAST_RANGE: f32 = 600.0;
+
+/// Gold a control ward is bought for when the owner carries none
+pub const CONTROL_WARD_COST: f32 = 75.0;
+
+#[derive(Default)]
+pub struct PluginWard;
+
+impl Plugin for PluginWard {
+    fn build(&self, app: &mut App) {
+        app.add_observer(on_command_ward_place);
+
+        app.add_systems(FixedUpdate, fixed_update_trinket);
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum WardType {
+    /// Stealthed, expires after a while
+    Stealth,
+    /// Visible, reveals stealth wards, one per owner
+    Control,
+    /// Visible, long range placement with small sight radius
+    Farsight,
+}
+
+impl WardType {
+    /// Lifetime in seconds, 0.0 means the ward lasts until destroyed
+    pub fn duration(&self) -> f32 {
+        match self {
+            WardType::Stealth => 90.0,
+            WardType::Control | WardType::Farsight => 0.0,
+        }
+    }
+
+    /// Number of hits the ward takes to be destroyed
+    pub fn hits(&self) -> f32 {
+        match self {
+            WardType::Stealth => 3.0,
+            WardType::Control => 4.0,
+            WardType::Farsight => 1.0,
+        }
+    }
+
+    pub fn sight_range(&self) -> f32 {
+        match self {
+            WardType::Stealth | WardType::Control => 900.0,
+            WardType::Farsight => 500.0,
+        }
+    }
+
+    pub fn cast_range(&self) -> f32 {
+        match self {
+            WardType::Stealth | WardType::Control => WARD_CAST_RANGE,
+            WardType::Farsight => 4000.0,
+        }
+    }
+}
+
+#[derive(Component, Debug, Clone)]
+#[require(HitCounted)]
+pub struct Ward {
+    pub ward_type: WardType,
+    pub owner: Entity,
+}
+
+/// Trinket slot of a champion, wards of its type placed with `Action::PlaceWard` use its charges
+#[derive(Component, Debug, Clone)]
+pub struct Trinket {
+    pub ward_type: WardType,
+    pub charges: u32,
+    pub max_charges: u32,
+    pub recharge_timer: Timer,
+}
+
+impl Default for Trinket {
+    fn default() -> Self {
+        Self {
+            ward_type: WardType::Stealth,
+            charges: 1,
+            max_charges: 2,
+            recharge_timer: Timer::from_seconds(120.0, TimerMode::Repeating),
+        }
+    }
+}
+
+/// Control wards carried by a champion, placed with `Action::PlaceWard` and `WardType::Control`.
+/// Placing one without any left buys it for `CONTROL_WARD_COST` gold.
+#[derive(Component, Debug, Clone, Default)]
+pub struct ControlWardCharges(pub u32);
+
+/// Place a ward from the owner's trinket, or a control ward from its control ward charges
+#[derive(EntityEvent, Debug)]
+pub struct CommandWardPlace {
+    pub entity: Entity,
+    pub ward_type: WardType,
+    pub position: Vec2,
+}
+
+#[derive(EntityEvent, Debug)]
+pub struct EventWardPlaced {
+    pub entity: Entity,
+    pub ward: Entity,
+    pub ward_type: WardType,
+}
+
+fn on_command_ward_place(
+    trigger: On<CommandWardPlace>,
+    mut commands: Commands,
+    mut q_owner: Query<(
+        &Transform,
+        &Team,
+        Option<&mut Trinket>,
+        Option<&mut ControlWardCharges>,
+        Option<&mut Gold>,
+    )>,
+    q_ward: Query<(Entity, &Ward)>,
+    res_grid: Option<Res<ResourceGrid>>,
+    assets_grid: Option<Res<Assets<ConfigNavigationGrid>>>,
+) {
+    let entity = trigger.event_target();
+    let ward_type = trigger.ward_type;
+
+    let Ok((transform, team, trinket, mut control_wards, gold)) = q_owner.get_mut(entity) else {
+        return;
+    };
+
+    if ward_type == WardType::Control {
+        buy_control_ward(control_wards.as_deref_mut(), gold.map(|v| v.into_inner()));
+    }
+
+    let charges = match ward_type {
+        WardType::Control => control_wards.map(|v| &mut v.into_inner().0),
+        _ => trinket
+            .map(|v| v.into_inner())
+            .filter(|v| v.ward_type == ward_type)
+            .map(|v| &mut v.charges),
+    };
+    let Some(charges) = charges.filter(|v| **v > 0) else {
+        debug!("{:?} has no {:?} ward charges", entity, ward_type);
+        return;
+    };
+
+    let distance = transform.translation.xz().distance(trigger.position);
+    if distance > ward_type.cast_range() {
+        debug!(
+            "{:?} ward position out of cast range {:.1} > {:.1}",
+            entity,
+            distance,
+            ward_type.cast_range()
+        );
+        return;
+    }
+
+    let grid = match (&res_grid, &assets_grid) {
+        (Some(res_grid), Some(assets_grid)) => assets_grid.get(&res_grid.0),
+        _ => None,
+    };
+    if grid.is_some_and(|grid| {
+        !grid.is_walkable_by_xy(grid.get_cell_xy_by_position(&trigger.position), 0.0)
+    }) {
+        debug!(
+            "{:?} ward position {} is not walkable",
+            entity, trigger.position
+        );
+        return;
+    }
+
+    *charges -= 1;
+
+    // Only one control ward per owner can be on the map
+    if ward_type == WardType::Control {
+        for (ward_entity, ward) in q_ward.iter() {
+            if ward.owner == entity && ward.ward_type == WardType::Control {
+                commands.entity(ward_entity).despawn();
+            }
+        }
+    }
+
+    let ward = commands
+        .spawn((
+            Ward {
+                ward_type,
+                owner: entity,
+            },
+            team.clone(),
+            Transform::from_xyz(
+                trigger.position.x,
+                transform.translation.y,
+                trigger.position.y,
+            ),
+            Health::new(ward_type.hits()),
+            SightRange(ward_type.sight_range()),
+            Lifetime::new_timer(ward_type.duration()),
+            Bounding {
+                radius: 30.0,
+                height: 100.0,
+            },
+        ))
+        .id();
+
+    match ward_type {
+        WardType::Stealth => {
+            commands.entity(ward).insert(Stealth);
+        }
+        WardType::Control => {
+            commands.entity(ward).insert(TrueSight);
+        }
+        WardType::Farsight => {}
+    }
+
+    debug!("{:?} placed {:?} ward {:?}", entity, ward_type, ward);
+
+    commands.trigger(EventWardPlaced {
+        entity,
+        ward,
+        ward_type,
+    });
+}
+
+fn buy_control_ward(control_wards: Option<&mut ControlWardCharges>, gold: Option<&mut Gold>) {
+    let (Some(control_wards), Some(gold)) = (control_wards, gold) else {
+        return;
+    };
+    if control_wards.0 > 0 || gold.0 < CONTROL_WARD_COST {
+        return;
+    }
+
+    gold.0 -= CONTROL_WARD_COST;
+    control_wards.0 += 1;
+}
+
+fn fixed_update_trinket(mut q_trinket: Query<&mut Trinket>, time: Res<Time<Fixed>>) {
+    for mut trinket in q_trinket.iter_mut() {
+        if trinket.charges >= trinket.max_charges {
+            trinket.recharge_timer.reset();
+            continue;
+        }
+
+        trinket.recharge_timer.tick(time.delta());
+
+        if trinket.recharge_timer.just_finished() {
+            trinket.charges += 1;
+        }
+    }
+}
@@ -15,20 +15,27 @@ pub use ui::*;
 plugin_group! {
     pub struct PluginCore {
         :PluginDamageReduction,
-        :PluginFioraPassive,
+        :PluginEpicMonster,
         :PluginFioraE,
         :PluginFioraR,
+        :PluginGrievousWounds,
+        :PluginResistReduction,
         :PluginRivenPassive,
-        :PluginRivenQ,
         :PluginShieldWhite,
         :PluginShieldMagic,
 
         :PluginBarrack,
+        :PluginBot,
         :PluginChampion,
         :PluginCharacter,
         :PluginDebugSphere,
+        :PluginInhibitor,
+        :PluginJungle,
         :PluginMinion,
+        :PluginNexus,
+        :PluginStructure,
         :PluginTurret,
+        :PluginWard,
 
         :PluginFiora,
         :PluginHwei,
@@ -40,11 +47,15 @@ plugin_group! {
         :PluginAttackAuto,
         :PluginAggro,
         :PluginBase,
+        :PluginBuff,
         :PluginCamera,
+        :PluginCombatLog,
         :PluginController,
         :PluginCooldown,
         :PluginDamage,
+        :PluginDeath,
         :PluginGame,
+        :PluginHeal,
         :PluginLife,
         :PluginLifetime,
         :PluginMap,
@@ -52,12 +63,18 @@ plugin_group! {
         :PluginMovement,
         :PluginNavigaton,
         :PluginParticle,
+        :PluginRegen,
         :PluginResource,
         :PluginRotate,
         :PluginRun,
         :PluginSkill,
+        :PluginSkillCast,
+        :PluginSkillRecast,
+        :PluginSkillEffect,
+        :PluginScript,
         :PluginSkin,
         :PluginState,
         :PluginUI,
+        :PluginVision,
     }
 }
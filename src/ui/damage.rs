@@ -1,7 +1,7 @@
-use bevy::color::palettes::css::{BLUE, RED, WHITE};
+use bevy::color::palettes::css::{BLUE, LIME, RED, WHITE};
 use bevy::prelude::*;
 
-use crate::{DamageType, EventDamageCreate};
+use crate::{DamageType, EventDamageCreate, EventHeal};
 
 #[derive(Default)]
 pub struct PluginUIDamage;
@@ -10,6 +10,7 @@ impl Plugin for PluginUIDamage {
     fn build(&self, app: &mut App) {
         app.add_systems(Update, update_damage_numbers);
         app.add_observer(on_event_damage_create);
+        app.add_observer(on_event_heal);
     }
 }
 
@@ -81,6 +82,46 @@ fn on_event_damage_create(
     ));
 }
 
+/// Listen for heal events and create green heal numbers
+fn on_event_heal(
+    trigger: On<EventHeal>,
+    mut commands: Commands,
+    global_transform: Query<&GlobalTransform>,
+) {
+    let heal_result = &trigger.heal_result;
+
+    // Only display health actually restored
+    if heal_result.final_heal < 1.0 {
+        return;
+    }
+
+    let Ok(target_transform) = global_transform.get(trigger.event_target()) else {
+        return;
+    };
+
+    commands.spawn((
+        Text::new(format!("+{:.0}", heal_result.final_heal)),
+        TextFont {
+            font_size: 24.0,
+            ..default()
+        },
+        TextColor(Color::Srgba(LIME)),
+        Node {
+            position_type: PositionType::Absolute,
+            ..default()
+        },
+        DamageNumber {
+            damage: heal_result.final_heal,
+            lifetime: 0.0,
+            max_lifetime: 1.0,
+            start_position: target_transform.translation(),
+            velocity_y: 250.0,
+            gravity: -200.0,
+            final_scale: 0.5,
+        },
+    ));
+}
+
 /// Update damage number animation effects
 fn update_damage_numbers(
     mut commands: Commands,
@@ -3,8 +3,8 @@ use league_core::SkinCharacterDataProperties;
 use lol_config::LoadHashKeyTrait;
 
 use crate::{
-    AbilityResource, AssetServerLoadLeague, CommandUpdateUIElement, Controller, Health, Level,
-    NodeType, SizeType, Skin, UIElementEntity, UIState,
+    AbilityResource, AbilityResourceType, AssetServerLoadLeague, CommandUpdateUIElement,
+    Controller, Health, Level, NodeType, SizeType, Skin, UIElementEntity, UIState,
 };
 
 #[derive(Component, Reflect, Default)]
@@ -148,6 +148,9 @@ fn update_player_health_fade(
 
 fn update_player_ability_resource(
     mut commands: Commands,
+    mut bar_type: Local<Option<AbilityResourceType>>,
+    mut q_image_node: Query<&mut ImageNode>,
+    q_children: Query<&Children>,
     res_ui_element_entity: Res<UIElementEntity>,
     q_ability_resource: Query<&AbilityResource, With<Controller>>,
 ) {
@@ -162,6 +165,19 @@ fn update_player_ability_resource(
 
     commands.entity(entity).insert(Visibility::Visible);
 
+    // The bar texture is blue, other resources draw a plain bar in their own colour
+    if *bar_type != Some(ability_resource.ar_type) {
+        let child = q_children.get(entity).ok().and_then(|v| v.first().copied());
+        if let Some(mut image_node) = child.and_then(|v| q_image_node.get_mut(v).ok()) {
+            if ability_resource.ar_type != AbilityResourceType::Mana {
+                image_node.image = Handle::default();
+                image_node.rect = None;
+                image_node.color = ability_resource.ar_type.bar_color();
+            }
+            *bar_type = Some(ability_resource.ar_type);
+        }
+    }
+
     commands.trigger(CommandUpdateUIElement {
         entity,
         size_type: SizeType::Width,
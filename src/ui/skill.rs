@@ -3,8 +3,8 @@ use league_core::SpellObject;
 use lol_config::LoadHashKeyTrait;
 
 use crate::{
-    CommandDespawnButton, CommandSkillLevelUp, CommandSpawnButton, Controller, Level, PassiveSkill,
-    ResourceCache, Skill, SkillPoints, Skills, UIElementEntity, UIState,
+    CommandDespawnButton, CommandSkillLevelUp, CommandSpawnButton, Controller, CoolDown, Level,
+    PassiveSkill, ResourceCache, Skill, SkillPoints, SkillRecast, Skills, UIElementEntity, UIState,
 };
 
 #[derive(Default)]
@@ -13,11 +13,14 @@ pub struct PluginUISkill;
 impl Plugin for PluginUISkill {
     fn build(&self, app: &mut App) {
         app.init_resource::<SkillLevelUpButton>();
+        app.init_resource::<SkillCooldownSweep>();
         app.add_systems(
             Update,
             (
                 update_skill_level_up_button.run_if(in_state(UIState::Loaded)),
                 update_player_skill_icon.run_if(in_state(UIState::Loaded).and(run_once)),
+                update_player_skill_recast_icon.run_if(in_state(UIState::Loaded)),
+                update_player_skill_cooldown_sweep.run_if(in_state(UIState::Loaded)),
             ),
         );
     }
@@ -28,6 +31,21 @@ struct SkillLevelUpButton {
     pub entities: [Option<Entity>; 4],
 }
 
+/// Dark overlays on the ability icons, shrinking as the cooldown runs out
+#[derive(Resource, Default)]
+struct SkillCooldownSweep {
+    pub entities: [Option<Entity>; 4],
+}
+
+/// Icon element of the passive (index 0) or of an ability (index 1..=4)
+fn skill_icon_key(index: usize) -> String {
+    if index == 0 {
+        "ClientStates/Gameplay/UX/LoL/PlayerFrame/UIBase/Player_Frame_Root/PlayerSpells/Passive/Passive_IconLoc".to_string()
+    } else {
+        format!("ClientStates/Gameplay/UX/LoL/PlayerFrame/UIBase/Player_Frame_Root/PlayerSpells/Ability{0}/Ability{0}_IconLoc", index - 1)
+    }
+}
+
 fn update_player_skill_icon(
     asset_server: Res<AssetServer>,
     mut commands: Commands,
@@ -51,11 +69,7 @@ fn update_player_skill_icon(
     };
 
     for (index, skill) in passive_skill.iter().chain(skills.iter()).enumerate() {
-        let key = if index == 0 {
-            "ClientStates/Gameplay/UX/LoL/PlayerFrame/UIBase/Player_Frame_Root/PlayerSpells/Passive/Passive_IconLoc".to_string()
-        } else {
-            format!("ClientStates/Gameplay/UX/LoL/PlayerFrame/UIBase/Player_Frame_Root/PlayerSpells/Ability{0}/Ability{0}_IconLoc", index - 1)
-        };
+        let key = skill_icon_key(index);
 
         let Some(&entity) = res_ui_element_entity.get_by_string(&key) else {
             debug!("Skill icon UI element not found {}", key);
@@ -100,6 +114,108 @@ fn update_player_skill_icon(
     }
 }
 
+/// Show the icon of the next stage while a recast is pending
+fn update_player_skill_recast_icon(
+    asset_server: Res<AssetServer>,
+    mut q_image_node: Query<&mut ImageNode>,
+    mut res_resource_cache: ResMut<ResourceCache>,
+    q_children: Query<&Children>,
+    q_skill: Query<(&Skill, &SkillRecast), Changed<SkillRecast>>,
+    q_skills: Query<&Skills, With<Controller>>,
+    res_assets_spell_object: Res<Assets<SpellObject>>,
+    res_ui_element_entity: Res<UIElementEntity>,
+) {
+    let Ok(skills) = q_skills.single() else {
+        return;
+    };
+
+    for (index, skill) in skills.iter().enumerate() {
+        let Ok((skill, recast)) = q_skill.get(skill) else {
+            continue;
+        };
+
+        let Some(icon_name) = res_assets_spell_object
+            .load_hash(skill.key_spell_object)
+            .and_then(|v| v.m_spell.as_ref())
+            .and_then(|v| v.m_img_icon_name.as_ref())
+            .and_then(|v| v.get(recast.stage).or(v.first()))
+        else {
+            continue;
+        };
+
+        let Some(&entity) = res_ui_element_entity.get_by_string(&skill_icon_key(index + 1)) else {
+            continue;
+        };
+        let Some(&child) = q_children.get(entity).ok().and_then(|v| v.first()) else {
+            continue;
+        };
+        let Ok(mut image_node) = q_image_node.get_mut(child) else {
+            continue;
+        };
+
+        let image = res_resource_cache.get_image(&asset_server, icon_name);
+        if image_node.image != image {
+            image_node.image = image;
+        }
+    }
+}
+
+fn update_player_skill_cooldown_sweep(
+    mut commands: Commands,
+    mut res_skill_cooldown_sweep: ResMut<SkillCooldownSweep>,
+    mut q_node: Query<&mut Node>,
+    q_cooldown: Query<&CoolDown>,
+    q_skills: Query<&Skills, With<Controller>>,
+    res_ui_element_entity: Res<UIElementEntity>,
+) {
+    let Ok(skills) = q_skills.single() else {
+        return;
+    };
+
+    for (index, skill) in skills.iter().enumerate().take(4) {
+        let Ok(cooldown) = q_cooldown.get(skill) else {
+            continue;
+        };
+        let height = Val::Percent(cooldown.remaining_fraction() * 100.0);
+
+        let Some(sweep) = res_skill_cooldown_sweep.entities[index] else {
+            let Some(&icon) = res_ui_element_entity.get_by_string(&skill_icon_key(index + 1))
+            else {
+                continue;
+            };
+            res_skill_cooldown_sweep.entities[index] =
+                Some(spawn_cooldown_sweep(&mut commands, icon, height));
+            continue;
+        };
+
+        let Ok(mut node) = q_node.get_mut(sweep) else {
+            continue;
+        };
+        if node.height != height {
+            node.height = height;
+        }
+    }
+}
+
+/// Dark overlay over a skill icon, its height follows the remaining cooldown
+fn spawn_cooldown_sweep(commands: &mut Commands, icon: Entity, height: Val) -> Entity {
+    commands
+        .spawn((
+            Node {
+                position_type: PositionType::Absolute,
+                left: Val::Px(0.0),
+                bottom: Val::Px(0.0),
+                width: Val::Percent(100.0),
+                height,
+                ..default()
+            },
+            BackgroundColor(Color::srgba(0.0, 0.0, 0.0, 0.6)),
+            Pickable::IGNORE,
+            ChildOf(icon),
+        ))
+        .id()
+}
+
 fn update_skill_level_up_button(
     mut commands: Commands,
     q_skill_points: Query<(Entity, &Level, &SkillPoints, &Skills), With<Controller>>,
@@ -139,7 +255,10 @@ fn update_skill_level_up_button(
                 continue;
             }
 
-            debug!("Spawning skill upgrade button entity {} index {}", entity, index);
+            debug!(
+                "Spawning skill upgrade button entity {} index {}",
+                entity, index
+            );
             let entity_button = commands
                 .spawn_empty()
                 .observe(move |_event: On<Pointer<Click>>, mut commands: Commands| {
@@ -153,7 +272,10 @@ fn update_skill_level_up_button(
             });
         } else {
             if let Some(entity_button) = res_skill_level_up_button.entities[index] {
-                debug!("Destroying skill upgrade button entity {} index {}", entity, index);
+                debug!(
+                    "Destroying skill upgrade button entity {} index {}",
+                    entity, index
+                );
                 res_skill_level_up_button.entities[index] = None;
                 commands.trigger(CommandDespawnButton {
                     entity: entity_button,